@@ -1,6 +1,33 @@
+use crate::ALLOW_WEAK_PASSWORD_FLAG;
+use account_utils::{
+    eth2_keystore::json_keystore::Kdf,
+    is_password_sufficiently_complex, read_password_from_user,
+    validator_definitions::{DefinitionsLock, LOCKFILE_NAME},
+    ZeroizeString,
+};
 use clap::ArgMatches;
+use environment::Environment;
 use std::fs::create_dir_all;
 use std::path::{Path, PathBuf};
+use types::{EthSpec, Hash256};
+
+pub const PASSWORD_CONFIRM_PROMPT: &str = "Enter the password again to confirm:";
+
+/// Takes the `DefinitionsLock` on `validator_dir`, which must be held whilst its validator
+/// definitions are read, modified and saved.
+pub fn lock_validator_dir(validator_dir: &Path) -> Result<DefinitionsLock, String> {
+    let path = validator_dir.join(LOCKFILE_NAME);
+    DefinitionsLock::try_acquire(validator_dir)
+        .map_err(|e| format!("Unable to lock {:?}: {:?}", path, e))?
+        .ok_or_else(|| {
+            format!(
+                "The validator directory is locked by a running validator client or another \
+                 account manager command holding {:?}. Stop the validator client or wait for the \
+                 command to finish, then try again.",
+                path
+            )
+        })
+}
 
 pub fn ensure_dir_exists<P: AsRef<Path>>(path: P) -> Result<(), String> {
     let path = path.as_ref();
@@ -19,3 +46,75 @@ pub fn base_wallet_dir(matches: &ArgMatches, arg: &'static str) -> Result<PathBu
         PathBuf::new().join(".lighthouse").join("wallets"),
     )
 }
+
+/// Returns the genesis validators root of the network given by `--testnet` or `--testnet-dir`,
+/// which is required to import or export slashing protection data.
+pub fn genesis_validators_root<T: EthSpec>(env: &Environment<T>) -> Result<Hash256, String> {
+    env.testnet
+        .as_ref()
+        .and_then(|testnet| testnet.genesis_state.as_ref())
+        .map(|genesis_state| genesis_state.genesis_validators_root)
+        .ok_or_else(|| {
+            "Unable to determine the genesis validators root, please specify a --testnet or \
+             --testnet-dir which includes the genesis state"
+                .to_string()
+        })
+}
+
+/// Prompts the user for a new keystore password, requiring it to be entered twice.
+///
+/// Unless `allow_weak_password == true`, the user is prompted again if the password fails
+/// `account_utils::is_password_sufficiently_complex`.
+///
+/// When reading from stdin there is nobody to re-prompt, so an error is returned instead.
+pub fn read_new_password(
+    prompt: &str,
+    use_stdin: bool,
+    allow_weak_password: bool,
+) -> Result<ZeroizeString, String> {
+    loop {
+        eprintln!("");
+        eprintln!("{}", prompt);
+
+        let password = read_password_from_user(use_stdin)?;
+
+        let result = if password.as_ref().is_empty() {
+            Err("Password cannot be empty.".to_string())
+        } else if allow_weak_password {
+            Ok(())
+        } else {
+            is_password_sufficiently_complex(password.as_ref()).map_err(|e| {
+                format!(
+                    "{} Use --{} to skip this check.",
+                    e, ALLOW_WEAK_PASSWORD_FLAG
+                )
+            })
+        }
+        .and_then(|()| {
+            eprintln!("");
+            eprintln!("{}", PASSWORD_CONFIRM_PROMPT);
+
+            if read_password_from_user(use_stdin)? == password {
+                Ok(())
+            } else {
+                Err("Passwords do not match.".to_string())
+            }
+        });
+
+        match result {
+            Ok(()) => break Ok(password),
+            Err(e) if use_stdin => break Err(e),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
+
+/// Returns a human-readable description of the key derivation function and its parameters.
+pub fn kdf_description(kdf: &Kdf) -> String {
+    match kdf {
+        Kdf::Scrypt(params) => {
+            format!("scrypt (n: {}, r: {}, p: {})", params.n, params.r, params.p)
+        }
+        Kdf::Pbkdf2(params) => format!("pbkdf2 (c: {})", params.c),
+    }
+}
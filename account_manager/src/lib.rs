@@ -5,12 +5,14 @@ pub mod wallet;
 use clap::App;
 use clap::ArgMatches;
 use environment::Environment;
+use std::fmt;
 use types::EthSpec;
 
 pub const CMD: &str = "account_manager";
 pub const SECRETS_DIR_FLAG: &str = "secrets-dir";
 pub const VALIDATOR_DIR_FLAG: &str = "validator-dir";
 pub const BASE_DIR_FLAG: &str = "base-dir";
+pub const ALLOW_WEAK_PASSWORD_FLAG: &str = "allow-weak-password";
 
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
     App::new(CMD)
@@ -20,16 +22,35 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .subcommand(validator::cli_app())
 }
 
+/// An error which stopped the account manager, with the code the process should exit with.
+#[derive(Debug)]
+pub struct ExitError {
+    pub message: String,
+    pub code: i32,
+}
+
+impl From<String> for ExitError {
+    fn from(message: String) -> Self {
+        Self { message, code: 1 }
+    }
+}
+
+impl fmt::Display for ExitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
 /// Run the account manager, returning an error if the operation did not succeed.
-pub fn run<T: EthSpec>(matches: &ArgMatches<'_>, env: Environment<T>) -> Result<(), String> {
+///
+/// The process is not exited here, so that the caller can drop the `Environment` (flushing the
+/// logs) before exiting with the `ExitError::code`.
+pub fn run<T: EthSpec>(matches: &ArgMatches<'_>, env: Environment<T>) -> Result<(), ExitError> {
     match matches.subcommand() {
         (wallet::CMD, Some(matches)) => wallet::cli_run(matches)?,
         (validator::CMD, Some(matches)) => validator::cli_run(matches, env)?,
         (unknown, _) => {
-            return Err(format!(
-                "{} is not a valid {} command. See --help.",
-                unknown, CMD
-            ));
+            return Err(format!("{} is not a valid {} command. See --help.", unknown, CMD).into());
         }
     }
 
@@ -0,0 +1,95 @@
+//! The `enable` and `disable` commands, which differ only in the value of the `enabled` flag they
+//! set.
+
+use crate::{common::lock_validator_dir, VALIDATOR_DIR_FLAG};
+use account_utils::validator_definitions::{ValidatorDefinitions, CONFIG_FILENAME};
+use clap::{App, Arg, ArgMatches};
+use std::path::PathBuf;
+
+pub const ENABLE_CMD: &str = "enable";
+pub const DISABLE_CMD: &str = "disable";
+pub const PUBKEY_FLAG: &str = "pubkey";
+pub const ALL_FLAG: &str = "all";
+
+/// Returns the `enable` command if `enabled == true`, otherwise the `disable` command.
+pub fn cli_app<'a, 'b>(enabled: bool) -> App<'a, 'b> {
+    let (cmd, about) = if enabled {
+        (
+            ENABLE_CMD,
+            "Enables validators in the validator definitions file, so that the validator \
+            client signs with them when it next starts.",
+        )
+    } else {
+        (
+            DISABLE_CMD,
+            "Disables validators in the validator definitions file, so that the validator \
+            client does not sign with them when it next starts. The keystores are not removed.",
+        )
+    };
+
+    App::new(cmd)
+        .about(about)
+        .arg(
+            Arg::with_name(PUBKEY_FLAG)
+                .long(PUBKEY_FLAG)
+                .value_name("PUBKEY")
+                .help("The public key of the validator to modify.")
+                .takes_value(true)
+                .required_unless(ALL_FLAG),
+        )
+        .arg(
+            Arg::with_name(ALL_FLAG)
+                .long(ALL_FLAG)
+                .help("If present, modify every validator in the validator definitions file.")
+                .conflicts_with(PUBKEY_FLAG)
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_DIR_FLAG)
+                .long(VALIDATOR_DIR_FLAG)
+                .value_name("VALIDATOR_DIRECTORY")
+                .help(
+                    "The path to search for validator directories. \
+                    Defaults to ~/.lighthouse/validators",
+                )
+                .takes_value(true),
+        )
+}
+
+/// Sets `enabled` on the validators selected by `matches`.
+pub fn cli_run(matches: &ArgMatches, enabled: bool) -> Result<(), String> {
+    let validator_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        VALIDATOR_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("validators"),
+    )?;
+    let pubkey = matches.value_of(PUBKEY_FLAG);
+
+    let _lock = lock_validator_dir(&validator_dir)?;
+    let mut defs = ValidatorDefinitions::open(&validator_dir)
+        .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?;
+
+    let mut num_modified = 0;
+
+    let selected = defs
+        .select_mut(pubkey)
+        .map_err(|e| format!("Unable to select validators: {:?}", e))?;
+
+    for def in selected {
+        if def.enabled != enabled {
+            def.enabled = enabled;
+            num_modified += 1;
+        }
+    }
+
+    defs.save(&validator_dir)
+        .map_err(|e| format!("Unable to save {}: {:?}", CONFIG_FILENAME, e))?;
+
+    eprintln!(
+        "Successfully {} {} validators.",
+        if enabled { "enabled" } else { "disabled" },
+        num_modified
+    );
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use crate::{common::ensure_dir_exists, VALIDATOR_DIR_FLAG};
+use account_utils::validator_definitions::{
+    SigningDefinition, ValidatorDefinitions, CONFIG_FILENAME,
+};
+use clap::{App, Arg, ArgMatches};
+use std::fs;
+use std::path::PathBuf;
+
+pub const CMD: &str = "export";
+pub const DIR_FLAG: &str = "directory";
+pub const FORCE_FLAG: &str = "force";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Exports the EIP-2335 keystores of all validators in a Lighthouse VC directory, \
+            along with a validator definitions file which only contains those validators. \
+            The output directory may be imported on another machine with the import command. \
+            Private keys are never decrypted or exported, and neither are the keystore \
+            passwords, which must be supplied again when importing.",
+        )
+        .arg(
+            Arg::with_name(DIR_FLAG)
+                .long(DIR_FLAG)
+                .value_name("EXPORT_DIRECTORY")
+                .help("Path to the directory where the keystores will be exported.")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_DIR_FLAG)
+                .long(VALIDATOR_DIR_FLAG)
+                .value_name("VALIDATOR_DIRECTORY")
+                .help(
+                    "The path to search for validator directories. \
+                    Defaults to ~/.lighthouse/validators",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(FORCE_FLAG)
+                .long(FORCE_FLAG)
+                .help("If present, export even if the export directory is not empty."),
+        )
+}
+
+pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
+    let export_dir: PathBuf = clap_utils::parse_required(matches, DIR_FLAG)?;
+    let validator_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        VALIDATOR_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("validators"),
+    )?;
+    let force = matches.is_present(FORCE_FLAG);
+
+    let defs = ValidatorDefinitions::open(&validator_dir)
+        .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?;
+
+    ensure_dir_exists(&export_dir)?;
+
+    let is_empty = fs::read_dir(&export_dir)
+        .map_err(|e| format!("Unable to read --{} {:?}: {:?}", DIR_FLAG, export_dir, e))?
+        .next()
+        .is_none();
+    if !is_empty && !force {
+        return Err(format!(
+            "Refusing to export into non-empty directory {:?}. Use --{} to override.",
+            export_dir, FORCE_FLAG
+        ));
+    }
+
+    // For each validator:
+    //
+    // - Copy the keystore JSON into a directory named after the public key.
+    // - Add a definition which points to the copied keystore, relative to the export directory so
+    //   that it can be moved to another machine.
+    //
+    // Only the (encrypted) keystore file is copied, never the decrypted secret key. The password
+    // is not exported either, since anyone with the keystore and its password has the key.
+    let mut export_defs = ValidatorDefinitions::default();
+    for def in defs.as_slice() {
+        let mut export_def = def.clone();
+        export_def.relative_paths = true;
+
+        match &mut export_def.signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                voting_keystore_password_path,
+                voting_keystore_password,
+            } => {
+                *voting_keystore_password_path = None;
+                *voting_keystore_password = None;
+
+                let dest_dir = export_dir.join(def.voting_public_key.to_hex_string());
+                fs::create_dir_all(&dest_dir)
+                    .map_err(|e| format!("Unable to create export directory: {:?}", e))?;
+
+                // Retain the keystore file name, but place it in the new directory.
+                let dest_keystore = voting_keystore_path
+                    .file_name()
+                    .map(|file_name| dest_dir.join(file_name))
+                    .ok_or_else(|| {
+                        format!("Badly formatted file name: {:?}", voting_keystore_path)
+                    })?;
+
+                fs::copy(&voting_keystore_path, &dest_keystore).map_err(|e| {
+                    format!(
+                        "Unable to copy keystore {:?}: {:?}",
+                        voting_keystore_path, e
+                    )
+                })?;
+
+                eprintln!("Exported keystore to {:?}", dest_keystore);
+
+                *voting_keystore_path = dest_keystore;
+            }
+        }
+
+        export_defs.push(export_def);
+    }
+
+    export_defs
+        .save(&export_dir)
+        .map_err(|e| format!("Unable to save {}: {:?}", CONFIG_FILENAME, e))?;
+
+    eprintln!("");
+    eprintln!(
+        "Successfully exported {} validators to {:?}.",
+        export_defs.as_slice().len(),
+        export_dir
+    );
+
+    Ok(())
+}
@@ -0,0 +1,340 @@
+//! An encrypted bundle of validator secret keys, as exported by hardware signers which protect
+//! many keys with a single password.
+//!
+//! A bundle is a JSON object with the following fields:
+//!
+//! ```json
+//! {
+//!     "version": 1,
+//!     "crypto": { "kdf": { .. }, "checksum": { .. }, "cipher": { .. } },
+//!     "validators": [
+//!         { "pubkey": "0xa5e8..", "path": "m/12381/3600/0/0/0" },
+//!         { "pubkey": "0x87a5..", "path": "m/12381/3600/1/0/0" }
+//!     ]
+//! }
+//! ```
+//!
+//! - `crypto` is an EIP-2335 crypto module, which encrypts the concatenation of the 32-byte secret
+//!   keys of the `validators`, in order.
+//! - `path` is the EIP-2334 derivation path of the key, if known, and may be omitted.
+#![cfg(feature = "hardware_bundle")]
+
+use bls::{Keypair, PublicKey};
+use eth2_keystore::{
+    decrypt, encrypt,
+    json_keystore::{
+        Aes128Ctr, ChecksumModule, Cipher, CipherModule, Crypto, EmptyMap, EmptyString, Kdf,
+        KdfModule, Sha256Checksum,
+    },
+    keypair_from_secret, Error as KeystoreError, Keystore, KeystoreBuilder, PlainText, IV_SIZE,
+    SALT_SIZE,
+};
+use rand::prelude::*;
+use serde_derive::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// The only supported `version` of a bundle.
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// The length of each secret key in the plain text of a bundle.
+const SECRET_KEY_LEN: usize = 32;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The bundle file could not be read.
+    UnableToReadFile(io::Error),
+    /// The bundle could not be parsed or serialized as JSON.
+    InvalidJson(serde_json::Error),
+    /// The bundle has a version other than `BUNDLE_VERSION`.
+    UnsupportedVersion(u32),
+    /// The bundle could not be encrypted or decrypted (e.g., the password is incorrect).
+    Crypto(KeystoreError),
+    /// The plain text is not one secret key for each validator.
+    InvalidPlainTextLen { len: usize, expected: usize },
+    /// The secret key of the validator at `index` is invalid.
+    InvalidSecretKey { index: usize, error: KeystoreError },
+    /// The secret key of the validator at `index` does not match its public key.
+    PublicKeyMismatch { index: usize },
+    /// A keystore could not be created for a validator.
+    UnableToCreateKeystore(KeystoreError),
+}
+
+/// A validator in a `Bundle`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BundleValidator {
+    pub pubkey: PublicKey,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+/// An encrypted bundle of secret keys, see the module documentation for the format.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Bundle {
+    pub version: u32,
+    pub crypto: Crypto,
+    pub validators: Vec<BundleValidator>,
+}
+
+impl Bundle {
+    /// Encrypts the secret keys of `keypairs` with `password`, using the given `kdf`.
+    ///
+    /// The AES `IV` is generated using `rand::thread_rng()`.
+    pub fn encrypt(keypairs: &[Keypair], password: &[u8], kdf: Kdf) -> Result<Self, Error> {
+        let mut plain_text = PlainText::zero(keypairs.len() * SECRET_KEY_LEN);
+        for (keypair, chunk) in keypairs
+            .iter()
+            .zip(plain_text.as_mut_bytes().chunks_mut(SECRET_KEY_LEN))
+        {
+            chunk.copy_from_slice(keypair.sk.serialize().as_bytes());
+        }
+
+        let iv = rand::thread_rng().gen::<[u8; IV_SIZE]>().to_vec().into();
+        let cipher = Cipher::Aes128Ctr(Aes128Ctr { iv });
+        let (cipher_text, checksum) =
+            encrypt(plain_text.as_bytes(), password, &kdf, &cipher).map_err(Error::Crypto)?;
+
+        Ok(Self {
+            version: BUNDLE_VERSION,
+            crypto: Crypto {
+                kdf: KdfModule {
+                    function: kdf.function(),
+                    params: kdf,
+                    message: EmptyString,
+                },
+                checksum: ChecksumModule {
+                    function: Sha256Checksum::function(),
+                    params: EmptyMap,
+                    message: checksum.to_vec().into(),
+                },
+                cipher: CipherModule {
+                    function: cipher.function(),
+                    params: cipher,
+                    message: cipher_text.into(),
+                },
+            },
+            validators: keypairs
+                .iter()
+                .map(|keypair| BundleValidator {
+                    pubkey: keypair.pk.clone(),
+                    path: None,
+                })
+                .collect(),
+        })
+    }
+
+    /// Decrypts the bundle with `password`, returning the keypair of each validator in order.
+    ///
+    /// ## Errors
+    ///
+    /// - The password is incorrect.
+    /// - A secret key does not match the public key of its validator.
+    pub fn decrypt(&self, password: &[u8]) -> Result<Vec<Keypair>, Error> {
+        if self.version != BUNDLE_VERSION {
+            return Err(Error::UnsupportedVersion(self.version));
+        }
+
+        let plain_text = decrypt(password, &self.crypto).map_err(Error::Crypto)?;
+
+        let expected = self.validators.len() * SECRET_KEY_LEN;
+        if plain_text.len() != expected {
+            return Err(Error::InvalidPlainTextLen {
+                len: plain_text.len(),
+                expected,
+            });
+        }
+
+        plain_text
+            .as_bytes()
+            .chunks(SECRET_KEY_LEN)
+            .zip(self.validators.iter())
+            .enumerate()
+            .map(|(index, (secret, validator))| {
+                let keypair = keypair_from_secret(secret)
+                    .map_err(|error| Error::InvalidSecretKey { index, error })?;
+
+                if keypair.pk == validator.pubkey {
+                    Ok(keypair)
+                } else {
+                    Err(Error::PublicKeyMismatch { index })
+                }
+            })
+            .collect()
+    }
+
+    /// Decrypts the bundle with `password`, returning an EIP-2335 keystore for each validator,
+    /// encrypted with the same `password`.
+    ///
+    /// The keystores use the KDF of the bundle, with a new salt for each keystore.
+    pub fn decrypt_keystores(&self, password: &[u8]) -> Result<Vec<Keystore>, Error> {
+        self.decrypt(password)?
+            .iter()
+            .zip(self.validators.iter())
+            .map(|(keypair, validator)| {
+                let path = validator.path.clone().unwrap_or_default();
+                KeystoreBuilder::new(keypair, password, path)
+                    .map(|builder| builder.kdf(with_new_salt(&self.crypto.kdf.params)))
+                    .and_then(|builder| builder.build())
+                    .map_err(Error::UnableToCreateKeystore)
+            })
+            .collect()
+    }
+
+    pub fn from_json_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let file = File::open(path).map_err(Error::UnableToReadFile)?;
+        serde_json::from_reader(file).map_err(Error::InvalidJson)
+    }
+
+    pub fn to_json_writer<W: Write>(&self, writer: W) -> Result<(), Error> {
+        serde_json::to_writer_pretty(writer, self).map_err(Error::InvalidJson)
+    }
+}
+
+/// Returns a copy of `kdf` with a salt generated using `rand::thread_rng()`.
+fn with_new_salt(kdf: &Kdf) -> Kdf {
+    let salt = rand::thread_rng().gen::<[u8; SALT_SIZE]>().to_vec().into();
+
+    match kdf.clone() {
+        Kdf::Pbkdf2(mut params) => {
+            params.salt = salt;
+            Kdf::Pbkdf2(params)
+        }
+        Kdf::Scrypt(mut params) => {
+            params.salt = salt;
+            Kdf::Scrypt(params)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth2_keystore::{
+        json_keystore::{Pbkdf2, Prf},
+        DKLEN,
+    };
+
+    const PASSWORD: &[u8] = b"testpassword";
+
+    /// A bundle of the secret keys `1` and `2`, encrypted with `PASSWORD` and the `kdf()`.
+    const BUNDLE: &str = r#"{
+        "version": 1,
+        "crypto": {
+            "kdf": {
+                "function": "pbkdf2",
+                "params": {
+                    "c": 2,
+                    "dklen": 32,
+                    "prf": "hmac-sha256",
+                    "salt": "2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a"
+                },
+                "message": ""
+            },
+            "checksum": {
+                "function": "sha256",
+                "params": {},
+                "message": "44742259e2b7a76f7cf99dba0f0ece1f1f8724eeb45f9f28ed9ad4cbacc3ddc6"
+            },
+            "cipher": {
+                "function": "aes-128-ctr",
+                "params": {
+                    "iv": "9e2748b4cfa6ef96ab4c707b74103d24"
+                },
+                "message": "1f6152b18abfabdd0776a75ed9c69a17268ae038244da70360dccd5278523cef676a767dbad6d567c0e8dae58bd5597105e212e441ccd313c19974190eb94479"
+            }
+        },
+        "validators": [
+            {
+                "pubkey": "0x97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb",
+                "path": "m/12381/3600/0/0/0"
+            },
+            {
+                "pubkey": "0xa572cbea904d67468808c8eb50a9450c9721db309128012543902d0ac358a62ae28f75bb8f1c7c42c39a8c5529bf0f4e",
+                "path": "m/12381/3600/1/0/0"
+            }
+        ]
+    }"#;
+
+    fn kdf() -> Kdf {
+        Kdf::Pbkdf2(Pbkdf2 {
+            c: 2,
+            dklen: DKLEN,
+            prf: Prf::HmacSha256,
+            salt: vec![42; SALT_SIZE].into(),
+        })
+    }
+
+    #[test]
+    fn round_trip() {
+        let keypairs = vec![Keypair::random(), Keypair::random()];
+        let bundle = Bundle::encrypt(&keypairs, PASSWORD, kdf()).unwrap();
+
+        let mut json = vec![];
+        bundle.to_json_writer(&mut json).unwrap();
+        let bundle: Bundle = serde_json::from_slice(&json).unwrap();
+
+        let decrypted = bundle.decrypt(PASSWORD).unwrap();
+        assert_eq!(
+            decrypted.iter().map(|kp| &kp.pk).collect::<Vec<_>>(),
+            keypairs.iter().map(|kp| &kp.pk).collect::<Vec<_>>()
+        );
+
+        let keystores = bundle.decrypt_keystores(PASSWORD).unwrap();
+        for (keystore, keypair) in keystores.iter().zip(keypairs.iter()) {
+            assert_eq!(keystore.decrypt_keypair(PASSWORD).unwrap().pk, keypair.pk);
+            assert_eq!(keystore.kdf().function(), kdf().function());
+            assert_ne!(keystore.kdf(), &kdf(), "the salt should not be reused");
+        }
+
+        assert!(matches!(
+            bundle.decrypt(b"wrongpassword"),
+            Err(Error::Crypto(KeystoreError::InvalidPassword))
+        ));
+    }
+
+    #[test]
+    fn fixture() {
+        let bundle: Bundle = serde_json::from_str(BUNDLE).unwrap();
+
+        let keypairs = bundle.decrypt(PASSWORD).unwrap();
+        for (i, keypair) in keypairs.iter().enumerate() {
+            let mut secret = [0; SECRET_KEY_LEN];
+            secret[SECRET_KEY_LEN - 1] = i as u8 + 1;
+            assert_eq!(keypair.sk.serialize().as_bytes(), &secret[..]);
+        }
+
+        let keystores = bundle.decrypt_keystores(PASSWORD).unwrap();
+        assert_eq!(keystores[0].path(), "m/12381/3600/0/0/0");
+        assert_eq!(keystores[1].path(), "m/12381/3600/1/0/0");
+
+        let mut json = vec![];
+        bundle.to_json_writer(&mut json).unwrap();
+        assert_eq!(serde_json::from_slice::<Bundle>(&json).unwrap(), bundle);
+    }
+
+    #[test]
+    fn public_key_mismatch() {
+        let keypairs = vec![Keypair::random(), Keypair::random()];
+        let mut bundle = Bundle::encrypt(&keypairs, PASSWORD, kdf()).unwrap();
+        bundle.validators[1].pubkey = Keypair::random().pk;
+
+        assert!(matches!(
+            bundle.decrypt(PASSWORD),
+            Err(Error::PublicKeyMismatch { index: 1 })
+        ));
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let mut bundle = Bundle::encrypt(&[Keypair::random()], PASSWORD, kdf()).unwrap();
+        bundle.version = 2;
+
+        assert!(matches!(
+            bundle.decrypt(PASSWORD),
+            Err(Error::UnsupportedVersion(2))
+        ));
+    }
+}
@@ -1,24 +1,162 @@
-use crate::{common::ensure_dir_exists, VALIDATOR_DIR_FLAG};
+use super::manifest::Manifest;
+use crate::{
+    common::{ensure_dir_exists, genesis_validators_root, kdf_description, read_new_password},
+    ExitError, ALLOW_WEAK_PASSWORD_FLAG, VALIDATOR_DIR_FLAG,
+};
 use account_utils::{
-    eth2_keystore::Keystore,
-    read_password_from_user,
+    eth2_keystore::{json_keystore::Kdf, validate_kdf, Keystore, KeystoreBuilder},
+    normalize_pubkey, read_password_from_user,
     validator_definitions::{
-        recursively_find_voting_keystores, ValidatorDefinition, ValidatorDefinitions,
-        CONFIG_FILENAME,
+        self, is_voting_keystore, recursively_find_voting_keystores,
+        recursively_find_voting_keystores_matching, DefinitionsLock, ValidatorDefinition,
+        ValidatorDefinitions, CONFIG_FILENAME,
     },
+    PlainText, ZeroizeString,
 };
+use chrono::DateTime;
 use clap::{App, Arg, ArgMatches};
-use std::fs;
-use std::path::PathBuf;
+use environment::Environment;
+use rayon::prelude::*;
+use regex::Regex;
+use serde_derive::{Deserialize, Serialize};
+use slashing_protection::{
+    interchange::{Interchange, InterchangeData, InterchangeError},
+    SlashingDatabase, SLASHING_PROTECTION_FILENAME,
+};
+use slog::{debug, error, info, o, warn, Drain, Level, Logger};
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::thread::sleep;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, SystemTimeError, UNIX_EPOCH};
+use tempfile::TempDir;
+use types::{Address, EthSpec, Hash256, PublicKey, GRAFFITI_BYTES_LEN};
+use zip::ZipArchive;
 
 pub const CMD: &str = "import";
 pub const KEYSTORE_FLAG: &str = "keystore";
 pub const DIR_FLAG: &str = "directory";
 pub const STDIN_PASSWORD_FLAG: &str = "stdin-passwords";
+pub const PASSWORD_FILE_FLAG: &str = "password-file";
+pub const PASSWORD_ENV_FLAG: &str = "password-env";
+pub const DRY_RUN_FLAG: &str = "dry-run";
+pub const VERIFY_ONLY_FLAG: &str = "verify-only";
+pub const JSON_FLAG: &str = "json";
+pub const JOBS_FLAG: &str = "jobs";
+pub const REENCRYPT_FLAG: &str = "reencrypt";
+pub const STDIN_PATHS_FLAG: &str = "stdin-paths";
+pub const NO_STORE_PASSWORD_FLAG: &str = "no-store-password";
+pub const FILENAME_PATTERN_FLAG: &str = "filename-pattern";
+pub const FOLLOW_SYMLINKS_FLAG: &str = "follow-symlinks";
+pub const SINCE_FLAG: &str = "since";
+pub const DEPOSIT_DATA_FLAG: &str = "deposit-data";
+pub const LOG_LEVEL_FLAG: &str = "log-level";
+pub const IO_RETRIES_FLAG: &str = "io-retries";
+pub const SKIP_EXISTING_FLAG: &str = "skip-existing";
+pub const MIN_SCRYPT_N_FLAG: &str = "min-scrypt-n";
+pub const MIN_PBKDF2_C_FLAG: &str = "min-pbkdf2-c";
+pub const COUNT_FLAG: &str = "count";
+pub const COUNT_ONLY_FLAG: &str = "count-only";
+pub const ARCHIVE_FLAG: &str = "archive";
+pub const FORCE_FLAG: &str = "force";
+pub const STRICT_PERMS_FLAG: &str = "strict-perms";
+pub const GRAFFITI_FLAG: &str = "graffiti";
+pub const FEE_RECIPIENT_FLAG: &str = "suggested-fee-recipient";
+pub const SLASHING_PROTECTION_FLAG: &str = "slashing-protection";
+pub const NO_TTY_FLAG: &str = "no-tty";
+pub const ALL_FLAG: &str = "all";
+pub const KEEP_GOING_FLAG: &str = "keep-going";
+pub const BACKUP_DEFINITIONS_FLAG: &str = "backup-existing-definitions";
+pub const MAX_BACKUPS_FLAG: &str = "max-definitions-backups";
+pub const WALLET_NAME_FLAG: &str = "wallet-name";
+pub const NO_PROGRESS_FLAG: &str = "no-progress";
+pub const FORMAT_FLAG: &str = "format";
+pub const OUTPUT_PATH_FLAG: &str = "output-path";
+pub const PROMPT_TIMEOUT_FLAG: &str = "prompt-timeout";
+pub const PROMPT_DELAY_FLAG: &str = "prompt-delay-ms";
+pub const JOURNAL_FLAG: &str = "journal";
+pub const DEST_NAMING_FLAG: &str = "dest-naming";
+pub const MANIFEST_FLAG: &str = "manifest";
+pub const RELATIVE_PATHS_FLAG: &str = "relative-paths";
+pub const MAX_KEYSTORE_SIZE_FLAG: &str = "max-keystore-size";
+pub const MAX_PASSWORD_ATTEMPTS_FLAG: &str = "max-password-attempts";
+pub const NETWORK_FLAG: &str = "network";
+pub const STRICT_NETWORK_FLAG: &str = "strict-network";
+pub const IMPORT_DISABLED_FLAG: &str = "import-disabled";
+
+/// The `--format` of an EIP-2335 keystore.
+pub const FORMAT_LIGHTHOUSE: &str = "lighthouse";
+/// The `--format` of a `hardware_bundle::Bundle`, only available with the `hardware_bundle`
+/// feature.
+pub const FORMAT_HARDWARE_BUNDLE: &str = "hardware-bundle";
+
+#[cfg(feature = "hardware_bundle")]
+const FORMATS: &[&str] = &[FORMAT_LIGHTHOUSE, FORMAT_HARDWARE_BUNDLE];
+#[cfg(not(feature = "hardware_bundle"))]
+const FORMATS: &[&str] = &[FORMAT_LIGHTHOUSE];
+
+/// The `--dest-naming` which names each keystore directory `0x<pubkey>`.
+pub const DEST_NAMING_PUBKEY: &str = "pubkey";
+/// The `--dest-naming` which names each keystore directory with the keystore UUID.
+pub const DEST_NAMING_UUID: &str = "uuid";
+
+/// The values of `--network`, which are also the network names recognised in keystore
+/// descriptions.
+pub const NETWORKS: &[&str] = &[
+    "mainnet", "goerli", "altona", "medalla", "spadina", "zinken", "pyrmont",
+];
+
+/// The default `--max-keystore-size`, in bytes. An EIP-2335 keystore is typically less than 1 KiB.
+pub const DEFAULT_MAX_KEYSTORE_SIZE: &str = "65536";
+
+/// The exit code when `--keep-going` is supplied and some, but not all, keystores were imported.
+pub const PARTIAL_FAILURE_EXIT_CODE: i32 = 2;
+
+/// Backups of the validator definitions file are named with this prefix, followed by the time of
+/// the backup in nanoseconds since the UNIX epoch.
+pub const BACKUP_PREFIX: &str = "validator_definitions.yml.bak.";
+
+pub use account_utils::validator_definitions::LOCKFILE_NAME;
+
+/// A warning is logged for scrypt keystores with an `n` below this value. It is a quarter of the
+/// `n` used by Lighthouse and the `eth2.0-deposit-cli`.
+const LOW_SCRYPT_N: u32 = 1 << 16;
+/// A warning is logged for pbkdf2 keystores with a `c` below this value, which is the `c` used by
+/// the `eth2.0-deposit-cli`. An iteration of pbkdf2 is far cheaper than a unit of scrypt `n`, so
+/// the two costs are never compared.
+const LOW_PBKDF2_C: u32 = 1 << 18;
+
+/// The delay before the first retry of a filesystem operation which failed with a transient
+/// error. The delay is doubled for each subsequent retry.
+const IO_RETRY_INITIAL_DELAY: Duration = Duration::from_millis(100);
+
+/// The first line of an ASCII-armored GPG message, which is decrypted with `gpg`.
+const GPG_ARMOR_HEADER: &str = "-----BEGIN PGP MESSAGE-----";
+
+/// The first line of an age file, binary or ASCII-armored, which is decrypted with `age`.
+const AGE_HEADERS: &[&str] = &[
+    "age-encryption.org/v1",
+    "-----BEGIN AGE ENCRYPTED FILE-----",
+];
+
+/// The pause after a password is entered at a prompt, so that the result can be read before the
+/// next prompt.
+const PROMPT_DELAY: Duration = Duration::from_secs(1);
+
+pub use account_utils::validator_definitions::STAGING_DIR;
 
 pub const PASSWORD_PROMPT: &str = "Enter the keystore password, or press enter to omit it:";
+pub const BUNDLE_PASSWORD_PROMPT: &str = "Enter the bundle password:";
+pub const PASSWORD_FILE_PASSPHRASE_PROMPT: &str = "Enter the passphrase for the password file:";
+pub const REENCRYPT_PASSWORD_PROMPT: &str = "Enter a new password for the imported keystores:";
+pub const SELECTION_PROMPT: &str =
+    "Enter the numbers of the keystores to import, separated by commas, or 'all':";
+pub const OVERWRITE_PROMPT: &str = "Enter 'yes' to overwrite it, or anything else to abort:";
 pub const KEYSTORE_REUSE_WARNING: &str = "DO NOT USE THE ORIGINAL KEYSTORES TO VALIDATE WITH \
                                           ANOTHER CLIENT, OR YOU WILL GET SLASHED.";
 
@@ -36,7 +174,23 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .value_name("KEYSTORE_PATH")
                 .help("Path to a single keystore to be imported.")
                 .conflicts_with(DIR_FLAG)
-                .required_unless(DIR_FLAG)
+                .required_unless_one(&[DIR_FLAG, STDIN_PATHS_FLAG, ARCHIVE_FLAG])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(FORMAT_FLAG)
+                .long(FORMAT_FLAG)
+                .value_name("FORMAT")
+                .help(
+                    "The format of the --keystore. 'lighthouse' is an EIP-2335 keystore. When \
+                    built with the `hardware_bundle` feature, 'hardware-bundle' is an encrypted \
+                    bundle of keys exported by a hardware signer. The bundle is decrypted with \
+                    --password-env or a password prompt, and each key is imported as an \
+                    EIP-2335 keystore encrypted with the bundle password.",
+                )
+                .possible_values(FORMATS)
+                .default_value(FORMAT_LIGHTHOUSE)
+                .requires_if(FORMAT_HARDWARE_BUNDLE, KEYSTORE_FLAG)
                 .takes_value(true),
         )
         .arg(
@@ -50,7 +204,136 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                     has the '.json' extension will be attempted to be imported.",
                 )
                 .conflicts_with(KEYSTORE_FLAG)
-                .required_unless(KEYSTORE_FLAG)
+                .required_unless_one(&[KEYSTORE_FLAG, STDIN_PATHS_FLAG, ARCHIVE_FLAG])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(ARCHIVE_FLAG)
+                .long(ARCHIVE_FLAG)
+                .value_name("ZIP_PATH")
+                .help(
+                    "Path to a ZIP archive which contains zero or more keystores for import. \
+                    The keystores are found in the archive as for --directory and extracted \
+                    to a temporary directory, which is removed after the import.",
+                )
+                .conflicts_with_all(&[KEYSTORE_FLAG, DIR_FLAG, STDIN_PATHS_FLAG])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(NO_TTY_FLAG)
+                .long(NO_TTY_FLAG)
+                .help(
+                    "If present, do not ask which of the keystores found with --directory or \
+                    --archive should be imported, even if a terminal is available.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(ALL_FLAG)
+                .long(ALL_FLAG)
+                .help(
+                    "If present, import every keystore found with --directory or --archive, \
+                    without asking which should be imported.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(FILENAME_PATTERN_FLAG)
+                .long(FILENAME_PATTERN_FLAG)
+                .value_name("REGEX")
+                .help(
+                    "A regular expression which must match the entire file name of each \
+                    keystore found in --directory, instead of the default keystore file \
+                    names. E.g., 'validator-.*\\.json'.",
+                )
+                .requires(DIR_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(FOLLOW_SYMLINKS_FLAG)
+                .long(FOLLOW_SYMLINKS_FLAG)
+                .help(
+                    "If present, follow symbolic links to files and directories whilst \
+                    searching --directory. By default they are skipped, since they may point \
+                    outside of the directory.",
+                )
+                .requires(DIR_FLAG)
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(SINCE_FLAG)
+                .long(SINCE_FLAG)
+                .value_name("RFC3339")
+                .help(
+                    "If present, only import the keystores found in --directory which were \
+                    modified after this time, e.g. '2020-10-01T12:00:00Z'. Combined with \
+                    --skip-existing, allows incremental imports from a shared directory.",
+                )
+                .requires(DIR_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(DEPOSIT_DATA_FLAG)
+                .long(DEPOSIT_DATA_FLAG)
+                .value_name("DEPOSIT_DATA_FILE")
+                .help(
+                    "The path to a deposit_data.json file. If present, only the keystores found \
+                    in --directory for the public keys in DEPOSIT_DATA_FILE are imported.",
+                )
+                .requires(DIR_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(COUNT_FLAG)
+                .long(COUNT_FLAG)
+                .value_name("COUNT")
+                .help(
+                    "If present, import at most COUNT of the keystores found in --directory, \
+                    in order of their file names. Keystores skipped by --skip-existing do not \
+                    count, so repeating the import progresses through the directory in batches.",
+                )
+                .requires(DIR_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(COUNT_ONLY_FLAG)
+                .long(COUNT_ONLY_FLAG)
+                .help(
+                    "If present, report how many keystores would be considered for import from \
+                    --directory and exit, without decrypting or importing any of them or \
+                    prompting for passwords. With --json, the path and public key of each \
+                    keystore are also reported.",
+                )
+                .requires(DIR_FLAG)
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(STDIN_PATHS_FLAG)
+                .long(STDIN_PATHS_FLAG)
+                .help(
+                    "If present, read newline-separated keystore paths from stdin. Since \
+                    stdin is used for the paths, the passwords must be supplied with \
+                    --password-file or --password-env and will not be prompted for.",
+                )
+                .conflicts_with_all(&[
+                    KEYSTORE_FLAG,
+                    DIR_FLAG,
+                    STDIN_PASSWORD_FLAG,
+                    REENCRYPT_FLAG,
+                ])
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(PASSWORD_ENV_FLAG)
+                .long(PASSWORD_ENV_FLAG)
+                .value_name("VAR_NAME")
+                .help(
+                    "The name of an environment variable containing a password to try for \
+                    every keystore without a password in --password-file. The variable is \
+                    removed from the environment once it has been read. Any keystore which \
+                    cannot be decrypted with this password will be prompted for \
+                    interactively.",
+                )
                 .takes_value(true),
         )
         .arg(
@@ -63,152 +346,4122 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 )
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name(WALLET_NAME_FLAG)
+                .long(WALLET_NAME_FLAG)
+                .value_name("WALLET_NAME")
+                .help(
+                    "If present, place the imported keystores in the WALLET_NAME sub-directory \
+                    of the validator directory, instead of directly in the validator directory. \
+                    Useful for keeping the keystores of each key provider apart.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(OUTPUT_PATH_FLAG)
+                .long(OUTPUT_PATH_FLAG)
+                .value_name("DEFINITIONS_DIR")
+                .help(
+                    "If present, write the validator definitions file to DEFINITIONS_DIR instead \
+                    of the validator directory. The keystores are still placed in the validator \
+                    directory and are referred to by their absolute paths.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(RELATIVE_PATHS_FLAG)
+                .long(RELATIVE_PATHS_FLAG)
+                .help(
+                    "If present, refer to the imported keystores by paths relative to the \
+                    validator directory in the validator definitions file, so that the \
+                    directory can be moved or mounted elsewhere.",
+                )
+                .conflicts_with(OUTPUT_PATH_FLAG)
+                .takes_value(false),
+        )
         .arg(
             Arg::with_name(STDIN_PASSWORD_FLAG)
                 .long(STDIN_PASSWORD_FLAG)
-                .help("If present, read passwords from stdin instead of tty."),
+                .help(
+                    "If present, read passwords from stdin instead of tty. Each password is \
+                    one line, without its line ending; leading and trailing spaces are part \
+                    of the password.",
+                ),
+        )
+        .arg(
+            Arg::with_name(PASSWORD_FILE_FLAG)
+                .long(PASSWORD_FILE_FLAG)
+                .value_name("PASSWORD_FILE_PATH")
+                .help(
+                    "Path to a file which maps keystores to their passwords. The file may \
+                    either be a JSON object or contain one 'pubkey:password' pair per line. \
+                    Keystores may be identified by their public key or UUID. Any keystore \
+                    without a correct password in this file will be prompted for \
+                    interactively. In a JSON object, the value may also be an object with \
+                    optional 'password', 'graffiti' and 'suggested_fee_recipient' fields, \
+                    which take precedence over the corresponding flags. The file may be \
+                    encrypted with age or as an ASCII-armored GPG message, in which case it is \
+                    decrypted with the `age` or `gpg` command.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(DRY_RUN_FLAG)
+                .long(DRY_RUN_FLAG)
+                .help(
+                    "If present, validate the keystores and print which files would be \
+                    imported, without modifying the validator directory. Passwords are \
+                    not requested interactively.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(VERIFY_ONLY_FLAG)
+                .long(VERIFY_ONLY_FLAG)
+                .help(
+                    "If present, decrypt each keystore to check that its password is correct, \
+                    and log whether it passed, without modifying the validator directory. \
+                    Passwords missing from --password-file and --password-env are prompted \
+                    for. Exits with an error if any keystore fails.",
+                )
+                .conflicts_with_all(&[DRY_RUN_FLAG, JSON_FLAG, REENCRYPT_FLAG])
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(JSON_FLAG)
+                .long(JSON_FLAG)
+                .help(
+                    "If present, print a JSON summary of the imported keystores to stdout. \
+                    All other output, including password prompts, is printed to stderr.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(NO_PROGRESS_FLAG)
+                .long(NO_PROGRESS_FLAG)
+                .help(
+                    "If present, do not print a progress line to stderr before each keystore is \
+                    decrypted and imported. Progress is never printed with --json.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(JOURNAL_FLAG)
+                .long(JOURNAL_FLAG)
+                .value_name("JOURNAL_FILE")
+                .help(
+                    "If present, append the source path and public key of each keystore to \
+                    JOURNAL_FILE as soon as it is imported, and skip the keystores already \
+                    recorded in it. Allows an interrupted import to be resumed.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(MANIFEST_FLAG)
+                .long(MANIFEST_FLAG)
+                .value_name("MANIFEST_FILE")
+                .help(
+                    "If present, write the path and SHA-256 checksum of each imported keystore \
+                    and of the validator definitions file to MANIFEST_FILE after the import. \
+                    The files can later be checked with `validator verify --manifest`.",
+                )
+                .conflicts_with(DRY_RUN_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(DEST_NAMING_FLAG)
+                .long(DEST_NAMING_FLAG)
+                .value_name("NAMING")
+                .help(
+                    "The name of the directory each keystore is placed in. 'pubkey' names it \
+                    with the validator public key, 'uuid' with the keystore UUID.",
+                )
+                .possible_values(&[DEST_NAMING_PUBKEY, DEST_NAMING_UUID])
+                .default_value(DEST_NAMING_PUBKEY)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(PROMPT_TIMEOUT_FLAG)
+                .long(PROMPT_TIMEOUT_FLAG)
+                .value_name("SECONDS")
+                .help(
+                    "If present, a keystore fails to import if its password is not entered \
+                    within SECONDS of it being requested, instead of waiting indefinitely. The \
+                    import continues with the next keystore. Only supported on Unix.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(MAX_PASSWORD_ATTEMPTS_FLAG)
+                .long(MAX_PASSWORD_ATTEMPTS_FLAG)
+                .value_name("N")
+                .help(
+                    "If present, stop prompting for the password of a keystore after N incorrect \
+                    passwords. The import is aborted, or with --keep-going the keystore is \
+                    skipped. By default the password is requested until it is correct.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(PROMPT_DELAY_FLAG)
+                .long(PROMPT_DELAY_FLAG)
+                .value_name("MILLISECONDS")
+                .help(
+                    "The pause after a password is entered at a prompt. Defaults to 1000, or 0 \
+                    with --no-tty or --json.",
+                )
+                .hidden(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(JOBS_FLAG)
+                .long(JOBS_FLAG)
+                .value_name("JOBS")
+                .help(
+                    "The maximum number of threads used to decrypt keystores with passwords \
+                    from --password-file. Defaults to the number of CPUs.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(REENCRYPT_FLAG)
+                .long(REENCRYPT_FLAG)
+                .help(
+                    "If present, request a new password and re-encrypt each keystore with it, \
+                    instead of copying the original keystore. The password of each original \
+                    keystore is then required.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(NO_STORE_PASSWORD_FLAG)
+                .long(NO_STORE_PASSWORD_FLAG)
+                .help(
+                    "If present, the password of each keystore is required and checked, but \
+                    it is not stored in the validator definitions file. The passwords must \
+                    then be supplied by other means when the validator client starts.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(MIN_SCRYPT_N_FLAG)
+                .long(MIN_SCRYPT_N_FLAG)
+                .value_name("N")
+                .help(
+                    "If present, refuse to import any scrypt keystores with an `n` parameter \
+                    below N.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(MIN_PBKDF2_C_FLAG)
+                .long(MIN_PBKDF2_C_FLAG)
+                .value_name("C")
+                .help(
+                    "If present, refuse to import any pbkdf2 keystores with a `c` parameter \
+                    below C.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(SKIP_EXISTING_FLAG)
+                .long(SKIP_EXISTING_FLAG)
+                .help(
+                    "If present, skip any keystore which has already been imported into the \
+                    validator directory, instead of refusing to import any keystores. Useful \
+                    for resuming a partially completed import. Keystore files identical to a \
+                    previously imported file are always skipped.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(STRICT_PERMS_FLAG)
+                .long(STRICT_PERMS_FLAG)
+                .help(
+                    "If present, refuse to import any keystores if one of them is readable by \
+                    users other than its owner, instead of logging a warning. Only checked on \
+                    Unix.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(NETWORK_FLAG)
+                .long(NETWORK_FLAG)
+                .value_name("NETWORK")
+                .help(
+                    "The network of the validator directory. If present, a warning is logged \
+                    for each keystore whose description names a different network.",
+                )
+                .possible_values(NETWORKS)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STRICT_NETWORK_FLAG)
+                .long(STRICT_NETWORK_FLAG)
+                .help(
+                    "If present, refuse to import any keystores if the description of one of \
+                    them names a network other than --network, instead of logging a warning.",
+                )
+                .requires(NETWORK_FLAG)
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(IMPORT_DISABLED_FLAG)
+                .long(IMPORT_DISABLED_FLAG)
+                .help(
+                    "If present, the imported validators are written to the validator \
+                    definitions file with `enabled: false`, so that the validator client does \
+                    not sign with them until they are enabled with `lighthouse account \
+                    validator enable`.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(GRAFFITI_FLAG)
+                .long(GRAFFITI_FLAG)
+                .value_name("GRAFFITI")
+                .help(
+                    "The graffiti to store in the validator definition of each imported \
+                    keystore. At most 32 bytes.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(FEE_RECIPIENT_FLAG)
+                .long(FEE_RECIPIENT_FLAG)
+                .value_name("ADDRESS")
+                .help(
+                    "The 0x-prefixed address to store as the suggested fee recipient in the \
+                    validator definition of each imported keystore.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(SLASHING_PROTECTION_FLAG)
+                .long(SLASHING_PROTECTION_FLAG)
+                .value_name("FILE")
+                .help(
+                    "The path to an EIP-3076 slashing protection interchange file, containing \
+                    the signing history of the validators being imported. The history is added \
+                    to the slashing protection database in the validator directory, and any \
+                    imported validator which is not in the file is registered without any \
+                    history. The file must be for the network given by --testnet or \
+                    --testnet-dir.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(BACKUP_DEFINITIONS_FLAG)
+                .long(BACKUP_DEFINITIONS_FLAG)
+                .help(
+                    "If present, copy the existing validator definitions file to \
+                    validator_definitions.yml.bak.<unix time in nanoseconds> before it is \
+                    first modified.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(MAX_BACKUPS_FLAG)
+                .long(MAX_BACKUPS_FLAG)
+                .value_name("COUNT")
+                .help(
+                    "The number of validator definitions file backups to keep, including the \
+                    new one. Older backups are removed. Defaults to keeping every backup.",
+                )
+                .requires(BACKUP_DEFINITIONS_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(KEEP_GOING_FLAG)
+                .long(KEEP_GOING_FLAG)
+                .help(
+                    "If present, continue with the remaining keystores when one cannot be \
+                    imported, and report the failures at the end. The exit code is 0 if every \
+                    keystore was imported, 2 if only some were imported and 1 if none were.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(FORCE_FLAG)
+                .long(FORCE_FLAG)
+                .help(
+                    "If present, proceed even if the validator directory is locked by a running \
+                    validator client or another account manager command, and overwrite the \
+                    validator definitions file without asking for confirmation if another \
+                    process has modified it during the import.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(MAX_KEYSTORE_SIZE_FLAG)
+                .long(MAX_KEYSTORE_SIZE_FLAG)
+                .value_name("BYTES")
+                .help(
+                    "Keystores larger than BYTES are skipped with a warning, without being \
+                    parsed. Catches files which match the keystore file names but are not \
+                    keystores.",
+                )
+                .default_value(DEFAULT_MAX_KEYSTORE_SIZE)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(IO_RETRIES_FLAG)
+                .long(IO_RETRIES_FLAG)
+                .value_name("RETRIES")
+                .help(
+                    "The number of times to retry copying or removing a file which fails with \
+                    a transient error (e.g., on a network file system), with exponential \
+                    backoff between each attempt.",
+                )
+                .takes_value(true)
+                .default_value("3"),
+        )
+        .arg(
+            Arg::with_name(LOG_LEVEL_FLAG)
+                .long(LOG_LEVEL_FLAG)
+                .value_name("LEVEL")
+                .help(
+                    "The verbosity level for the logs emitted by the import. Password prompts \
+                    are always printed. Logs are also limited by --debug-level.",
+                )
+                .possible_values(&["info", "debug", "trace", "warn", "error", "crit"])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(ALLOW_WEAK_PASSWORD_FLAG)
+                .long(ALLOW_WEAK_PASSWORD_FLAG)
+                .help(
+                    "If present, do not check the strength of the new password given for --reencrypt.",
+                )
+                .takes_value(false),
         )
 }
 
-pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
+/// A summary of the keystores imported by `cli_run`, printed to stdout with `--json`.
+#[derive(Serialize)]
+pub struct ImportSummary {
+    pub validators: Vec<ImportedKeystore>,
+    pub count: usize,
+    /// The sum of `decrypt_time_ms` over the `validators`.
+    pub total_decrypt_time_ms: u64,
+}
+
+/// The keystores found by `cli_run` with `--count-only`, printed to stdout with `--json`.
+#[derive(Serialize)]
+pub struct DiscoveryReport {
+    pub count: usize,
+    pub keystores: Vec<DiscoveredKeystore>,
+}
+
+/// A keystore found with `--count-only`.
+#[derive(Serialize)]
+pub struct DiscoveredKeystore {
+    pub path: PathBuf,
+    /// The public key from the keystore file, or `None` if it could not be read.
+    pub pubkey: Option<String>,
+}
+
+/// A keystore which was (or, with `--dry-run`, would be) imported.
+#[derive(Serialize)]
+pub struct ImportedKeystore {
+    pub pubkey: String,
+    pub uuid: String,
+    pub source_path: PathBuf,
+    pub dest_path: PathBuf,
+    pub password_saved: bool,
+    /// `false` if the validator was imported with `--import-disabled`.
+    pub enabled: bool,
+    pub kdf: String,
+    pub kdf_cost: u32,
+    /// All of the parameters which determine the cost of the KDF, e.g. `scrypt (n: 262144, r: 8,
+    /// p: 1)`.
+    pub kdf_params: String,
+    /// The time taken to decrypt the keystore when its password was checked, or `None` if it was
+    /// imported without a password.
+    pub decrypt_time_ms: Option<u64>,
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, mut env: Environment<T>) -> Result<(), ExitError> {
     let keystore: Option<PathBuf> = clap_utils::parse_optional(matches, KEYSTORE_FLAG)?;
     let keystores_dir: Option<PathBuf> = clap_utils::parse_optional(matches, DIR_FLAG)?;
+    let archive: Option<PathBuf> = clap_utils::parse_optional(matches, ARCHIVE_FLAG)?;
     let validator_dir = clap_utils::parse_path_with_default_in_home_dir(
         matches,
         VALIDATOR_DIR_FLAG,
         PathBuf::new().join(".lighthouse").join("validators"),
     )?;
     let stdin_password = matches.is_present(STDIN_PASSWORD_FLAG);
+    let password_file: Option<PathBuf> = clap_utils::parse_optional(matches, PASSWORD_FILE_FLAG)?;
+    let password_env = matches.value_of(PASSWORD_ENV_FLAG);
+    let dry_run = matches.is_present(DRY_RUN_FLAG);
+    let verify_only = matches.is_present(VERIFY_ONLY_FLAG);
+    let json = matches.is_present(JSON_FLAG);
+    let progress = !json && !matches.is_present(NO_PROGRESS_FLAG);
+    let jobs: Option<usize> = clap_utils::parse_optional(matches, JOBS_FLAG)?;
+    let prompt_timeout =
+        clap_utils::parse_optional(matches, PROMPT_TIMEOUT_FLAG)?.map(Duration::from_secs);
+    let max_password_attempts: Option<u32> =
+        clap_utils::parse_optional(matches, MAX_PASSWORD_ATTEMPTS_FLAG)?;
+    if max_password_attempts == Some(0) {
+        return Err(format!("--{} must be at least 1", MAX_PASSWORD_ATTEMPTS_FLAG).into());
+    }
+    // The pause is only useful to someone watching the terminal.
+    let prompt_delay = match clap_utils::parse_optional(matches, PROMPT_DELAY_FLAG)? {
+        Some(millis) => Duration::from_millis(millis),
+        None if json || matches.is_present(NO_TTY_FLAG) => Duration::from_secs(0),
+        None => PROMPT_DELAY,
+    };
+    let reencrypt = matches.is_present(REENCRYPT_FLAG);
+    let store_password = !matches.is_present(NO_STORE_PASSWORD_FLAG);
+    let allow_weak_password = matches.is_present(ALLOW_WEAK_PASSWORD_FLAG);
+    let stdin_paths = matches.is_present(STDIN_PATHS_FLAG);
+    let io_retries: u32 = clap_utils::parse_required(matches, IO_RETRIES_FLAG)?;
+    let skip_existing = matches.is_present(SKIP_EXISTING_FLAG);
+    let min_scrypt_n: Option<u32> = clap_utils::parse_optional(matches, MIN_SCRYPT_N_FLAG)?;
+    let min_pbkdf2_c: Option<u32> = clap_utils::parse_optional(matches, MIN_PBKDF2_C_FLAG)?;
+    let count: Option<usize> = clap_utils::parse_optional(matches, COUNT_FLAG)?;
+    let count_only = matches.is_present(COUNT_ONLY_FLAG);
+    let slashing_protection: Option<PathBuf> =
+        clap_utils::parse_optional(matches, SLASHING_PROTECTION_FLAG)?;
+    let force = matches.is_present(FORCE_FLAG);
+    let keep_going = matches.is_present(KEEP_GOING_FLAG);
+    let backup_definitions = matches.is_present(BACKUP_DEFINITIONS_FLAG);
+    let max_backups: Option<usize> = clap_utils::parse_optional(matches, MAX_BACKUPS_FLAG)?;
+    let definitions_dir: Option<PathBuf> = clap_utils::parse_optional(matches, OUTPUT_PATH_FLAG)?;
+    let journal: Option<PathBuf> = clap_utils::parse_optional(matches, JOURNAL_FLAG)?;
+    let manifest: Option<PathBuf> = clap_utils::parse_optional(matches, MANIFEST_FLAG)?;
+    let relative_paths = matches.is_present(RELATIVE_PATHS_FLAG);
+    let dest_naming = match matches.value_of(DEST_NAMING_FLAG) {
+        Some(DEST_NAMING_UUID) => DestNaming::Uuid,
+        _ => DestNaming::Pubkey,
+    };
+    let wallet_name = matches
+        .value_of(WALLET_NAME_FLAG)
+        .map(|name| {
+            parse_wallet_name(name).map_err(|e| format!("Invalid --{}: {}", WALLET_NAME_FLAG, e))
+        })
+        .transpose()?;
+    let strict_perms = matches.is_present(STRICT_PERMS_FLAG);
+    let network = matches.value_of(NETWORK_FLAG).map(String::from);
+    let strict_network = matches.is_present(STRICT_NETWORK_FLAG);
+    let import_disabled = matches.is_present(IMPORT_DISABLED_FLAG);
+    let select_keystores = !matches.is_present(NO_TTY_FLAG) && !matches.is_present(ALL_FLAG);
+    let graffiti = matches
+        .value_of(GRAFFITI_FLAG)
+        .map(|graffiti| {
+            parse_graffiti(graffiti).map_err(|e| format!("Invalid --{}: {}", GRAFFITI_FLAG, e))
+        })
+        .transpose()?;
+    let suggested_fee_recipient = matches
+        .value_of(FEE_RECIPIENT_FLAG)
+        .map(|address| {
+            parse_fee_recipient(address)
+                .map_err(|e| format!("Invalid --{}: {}", FEE_RECIPIENT_FLAG, e))
+        })
+        .transpose()?;
+    let filename_pattern = matches
+        .value_of(FILENAME_PATTERN_FLAG)
+        .map(|pattern| {
+            // Anchor the pattern so that it must match the whole file name.
+            Regex::new(&format!("^(?:{})$", pattern))
+                .map_err(|e| format!("Invalid --{}: {:?}", FILENAME_PATTERN_FLAG, e))
+        })
+        .transpose()?;
+    let deposit_data: Option<PathBuf> = clap_utils::parse_optional(matches, DEPOSIT_DATA_FLAG)?;
+    let follow_symlinks = matches.is_present(FOLLOW_SYMLINKS_FLAG);
+    let since = matches
+        .value_of(SINCE_FLAG)
+        .map(|since| parse_rfc3339(since).map_err(|e| format!("Invalid --{}: {}", SINCE_FLAG, e)))
+        .transpose()?;
+    let max_keystore_size: u64 = clap_utils::parse_required(matches, MAX_KEYSTORE_SIZE_FLAG)?;
+
+    let log = env.core_context().log().clone();
+    let log = match matches.value_of(LOG_LEVEL_FLAG) {
+        Some(level) => Logger::root(log.filter_level(parse_log_level(level)?).fuse(), o!()),
+        None => log,
+    };
+
+    if stdin_paths && password_file.is_none() && password_env.is_none() {
+        return Err(format!(
+            "--{} requires --{} or --{}",
+            STDIN_PATHS_FLAG, PASSWORD_FILE_FLAG, PASSWORD_ENV_FLAG
+        )
+        .into());
+    }
+
+    let (passwords, overrides) = match password_file {
+        // Nothing is decrypted with `--count-only`, so the passphrase is not asked for.
+        Some(path) if !count_only => read_password_file(&path, stdin_password)?,
+        _ => (HashMap::new(), HashMap::new()),
+    };
+    let env_password = password_env.map(read_password_env).transpose()?;
 
-    ensure_dir_exists(&validator_dir)?;
+    // Abort before any files are modified if the interchange file is for another network.
+    let interchange = slashing_protection
+        .map(|path| {
+            let genesis_validators_root = genesis_validators_root(&env)
+                .map_err(|e| format!("Unable to use --{}: {}", SLASHING_PROTECTION_FLAG, e))?;
+            read_interchange(&path, genesis_validators_root)
+                .map(|interchange| (interchange, genesis_validators_root))
+        })
+        .transpose()?;
 
-    let mut defs = ValidatorDefinitions::open_or_create(&validator_dir)
-        .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?;
+    // The keystores in an archive are imported from a temporary directory, which is removed when
+    // `extracted_archive` is dropped, including on error.
+    let extracted_archive = archive
+        .map(|archive| extract_archive(&log, &archive))
+        .transpose()?;
+    let keystores_dir = keystores_dir.or_else(|| {
+        extracted_archive
+            .as_ref()
+            .map(|extracted| extracted.dir.path().to_path_buf())
+    });
 
     // Collect the paths for the keystores that should be imported.
+    let from_dir = keystores_dir.is_some();
     let keystore_paths = match (keystore, keystores_dir) {
-        (Some(keystore), None) => vec![keystore],
-        (None, Some(keystores_dir)) => {
-            let mut keystores = vec![];
-
-            recursively_find_voting_keystores(&keystores_dir, &mut keystores)
-                .map_err(|e| format!("Unable to search {:?}: {:?}", keystores_dir, e))?;
+        (None, None) if stdin_paths => {
+            let keystores = read_stdin_paths()?;
 
             if keystores.is_empty() {
-                eprintln!("No keystores found in {:?}", keystores_dir);
+                info!(log, "No keystore paths supplied on stdin");
+                if json {
+                    print_json_summary(vec![])?;
+                }
                 return Ok(());
             }
 
             keystores
         }
-        _ => {
-            return Err(format!(
-                "Must supply either --{} or --{}",
-                KEYSTORE_FLAG, DIR_FLAG
-            ))
+        (Some(keystore), None) => vec![keystore],
+        (None, Some(keystores_dir)) => {
+            let mut keystores = vec![];
+
+            match &filename_pattern {
+                Some(pattern) => recursively_find_voting_keystores_matching(
+                    &keystores_dir,
+                    pattern,
+                    follow_symlinks,
+                    &mut keystores,
+                ),
+                None => recursively_find_voting_keystores(
+                    &keystores_dir,
+                    follow_symlinks,
+                    &mut keystores,
+                ),
+            }
+            .map_err(|e| format!("Unable to search {:?}: {:?}", keystores_dir, e))?;
+
+            // Only the modification times are read, so this is cheap compared to decryption.
+            if let Some(since) = since {
+                keystores = filter_keystores_modified_since(&log, keystores, since);
+            }
+
+            if keystores.is_empty() {
+                info!(
+                    log,
+                    "No keystores found";
+                    "dir" => format!("{:?}", keystores_dir)
+                );
+                if count_only {
+                    print_discovery_report(&[], json)?;
+                } else if json {
+                    print_json_summary(vec![])?;
+                }
+                return Ok(());
+            }
+
+            // Sort by file name so that the import order, and therefore the keystores chosen by
+            // `--count`, does not depend on the file system.
+            keystores.sort_by(|a, b| a.file_name().cmp(&b.file_name()).then_with(|| a.cmp(b)));
+
+            keystores
+        }
+        _ => {
+            return Err(format!(
+                "Must supply either --{}, --{}, --{} or --{}",
+                KEYSTORE_FLAG, DIR_FLAG, ARCHIVE_FLAG, STDIN_PATHS_FLAG
+            )
+            .into())
+        }
+    };
+
+    // A bundle is not a keystore, and may be much larger.
+    let keystore_paths = if matches.value_of(FORMAT_FLAG) == Some(FORMAT_HARDWARE_BUNDLE) {
+        keystore_paths
+    } else {
+        filter_large_keystores(&log, keystore_paths, max_keystore_size)
+    };
+
+    // Only the validators which were funded are imported.
+    let keystore_paths = match deposit_data {
+        Some(path) => {
+            let deposit_pubkeys = read_deposit_data(&path)?;
+            filter_deposit_data_keystores(&log, keystore_paths, &deposit_pubkeys)
+        }
+        None => keystore_paths,
+    };
+
+    if count_only {
+        return Ok(print_discovery_report(&keystore_paths, json)?);
+    }
+
+    // Let the user choose which of the keystores in a (possibly shared) directory to import,
+    // when they can be asked.
+    let keystore_paths = if from_dir
+        && select_keystores
+        && keystore_paths.len() > 1
+        && !stdin_password
+        && atty::is(atty::Stream::Stdin)
+    {
+        select_keystore_paths(keystore_paths)?
+    } else {
+        keystore_paths
+    };
+
+    // The keys in a bundle are imported from keystores in a temporary directory, which is removed
+    // like an extracted archive. The keystores are encrypted with the bundle password.
+    #[cfg(feature = "hardware_bundle")]
+    let (keystore_paths, env_password, extracted_archive) =
+        if matches.value_of(FORMAT_FLAG) == Some(FORMAT_HARDWARE_BUNDLE) {
+            let (unbundled, keystore_paths, password) =
+                unbundle_keystores(&log, &keystore_paths[0], env_password, stdin_password)?;
+            (keystore_paths, Some(password), Some(unbundled))
+        } else {
+            (keystore_paths, env_password, extracted_archive)
+        };
+
+    if verify_only {
+        return Ok(verify_keystores(
+            &log,
+            &keystore_paths,
+            &passwords,
+            env_password.as_ref(),
+            // Interactive prompts are not possible when stdin is used for the paths.
+            !stdin_paths,
+            stdin_password,
+            prompt_timeout,
+        )?);
+    }
+
+    // The new password is requested once and used for every re-encrypted keystore.
+    let new_password = if reencrypt && !dry_run {
+        Some(read_new_password(
+            REENCRYPT_PASSWORD_PROMPT,
+            stdin_password,
+            allow_weak_password,
+        )?)
+    } else {
+        None
+    };
+
+    // The keystores and the definitions file are on separate paths, which must both exist.
+    if let Some(definitions_dir) = definitions_dir.as_ref().filter(|_| !dry_run) {
+        ensure_dir_exists(&validator_dir)?;
+        ensure_dir_exists(definitions_dir)?;
+    }
+
+    let definitions_path = definitions_dir
+        .as_ref()
+        .unwrap_or(&validator_dir)
+        .join(CONFIG_FILENAME);
+
+    let report = import_keystores(ImportConfig {
+        keystore_paths,
+        validator_dir,
+        definitions_dir,
+        relative_paths,
+        wallet_name,
+        dest_naming,
+        passwords,
+        default_password: env_password,
+        overrides,
+        // Interactive prompts are not possible when stdin is used for the paths.
+        interactive: !stdin_paths,
+        stdin_passwords: stdin_password,
+        prompt_timeout,
+        prompt_delay,
+        max_password_attempts,
+        new_password,
+        store_password,
+        dry_run,
+        skip_existing,
+        count,
+        min_scrypt_n,
+        min_pbkdf2_c,
+        strict_perms,
+        network,
+        strict_network,
+        import_disabled,
+        graffiti,
+        suggested_fee_recipient,
+        slashing_protection: interchange,
+        backup_definitions,
+        max_backups,
+        journal,
+        keep_going,
+        force,
+        io_retries,
+        jobs,
+        progress,
+        log,
+    })
+    .map_err(|e| e.to_string())?;
+
+    if keep_going {
+        print_outcomes(&report.outcomes);
+    }
+
+    let num_imported_keystores = report.num_imported();
+    let num_failed_keystores = report.num_failed();
+
+    if let Some(manifest) = manifest {
+        let keystore_paths = report
+            .imported
+            .iter()
+            .map(|keystore| keystore.dest_path.clone())
+            .collect::<Vec<_>>();
+        Manifest::new(&keystore_paths, definitions_path)?.to_json_file(&manifest)?;
+    }
+
+    if json {
+        print_json_summary(report.imported)?;
+    } else {
+        print_decrypt_times(&report.imported);
+    }
+
+    if num_failed_keystores > 0 && num_imported_keystores == 0 {
+        Err(format!(
+            "Unable to import any of the {} keystores",
+            num_failed_keystores
+        )
+        .into())
+    } else if num_failed_keystores > 0 {
+        Err(ExitError {
+            message: format!(
+                "Unable to import {} of {} keystores",
+                num_failed_keystores,
+                num_failed_keystores + num_imported_keystores
+            ),
+            code: PARTIAL_FAILURE_EXIT_CODE,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// The keystores and options for `import_keystores`.
+pub struct ImportConfig {
+    /// The keystores to import, in the order they should be imported.
+    pub keystore_paths: Vec<PathBuf>,
+    /// The validator directory containing the validator definitions file.
+    pub validator_dir: PathBuf,
+    /// If present, the validator definitions file is written to this directory instead of the
+    /// `validator_dir`, with absolute paths to the keystores.
+    pub definitions_dir: Option<PathBuf>,
+    /// If `true`, the keystores are referred to by paths relative to the `validator_dir`. Ignored
+    /// with a `definitions_dir`.
+    pub relative_paths: bool,
+    /// A sub-directory of the `validator_dir` to place the keystores in.
+    pub wallet_name: Option<String>,
+    /// The name of the directory each keystore is placed in.
+    pub dest_naming: DestNaming,
+    /// Passwords keyed by keystore public key (without the `0x` prefix) or UUID.
+    pub passwords: Passwords,
+    /// A password to try for every keystore without an entry in `passwords`.
+    pub default_password: Option<ZeroizeString>,
+    /// Graffiti and fee recipients keyed like `passwords`, which take precedence over `graffiti`
+    /// and `suggested_fee_recipient`.
+    pub overrides: HashMap<String, Overrides>,
+    /// If `true`, prompt for missing or incorrect passwords and before overwriting a definitions
+    /// file modified by another process. Otherwise, every keystore must have a correct password.
+    pub interactive: bool,
+    /// If `true`, read prompted passwords from stdin instead of the tty.
+    pub stdin_passwords: bool,
+    /// If present, the import is aborted when a prompted password is not entered in time.
+    pub prompt_timeout: Option<Duration>,
+    /// The pause after a password is entered at a prompt.
+    pub prompt_delay: Duration,
+    /// If present, a keystore fails to import after this many incorrect passwords are entered at
+    /// the prompt.
+    pub max_password_attempts: Option<u32>,
+    /// If present, the keystores are re-encrypted with this password.
+    pub new_password: Option<ZeroizeString>,
+    pub store_password: bool,
+    pub dry_run: bool,
+    pub skip_existing: bool,
+    pub count: Option<usize>,
+    /// Scrypt keystores with an `n` below this are refused.
+    pub min_scrypt_n: Option<u32>,
+    /// Pbkdf2 keystores with a `c` below this are refused.
+    pub min_pbkdf2_c: Option<u32>,
+    pub strict_perms: bool,
+    /// If present, keystores whose description names another network are reported.
+    pub network: Option<String>,
+    /// If `true`, keystores for another network than `network` are an error, rather than a
+    /// warning.
+    pub strict_network: bool,
+    /// If `true`, the validator definitions are written with `enabled: false`.
+    pub import_disabled: bool,
+    pub graffiti: Option<String>,
+    pub suggested_fee_recipient: Option<Address>,
+    /// An interchange file and the genesis validators root it was checked against.
+    pub slashing_protection: Option<(Interchange, Hash256)>,
+    pub backup_definitions: bool,
+    pub max_backups: Option<usize>,
+    /// If present, each imported keystore is recorded in this file, and the keystores already
+    /// recorded in it are skipped.
+    pub journal: Option<PathBuf>,
+    pub keep_going: bool,
+    pub force: bool,
+    pub io_retries: u32,
+    pub jobs: Option<usize>,
+    /// If `true`, print a progress line to stderr before each keystore.
+    pub progress: bool,
+    pub log: Logger,
+}
+
+impl ImportConfig {
+    /// Returns a config which imports `keystore_paths` into `validator_dir` without prompting,
+    /// storing the passwords from `passwords` in the validator definitions file.
+    pub fn new(keystore_paths: Vec<PathBuf>, validator_dir: PathBuf, log: Logger) -> Self {
+        Self {
+            keystore_paths,
+            validator_dir,
+            definitions_dir: None,
+            relative_paths: false,
+            wallet_name: None,
+            dest_naming: DestNaming::Pubkey,
+            passwords: Passwords::new(),
+            default_password: None,
+            overrides: HashMap::new(),
+            interactive: false,
+            stdin_passwords: false,
+            prompt_timeout: None,
+            prompt_delay: PROMPT_DELAY,
+            max_password_attempts: None,
+            new_password: None,
+            store_password: true,
+            dry_run: false,
+            skip_existing: false,
+            count: None,
+            min_scrypt_n: None,
+            min_pbkdf2_c: None,
+            strict_perms: false,
+            network: None,
+            strict_network: false,
+            import_disabled: false,
+            graffiti: None,
+            suggested_fee_recipient: None,
+            slashing_protection: None,
+            backup_definitions: false,
+            max_backups: None,
+            journal: None,
+            keep_going: false,
+            force: false,
+            io_retries: 3,
+            jobs: None,
+            progress: false,
+            log,
+        }
+    }
+}
+
+/// The name of the directory an imported keystore is placed in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DestNaming {
+    /// The validator public key, with a `0x` prefix.
+    Pubkey,
+    /// The keystore UUID.
+    Uuid,
+}
+
+impl DestNaming {
+    /// Returns the name of the directory for `keystore`.
+    pub fn dir_name(self, keystore: &Keystore) -> String {
+        match self {
+            DestNaming::Pubkey => format!("0x{}", keystore.pubkey()),
+            DestNaming::Uuid => keystore.uuid().to_string(),
+        }
+    }
+}
+
+/// The outcome of `import_keystores`.
+pub struct ImportReport {
+    /// The outcome for each keystore that was attempted, in order.
+    pub outcomes: Vec<KeystoreReport>,
+    /// The keystores which were (or, with `dry_run`, would be) imported.
+    pub imported: Vec<ImportedKeystore>,
+    /// The number of keystores which were skipped because they were already imported.
+    pub num_skipped: usize,
+    /// The number of keystores left for a later import by `count`.
+    pub num_remaining: usize,
+}
+
+impl ImportReport {
+    pub fn num_imported(&self) -> usize {
+        self.imported.len()
+    }
+
+    pub fn num_failed(&self) -> usize {
+        self.outcomes
+            .iter()
+            .filter(|report| matches!(report.outcome, KeystoreOutcome::Failed(_)))
+            .count()
+    }
+}
+
+/// The outcome of importing a single keystore.
+pub struct KeystoreReport {
+    pub path: PathBuf,
+    pub pubkey: String,
+    pub outcome: KeystoreOutcome,
+}
+
+/// An error which stopped `import_keystores`, or the import of a single keystore with
+/// `keep_going`.
+#[derive(Debug)]
+pub enum ImportError {
+    /// The lock on the validator directory, at the given path, is held by another process.
+    Locked(PathBuf),
+    /// The validator definitions file could not be opened.
+    DefinitionsOpen(validator_definitions::Error),
+    /// The validator definitions file could not be saved.
+    DefinitionsSave(validator_definitions::Error),
+    /// The validator definitions file was modified by another process and was not overwritten.
+    DefinitionsModified,
+    /// A validator definition could not be created for the imported keystore.
+    NewDefinition {
+        path: PathBuf,
+        error: validator_definitions::Error,
+    },
+    /// A keystore could not be read or parsed.
+    KeystoreRead {
+        path: PathBuf,
+        error: eth2_keystore::Error,
+    },
+    /// A keystore has an invalid public key.
+    InvalidPubkey(PathBuf),
+    /// A keystore has a file name which is not valid UTF-8.
+    InvalidFileName(PathBuf),
+    /// A keystore has unsupported KDF parameters.
+    UnsupportedKdf {
+        path: PathBuf,
+        error: eth2_keystore::Error,
+    },
+    /// Keystores have a KDF cost below `min_scrypt_n` or `min_pbkdf2_c`. No files were modified.
+    WeakKdf(Vec<PathBuf>),
+    /// Keystores are readable by other users and `strict_perms` is set. No files were modified.
+    ReadableByOthers(Vec<PathBuf>),
+    /// Keystores have a description naming another network and `strict_network` is set. No files
+    /// were modified.
+    NetworkMismatch {
+        network: String,
+        keystores: Vec<PathBuf>,
+    },
+    /// Public keys appear more than once, or are already imported. No files were modified.
+    DuplicatePubkeys(Vec<String>),
+    /// Keystores without a correct password when not `interactive`. No files were modified.
+    MissingPasswords(Vec<PathBuf>),
+    /// A keystore could not be decrypted, for a reason other than an incorrect password.
+    Decrypt {
+        path: PathBuf,
+        pubkey: String,
+        error: eth2_keystore::Error,
+    },
+    /// `max_password_attempts` incorrect passwords were entered at the prompt for a keystore.
+    PasswordAttempts {
+        path: PathBuf,
+        pubkey: String,
+        attempts: u32,
+    },
+    /// A keystore could not be re-encrypted with the new password.
+    Reencrypt {
+        path: PathBuf,
+        error: eth2_keystore::Error,
+    },
+    /// A keystore could not be written to the given path.
+    KeystoreWrite {
+        path: PathBuf,
+        error: eth2_keystore::Error,
+    },
+    /// A keystore could not be copied or moved into the validator directory.
+    IoCopy {
+        src: PathBuf,
+        dest: PathBuf,
+        error: io::Error,
+    },
+    /// Any other filesystem operation on `path` failed.
+    Io { path: PathBuf, error: io::Error },
+    /// The slashing protection interchange cannot be imported for this network.
+    IncompatibleInterchange(InterchangeError),
+    /// The slashing protection database at `path` could not be updated.
+    SlashingProtection {
+        path: PathBuf,
+        error: InterchangeError,
+    },
+    /// A password or confirmation could not be read from the user.
+    UserInput(String),
+    /// A password was not entered within the `--prompt-timeout`.
+    PromptTimeout(Duration),
+    /// The thread pool used to decrypt keystores could not be created.
+    ThreadPool(rayon::ThreadPoolBuildError),
+    /// The system time is before the UNIX epoch.
+    SystemTime(SystemTimeError),
+}
+
+impl ImportError {
+    fn io(path: &Path, error: io::Error) -> Self {
+        ImportError::Io {
+            path: path.to_path_buf(),
+            error,
+        }
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let paths = |paths: &[PathBuf]| {
+            paths
+                .iter()
+                .map(|path| format!("{:?}", path))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match self {
+            ImportError::Locked(path) => write!(
+                f,
+                "The validator directory is locked by a running validator client or another \
+                 account manager command holding {:?}. Stop the validator client or wait for the \
+                 command to finish, then try again, or use --{} to ignore the lock.",
+                path, FORCE_FLAG
+            ),
+            ImportError::DefinitionsOpen(e) => {
+                write!(f, "Unable to open {}: {:?}", CONFIG_FILENAME, e)
+            }
+            ImportError::DefinitionsSave(e) => {
+                write!(f, "Unable to save {}: {:?}", CONFIG_FILENAME, e)
+            }
+            ImportError::DefinitionsModified => write!(
+                f,
+                "Not overwriting {}. Stop the validator client or use --{}, then try again. \
+                 Previously imported keystores are unaffected.",
+                CONFIG_FILENAME, FORCE_FLAG
+            ),
+            ImportError::NewDefinition { path, error } => write!(
+                f,
+                "Unable to create new validator definition for {:?}: {:?}",
+                path, error
+            ),
+            ImportError::KeystoreRead { path, error } => {
+                write!(f, "Unable to read keystore JSON {:?}: {:?}", path, error)
+            }
+            ImportError::InvalidPubkey(path) => {
+                write!(f, "Invalid public key in keystore {:?}", path)
+            }
+            ImportError::InvalidFileName(path) => {
+                write!(f, "Badly formatted file name: {:?}", path)
+            }
+            ImportError::UnsupportedKdf { path, error } => {
+                write!(f, "Unsupported KDF parameters in {:?}: {:?}", path, error)
+            }
+            ImportError::WeakKdf(keystores) => write!(
+                f,
+                "Refusing to import keystores with a KDF cost below --{} or --{}, no files were \
+                 modified: {}",
+                MIN_SCRYPT_N_FLAG,
+                MIN_PBKDF2_C_FLAG,
+                paths(keystores)
+            ),
+            ImportError::ReadableByOthers(keystores) => write!(
+                f,
+                "Refusing to import keystores which are readable by other users, no files were \
+                 modified: {}",
+                paths(keystores)
+            ),
+            ImportError::NetworkMismatch { network, keystores } => write!(
+                f,
+                "Refusing to import keystores for a network other than {}, no files were \
+                 modified: {}",
+                network,
+                paths(keystores)
+            ),
+            ImportError::DuplicatePubkeys(pubkeys) => write!(
+                f,
+                "Refusing to import duplicate public keys, no files were modified: {}",
+                pubkeys.join(", ")
+            ),
+            ImportError::MissingPasswords(keystores) => write!(
+                f,
+                "--{} and --{} do not provide a correct password for: {}",
+                PASSWORD_FILE_FLAG,
+                PASSWORD_ENV_FLAG,
+                paths(keystores)
+            ),
+            ImportError::Decrypt {
+                path,
+                pubkey,
+                error: eth2_keystore::Error::PublicKeyMismatch,
+            } => write!(
+                f,
+                "The secret key in keystore {:?} does not match its public key 0x{}, the keystore \
+                 may have been tampered with",
+                path, pubkey
+            ),
+            ImportError::PasswordAttempts {
+                path,
+                pubkey,
+                attempts,
+            } => write!(
+                f,
+                "{} incorrect passwords were entered for keystore {:?} with public key 0x{}",
+                attempts, path, pubkey
+            ),
+            ImportError::Decrypt { path, error, .. } => {
+                write!(
+                    f,
+                    "Error whilst decrypting keystore {:?}: {:?}",
+                    path, error
+                )
+            }
+            ImportError::Reencrypt { path, error } => {
+                write!(f, "Unable to re-encrypt keystore {:?}: {:?}", path, error)
+            }
+            ImportError::KeystoreWrite { path, error } => {
+                write!(f, "Unable to write keystore {:?}: {:?}", path, error)
+            }
+            ImportError::IoCopy { src, dest, error } => write!(
+                f,
+                "Unable to copy keystore {:?} to {:?}: {:?}",
+                src, dest, error
+            ),
+            ImportError::Io { path, error } => {
+                write!(f, "Unable to access {:?}: {:?}", path, error)
+            }
+            ImportError::IncompatibleInterchange(
+                InterchangeError::GenesisValidatorsRootMismatch {
+                    interchange_file,
+                    client,
+                },
+            ) => write!(
+                f,
+                "The slashing protection interchange is for a different network, no files were \
+                 modified. Genesis validators root of the interchange: {:?}, of this network: {:?}",
+                interchange_file, client
+            ),
+            ImportError::IncompatibleInterchange(e) => {
+                write!(
+                    f,
+                    "Unable to use the slashing protection interchange: {:?}",
+                    e
+                )
+            }
+            ImportError::SlashingProtection { path, error } => write!(
+                f,
+                "Unable to import slashing protection data into {:?}: {:?}",
+                path, error
+            ),
+            ImportError::UserInput(e) => write!(f, "{}", e),
+            ImportError::PromptTimeout(timeout) => write!(
+                f,
+                "No password was entered within {} seconds",
+                timeout.as_secs()
+            ),
+            ImportError::ThreadPool(e) => write!(f, "Unable to build thread pool: {:?}", e),
+            ImportError::SystemTime(e) => write!(f, "Unable to read system time: {:?}", e),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+/// Imports the keystores in `config.keystore_paths` into `config.validator_dir`.
+///
+/// Every keystore is checked before any files are modified. Unless `config.keep_going` is set,
+/// the import stops at the first keystore which cannot be imported.
+pub fn import_keystores(config: ImportConfig) -> Result<ImportReport, ImportError> {
+    import_keystores_with_events(config, |_| ())
+}
+
+/// As `import_keystores`, calling `on_event` as each keystore is imported so that the progress of
+/// the import can be shown.
+pub fn import_keystores_with_events<F>(
+    config: ImportConfig,
+    mut on_event: F,
+) -> Result<ImportReport, ImportError>
+where
+    F: FnMut(ImportEvent),
+{
+    let ImportConfig {
+        keystore_paths,
+        validator_dir,
+        definitions_dir,
+        relative_paths,
+        wallet_name,
+        dest_naming,
+        passwords,
+        default_password,
+        overrides,
+        interactive,
+        stdin_passwords,
+        prompt_timeout,
+        prompt_delay,
+        max_password_attempts,
+        new_password,
+        store_password,
+        dry_run,
+        skip_existing,
+        count,
+        min_scrypt_n,
+        min_pbkdf2_c,
+        strict_perms,
+        network,
+        strict_network,
+        import_disabled,
+        graffiti,
+        suggested_fee_recipient,
+        slashing_protection,
+        backup_definitions,
+        max_backups,
+        journal,
+        keep_going,
+        force,
+        io_retries,
+        jobs,
+        progress,
+        log,
+    } = config;
+
+    // Loading the slashing protection history of another network is dangerous, so it is checked
+    // before any files are modified.
+    if let Some((interchange, genesis_validators_root)) = &slashing_protection {
+        interchange
+            .check_compatible(*genesis_validators_root)
+            .map_err(ImportError::IncompatibleInterchange)?;
+    }
+
+    // The directory which will contain a directory for each imported keystore.
+    let keystores_root = match &wallet_name {
+        Some(wallet_name) => validator_dir.join(wallet_name),
+        None => validator_dir.clone(),
+    };
+
+    // The keystore paths in a definitions file outside of the `validator_dir` must not depend on
+    // the working directory of the validator client.
+    let (definitions_dir, keystores_root) = match definitions_dir {
+        Some(definitions_dir) if keystores_root.is_relative() => {
+            let cwd = env::current_dir().map_err(|e| ImportError::io(&keystores_root, e))?;
+            (definitions_dir, cwd.join(keystores_root))
         }
+        Some(definitions_dir) => (definitions_dir, keystores_root),
+        None => (validator_dir.clone(), keystores_root),
+    };
+
+    // Held until the end of the import so that concurrent imports cannot discard each other's
+    // changes to the definitions file.
+    let _lock = if dry_run {
+        None
+    } else {
+        fs::create_dir_all(&validator_dir).map_err(|e| ImportError::io(&validator_dir, e))?;
+        fs::create_dir_all(&definitions_dir).map_err(|e| ImportError::io(&definitions_dir, e))?;
+        lock_definitions(&log, &definitions_dir, force)?
+    };
+
+    if !dry_run {
+        remove_stale_staging_dirs(&log, io_retries, &validator_dir)?;
+    }
+
+    // Only a definitions file from before the import is backed up.
+    let defs_existed = definitions_dir.join(CONFIG_FILENAME).exists();
+
+    // A dry run must not create the validator directory or definitions file.
+    let mut defs = if dry_run {
+        if definitions_dir.join(CONFIG_FILENAME).exists() {
+            ValidatorDefinitions::open(&definitions_dir)
+        } else {
+            Ok(ValidatorDefinitions::default())
+        }
+    } else {
+        ValidatorDefinitions::open_or_create(&definitions_dir)
+    }
+    .map_err(ImportError::DefinitionsOpen)?;
+
+    // Used to detect another process modifying the definitions file whilst we hold a copy of it.
+    let mut defs_modified = definitions_modified(&definitions_dir);
+
+    // A dry run must not create the journal.
+    let mut journal = journal
+        .map(|path| Journal::open(&path, !dry_run))
+        .transpose()?;
+
+    // Read each keystore. Reading the JSON is fast, so this is done serially.
+    let keystores = keystore_paths
+        .iter()
+        .map(|src_keystore| {
+            Keystore::from_json_file(src_keystore).map_err(|error| ImportError::KeystoreRead {
+                path: src_keystore.clone(),
+                error,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Abort before any passwords are checked or files are modified if a keystore has unsupported
+    // KDF parameters, or a KDF cost below `--min-scrypt-n` or `--min-pbkdf2-c`. Keystores with an
+    // unknown version have already been rejected whilst parsing the JSON.
+    let mut weak_kdf_keystores = vec![];
+    for (src_keystore, keystore) in keystore_paths.iter().zip(keystores.iter()) {
+        validate_kdf(keystore.kdf()).map_err(|error| ImportError::UnsupportedKdf {
+            path: src_keystore.clone(),
+            error,
+        })?;
+
+        let cost = kdf_cost(keystore.kdf());
+        let (min_cost, low_cost) = match keystore.kdf() {
+            Kdf::Scrypt(_) => (min_scrypt_n, LOW_SCRYPT_N),
+            Kdf::Pbkdf2(_) => (min_pbkdf2_c, LOW_PBKDF2_C),
+        };
+        if min_cost.map_or(false, |min_cost| cost < min_cost) {
+            weak_kdf_keystores.push(src_keystore.clone());
+        } else if cost < low_cost {
+            warn!(
+                log,
+                "Keystore has a low KDF cost";
+                "kdf_cost" => cost,
+                "kdf" => kdf_name(keystore.kdf()),
+                "path" => format!("{:?}", src_keystore)
+            );
+        }
+    }
+    if !weak_kdf_keystores.is_empty() {
+        return Err(ImportError::WeakKdf(weak_kdf_keystores));
+    }
+
+    // Abort before any files are modified if a keystore is readable by other users and
+    // `--strict-perms` is supplied. The imported keystores are only readable by their owner.
+    let mut readable_keystores = vec![];
+    for src_keystore in &keystore_paths {
+        if is_readable_by_others(src_keystore)? {
+            if strict_perms {
+                readable_keystores.push(src_keystore.clone());
+            } else {
+                warn!(
+                    log,
+                    "Keystore is readable by other users";
+                    "path" => format!("{:?}", src_keystore)
+                );
+            }
+        }
+    }
+    if !readable_keystores.is_empty() {
+        return Err(ImportError::ReadableByOthers(readable_keystores));
+    }
+
+    // As above, abort if a keystore description names another network than `--network` and
+    // `--strict-network` is supplied.
+    if let Some(network) = network {
+        let mut mismatched_keystores = vec![];
+        for (src_keystore, keystore) in keystore_paths.iter().zip(keystores.iter()) {
+            let hints = keystore
+                .description()
+                .map(network_hints)
+                .unwrap_or_default();
+            if hints.is_empty() || hints.contains(&network.as_str()) {
+                continue;
+            }
+
+            if strict_network {
+                mismatched_keystores.push(src_keystore.clone());
+            } else {
+                warn!(
+                    log,
+                    "Keystore description names another network";
+                    "networks" => hints.join(", "),
+                    "expected" => &network,
+                    "path" => format!("{:?}", src_keystore)
+                );
+            }
+        }
+        if !mismatched_keystores.is_empty() {
+            return Err(ImportError::NetworkMismatch {
+                network,
+                keystores: mismatched_keystores,
+            });
+        }
+    }
+
+    // A keystore file with the same contents as one that was previously imported is always
+    // skipped, so that an interrupted import can be run again.
+    let keystore_hashes = keystore_paths
+        .iter()
+        .map(|path| keystore_sha256(path).map(|hash| (path.clone(), hash)))
+        .collect::<Result<HashMap<_, _>, ImportError>>()?;
+    let imported_hashes = defs
+        .as_slice()
+        .iter()
+        .filter_map(|def| def.imported_keystore_sha256.as_ref())
+        .collect::<HashSet<_>>();
+
+    // Abort before any files are modified if a public key or destination directory appears more
+    // than once in the keystores being imported, or if it is already known to the
+    // `validator_dir` (unless `--skip-existing` is supplied).
+    let known_pubkeys = defined_pubkeys(&defs);
+    let mut seen_pubkeys = HashSet::new();
+    let mut seen_dest_names = HashSet::new();
+    let mut duplicate_pubkeys = vec![];
+    let mut existing_pubkeys = HashSet::new();
+    for (src_keystore, keystore) in keystore_paths.iter().zip(keystores.iter()) {
+        let pubkey = format!("0x{}", keystore.pubkey());
+        let dest_name = dest_naming.dir_name(keystore);
+        let is_existing = is_known_pubkey(&validator_dir, &known_pubkeys, &pubkey)
+            || keystores_root.join(&pubkey).exists()
+            || keystores_root.join(&dest_name).exists();
+        let is_imported = imported_hashes.contains(&keystore_hashes[src_keystore])
+            || journal.as_ref().map_or(false, |journal| {
+                journal.contains(src_keystore, keystore.pubkey())
+            });
+
+        if is_imported || (is_existing && skip_existing) {
+            existing_pubkeys.insert(pubkey);
+            continue;
+        }
+
+        let is_duplicate = !seen_pubkeys.insert(pubkey.clone())
+            || !seen_dest_names.insert(dest_name)
+            || is_existing;
+
+        if is_duplicate && !duplicate_pubkeys.contains(&pubkey) {
+            duplicate_pubkeys.push(pubkey);
+        }
+    }
+    if !duplicate_pubkeys.is_empty() {
+        return Err(ImportError::DuplicatePubkeys(duplicate_pubkeys));
+    }
+
+    let num_keystores = keystore_paths.len();
+    let (mut keystore_paths, mut keystores): (Vec<_>, Vec<_>) = keystore_paths
+        .into_iter()
+        .zip(keystores)
+        .filter(|(src_keystore, keystore)| {
+            let pubkey = format!("0x{}", keystore.pubkey());
+            let is_existing = existing_pubkeys.contains(&pubkey);
+
+            if is_existing {
+                info!(
+                    log,
+                    "Skipping keystore for validator which is already imported";
+                    "pubkey" => pubkey,
+                    "path" => format!("{:?}", src_keystore)
+                );
+            }
+
+            !is_existing
+        })
+        .unzip();
+
+    // Keystores beyond `--count` are left for a later import.
+    let num_remaining_keystores = match count {
+        Some(count) if keystore_paths.len() > count => {
+            let num_remaining_keystores = keystore_paths.len() - count;
+            info!(
+                log,
+                "Leaving the remaining keystores for a later import";
+                "count" => count,
+                "remaining" => num_remaining_keystores
+            );
+            keystore_paths.truncate(count);
+            keystores.truncate(count);
+            num_remaining_keystores
+        }
+        _ => 0,
+    };
+
+    // Check the password from the `passwords` (if any) for each keystore which will be imported.
+    //
+    // Decryption is CPU-heavy so this is done in parallel, before any files are modified. The
+    // interactive prompts and filesystem changes below remain serial.
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs.unwrap_or(0))
+        .build()
+        .map_err(ImportError::ThreadPool)?;
+
+    let decrypt_progress = progress
+        && (!passwords.is_empty() || default_password.is_some())
+        && keystore_paths.len() > 1;
+    let num_decrypted = AtomicUsize::new(0);
+    let keystores = pool.install(|| {
+        keystores
+            .into_par_iter()
+            .map(|keystore| {
+                if decrypt_progress {
+                    print_progress(
+                        num_decrypted.fetch_add(1, Ordering::Relaxed) + 1,
+                        keystore_paths.len(),
+                        "checking the password for",
+                        keystore.pubkey(),
+                    );
+                }
+
+                let file_password = passwords
+                    .get(keystore.pubkey())
+                    .or_else(|| passwords.get(&keystore.uuid().to_string()))
+                    .map(|password| (PASSWORD_FILE_FLAG, password))
+                    .or_else(|| {
+                        default_password
+                            .as_ref()
+                            .map(|password| (PASSWORD_ENV_FLAG, password))
+                    })
+                    .map(|(source, password)| {
+                        let started = Instant::now();
+                        let result = keystore
+                            .decrypt_keypair(password.as_ref())
+                            .map(|_| (password.clone(), started.elapsed()));
+                        (source, result)
+                    });
+
+                (keystore, file_password)
+            })
+            .collect::<Vec<_>>()
+    });
+
+    // Without interactive prompts, every keystore must have a correct password in the
+    // `passwords` or the `default_password`.
+    if !interactive {
+        let missing = keystore_paths
+            .iter()
+            .zip(keystores.iter())
+            .filter(|(_, (_, file_password))| {
+                matches!(
+                    file_password,
+                    None | Some((_, Err(eth2_keystore::Error::InvalidPassword)))
+                )
+            })
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+
+        if !missing.is_empty() {
+            return Err(ImportError::MissingPasswords(missing));
+        }
+    }
+
+    // Seed the slashing protection database before any validator is added to the definitions
+    // file, so that the validator client cannot sign without the imported history.
+    if let Some((interchange, genesis_validators_root)) = slashing_protection.filter(|_| !dry_run) {
+        let pubkeys = keystore_paths
+            .iter()
+            .zip(keystores.iter())
+            .map(|(src_keystore, (keystore, _))| {
+                keystore
+                    .public_key()
+                    .ok_or_else(|| ImportError::InvalidPubkey(src_keystore.clone()))
+            })
+            .collect::<Result<Vec<_>, ImportError>>()?;
+        seed_slashing_protection(
+            &log,
+            &validator_dir,
+            interchange,
+            genesis_validators_root,
+            &pubkeys,
+        )?;
+    }
+
+    if backup_definitions && defs_existed && !dry_run && !keystore_paths.is_empty() {
+        let backup = backup_definitions_file(&definitions_dir, max_backups)?;
+        info!(
+            log,
+            "Backed up {}", CONFIG_FILENAME;
+            "path" => format!("{:?}", backup)
+        );
+    }
+
+    if wallet_name.is_some() && !dry_run && !keystore_paths.is_empty() {
+        fs::create_dir_all(&keystores_root).map_err(|e| ImportError::io(&keystores_root, e))?;
+        sync_dir(&validator_dir)?;
+    }
+
+    warn!(log, "{}", KEYSTORE_REUSE_WARNING);
+
+    // For each keystore:
+    //
+    // - Obtain the keystore password, if the user desires.
+    // - Copy the keystore into the `validator_dir`, or re-encrypt it with a new password.
+    // - Add the keystore to the validator definitions file.
+    //
+    // Skip keystores that already exist, but exit early if any operation fails (unless
+    // `--keep-going` is supplied).
+    let mut outcomes = vec![];
+    let mut imported_keystores = vec![];
+    let num_to_import = keystore_paths.len();
+    for (i, (src_keystore, (keystore, file_password))) in
+        keystore_paths.iter().zip(keystores).enumerate()
+    {
+        let pubkey = format!("0x{}", keystore.pubkey());
+
+        // Printed before any prompt or decryption, so that slow keystores still show progress.
+        if progress && num_to_import > 1 {
+            print_progress(i + 1, num_to_import, "importing", keystore.pubkey());
+        }
+        on_event(ImportEvent::Started {
+            index: i,
+            total: num_to_import,
+            path: src_keystore.clone(),
+            pubkey: pubkey.clone(),
+        });
+
+        let result = (|| {
+            info!(
+                log,
+                "Found keystore";
+                "kdf_cost" => kdf_cost(keystore.kdf()),
+                "kdf" => kdf_name(keystore.kdf()),
+                "uuid" => keystore.uuid().to_string(),
+                "pubkey" => &pubkey,
+                "path" => format!("{:?}", src_keystore)
+            );
+
+            // The contents of the target are copied by `stage_keystore`, and the link and its
+            // target are never modified.
+            if let Ok(target) = fs::read_link(src_keystore) {
+                warn!(
+                    log,
+                    "Keystore is a symbolic link, copying its target";
+                    "target" => format!("{:?}", target),
+                    "path" => format!("{:?}", src_keystore)
+                );
+            }
+
+            // Use the password from the `--password-file` or `--password-env` (if any), before
+            // prompting the user.
+            let file_password = match file_password {
+                Some((source, Ok((password, decrypt_time)))) => {
+                    Some((source, password, decrypt_time))
+                }
+                Some((source, Err(eth2_keystore::Error::InvalidPassword))) => {
+                    warn!(
+                        log,
+                        "Incorrect password in --{}", source;
+                        "pubkey" => &pubkey
+                    );
+                    None
+                }
+                Some((_, Err(error))) => {
+                    return Err(ImportError::Decrypt {
+                        path: src_keystore.clone(),
+                        pubkey: keystore.pubkey().to_string(),
+                        error,
+                    })
+                }
+                None => None,
+            };
+
+            let (password_opt, decrypt_time) =
+                if let Some((source, password, decrypt_time)) = file_password {
+                    info!(
+                        log,
+                        "Password from --{} is correct", source;
+                        "pubkey" => &pubkey
+                    );
+                    (Some(password), Some(decrypt_time))
+                } else if dry_run || !interactive {
+                    (None, None)
+                } else {
+                    // The prompts are printed directly to stderr so they are shown regardless of the
+                    // log level.
+                    eprintln!("");
+                    eprintln!("Keystore found at {:?}:", src_keystore);
+                    eprintln!("");
+                    eprintln!(" - Public key: {}", pubkey);
+                    eprintln!(" - UUID: {}", keystore.uuid());
+                    eprintln!(" - KDF: {}", kdf_description(keystore.kdf()));
+                    eprintln!("");
+                    if store_password {
+                        eprintln!(
+                        "If you enter the password it will be stored as plain-text in {} so that \
+                         it is not required each time the validator client starts.",
+                        CONFIG_FILENAME
+                    );
+                    } else {
+                        eprintln!(
+                            "The password will be checked but not stored in {}.",
+                            CONFIG_FILENAME
+                        );
+                    }
+
+                    let mut num_attempts = 0;
+                    loop {
+                        eprintln!("");
+                        eprintln!("{}", PASSWORD_PROMPT);
+
+                        let password = read_password_with_timeout(stdin_passwords, prompt_timeout)?;
+
+                        if password.as_ref().is_empty() && new_password.is_some() {
+                            eprintln!("The password is required to re-encrypt the keystore.");
+                            continue;
+                        } else if password.as_ref().is_empty() && !store_password {
+                            eprintln!("The password is required to check the keystore.");
+                            continue;
+                        } else if password.as_ref().is_empty() {
+                            eprintln!("Continuing without password.");
+                            sleep(prompt_delay); // Provides nicer UX.
+                            break (None, None);
+                        }
+
+                        let started = Instant::now();
+                        match keystore.decrypt_keypair(password.as_ref()) {
+                            Ok(_) => {
+                                let decrypt_time = started.elapsed();
+                                eprintln!("Password is correct.");
+                                eprintln!("");
+                                sleep(prompt_delay); // Provides nicer UX.
+                                break (Some(password), Some(decrypt_time));
+                            }
+                            Err(eth2_keystore::Error::InvalidPassword) => {
+                                eprintln!("Invalid password");
+
+                                num_attempts += 1;
+                                if max_password_attempts == Some(num_attempts) {
+                                    return Err(ImportError::PasswordAttempts {
+                                        path: src_keystore.clone(),
+                                        pubkey: keystore.pubkey().to_string(),
+                                        attempts: num_attempts,
+                                    });
+                                }
+                            }
+                            Err(error) => {
+                                return Err(ImportError::Decrypt {
+                                    path: src_keystore.clone(),
+                                    pubkey: keystore.pubkey().to_string(),
+                                    error,
+                                })
+                            }
+                        }
+                    }
+                };
+
+            if decrypt_time.is_some() {
+                on_event(ImportEvent::DecryptOk {
+                    pubkey: pubkey.clone(),
+                });
+            }
+
+            // The keystore is placed in a directory named by `dest_naming`, which matches the name of
+            // the public key by default. This provides some loose protection against adding the
+            // same keystore twice.
+            let dest_dir = keystores_root.join(dest_naming.dir_name(&keystore));
+
+            // Retain the keystore file name, but place it in the new directory.
+            let keystore_file_name = src_keystore
+                .file_name()
+                .and_then(|file_name| file_name.to_str())
+                .ok_or_else(|| ImportError::InvalidFileName(src_keystore.clone()))?;
+            let dest_keystore = dest_dir.join(keystore_file_name);
+
+            if dest_dir.exists() {
+                warn!(
+                    log,
+                    "Skipping import of keystore for existing directory";
+                    "pubkey" => &pubkey,
+                    "path" => format!("{:?}", src_keystore)
+                );
+                return Ok(KeystoreOutcome::Skipped);
+            }
+
+            let imported_keystore = ImportedKeystore {
+                pubkey: pubkey.clone(),
+                uuid: keystore.uuid().to_string(),
+                source_path: src_keystore.clone(),
+                dest_path: dest_keystore.clone(),
+                password_saved: store_password && password_opt.is_some(),
+                enabled: !import_disabled,
+                kdf: kdf_name(keystore.kdf()).to_string(),
+                kdf_cost: kdf_cost(keystore.kdf()),
+                kdf_params: kdf_description(keystore.kdf()),
+                decrypt_time_ms: decrypt_time.map(|time| time.as_millis() as u64),
+            };
+
+            if dry_run {
+                info!(
+                    log,
+                    "Would copy keystore";
+                    "to" => format!("{:?}", dest_keystore),
+                    "from" => format!("{:?}", src_keystore)
+                );
+                imported_keystores.push(imported_keystore);
+                return Ok(KeystoreOutcome::WouldImport);
+            }
+
+            // Saving `defs` would discard any changes made by another process since it was opened,
+            // so confirm with the user before any files are modified.
+            if !force && definitions_modified(&definitions_dir) != defs_modified {
+                confirm_overwrite(&log, &definitions_dir, interactive)?;
+            }
+
+            let result = write_staged(
+                &log,
+                io_retries,
+                &validator_dir,
+                &keystore,
+                &dest_dir,
+                keystore_file_name,
+                |staging_keystore| {
+                    stage_keystore(
+                        &log,
+                        src_keystore,
+                        &keystore,
+                        staging_keystore,
+                        password_opt,
+                        new_password.as_ref(),
+                        io_retries,
+                    )
+                },
+            );
+
+            if let Err(e) = &result {
+                error!(
+                    log,
+                    "Unable to import keystore";
+                    "error" => e.to_string(),
+                    "pubkey" => &pubkey
+                );
+            }
+
+            // The password has been checked, but is discarded if it should not be stored.
+            let password_opt = result?.filter(|_| store_password);
+
+            on_event(ImportEvent::Moved {
+                pubkey: pubkey.clone(),
+                path: dest_keystore.clone(),
+            });
+
+            info!(log, "Successfully imported keystore"; "pubkey" => &pubkey);
+
+            let mut validator_def =
+                ValidatorDefinition::new_keystore_with_password(&dest_keystore, password_opt)
+                    .map_err(|error| ImportError::NewDefinition {
+                        path: dest_keystore.clone(),
+                        error,
+                    })?;
+
+            // Values from the `--password-file` take precedence over the flags.
+            let overrides = overrides
+                .get(keystore.pubkey())
+                .or_else(|| overrides.get(&keystore.uuid().to_string()));
+            validator_def.imported_keystore_sha256 = keystore_hashes.get(src_keystore).cloned();
+            validator_def.relative_paths = relative_paths && definitions_dir == validator_dir;
+            validator_def.enabled = !import_disabled;
+            validator_def.graffiti = overrides
+                .and_then(|overrides| overrides.graffiti.clone())
+                .or_else(|| graffiti.clone());
+            validator_def.suggested_fee_recipient = overrides
+                .and_then(|overrides| overrides.suggested_fee_recipient)
+                .or(suggested_fee_recipient);
+
+            defs.push(validator_def);
+
+            defs.save(&definitions_dir)
+                .map_err(ImportError::DefinitionsSave)?;
+            defs_modified = definitions_modified(&definitions_dir);
+
+            debug!(log, "Successfully updated {}", CONFIG_FILENAME);
+            on_event(ImportEvent::DefinitionSaved {
+                pubkey: pubkey.clone(),
+            });
+
+            if let Some(journal) = &mut journal {
+                journal.append(src_keystore, keystore.pubkey())?;
+            }
+
+            imported_keystores.push(imported_keystore);
+            Ok(KeystoreOutcome::Imported)
+        })();
+
+        if let Err(e) = &result {
+            on_event(ImportEvent::Error {
+                pubkey: pubkey.clone(),
+                error: e.to_string(),
+            });
+        }
+
+        let outcome = match result {
+            Ok(outcome) => outcome,
+            // Refusing to overwrite the definitions file, or being unable to ask, aborts the
+            // whole import, even with `--keep-going`.
+            Err(e @ ImportError::DefinitionsModified) | Err(e @ ImportError::UserInput(_)) => {
+                return Err(e)
+            }
+            // The user may still answer the next prompt, so only this keystore fails.
+            Err(e @ ImportError::PromptTimeout(_)) => {
+                error!(
+                    log,
+                    "Password prompt timed out, continuing with the next keystore";
+                    "error" => e.to_string(),
+                    "pubkey" => &pubkey
+                );
+                KeystoreOutcome::Failed(e)
+            }
+            Err(e) if keep_going => {
+                error!(
+                    log,
+                    "Unable to import keystore, continuing with the next";
+                    "error" => e.to_string(),
+                    "pubkey" => &pubkey
+                );
+                KeystoreOutcome::Failed(e)
+            }
+            Err(e) => return Err(e),
+        };
+        outcomes.push(KeystoreReport {
+            path: src_keystore.clone(),
+            pubkey,
+            outcome,
+        });
+    }
+
+    // Only succeeds if the staging directory is empty.
+    let _ = fs::remove_dir(validator_dir.join(STAGING_DIR));
+
+    let num_imported_keystores = outcomes
+        .iter()
+        .filter(|report| report.outcome.is_imported())
+        .count();
+    let num_failed_keystores = outcomes
+        .iter()
+        .filter(|report| matches!(report.outcome, KeystoreOutcome::Failed(_)))
+        .count();
+    let num_skipped_keystores =
+        num_keystores - num_remaining_keystores - num_imported_keystores - num_failed_keystores;
+    if dry_run {
+        info!(
+            log,
+            "Dry run complete";
+            "remaining" => num_remaining_keystores,
+            "failed" => num_failed_keystores,
+            "skipped" => num_skipped_keystores,
+            "would_import" => num_imported_keystores
+        );
+    } else {
+        info!(
+            log,
+            "Import complete";
+            "remaining" => num_remaining_keystores,
+            "failed" => num_failed_keystores,
+            "skipped" => num_skipped_keystores,
+            "imported" => num_imported_keystores
+        );
+    }
+    if !store_password && num_imported_keystores > 0 {
+        warn!(
+            log,
+            "The passwords were not stored in {}. The validator client will require an external \
+             password source (e.g., a `voting_keystore_password_path` or a password prompt) for \
+             these validators when it starts.",
+            CONFIG_FILENAME
+        );
+    }
+    if import_disabled && num_imported_keystores > 0 {
+        warn!(
+            log,
+            "The validators were imported but are disabled in {}. The validator client will not \
+             sign with them until they are enabled with `lighthouse account validator enable`.",
+            CONFIG_FILENAME
+        );
+    }
+    warn!(log, "{}", KEYSTORE_REUSE_WARNING);
+
+    Ok(ImportReport {
+        outcomes,
+        imported: imported_keystores,
+        num_skipped: num_skipped_keystores,
+        num_remaining: num_remaining_keystores,
+    })
+}
+
+/// An event in the import of a single keystore, passed to the callback of
+/// `import_keystores_with_events`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportEvent {
+    /// The import of the `index`th of `total` keystores has started.
+    Started {
+        index: usize,
+        total: usize,
+        path: PathBuf,
+        pubkey: String,
+    },
+    /// The password of the keystore is correct.
+    DecryptOk { pubkey: String },
+    /// The keystore has been copied (or re-encrypted) to `path`.
+    Moved { pubkey: String, path: PathBuf },
+    /// The validator definition of the keystore has been saved.
+    DefinitionSaved { pubkey: String },
+    /// The keystore could not be imported. The import continues with the next keystore only if
+    /// `keep_going` is set, or the password prompt timed out.
+    Error { pubkey: String, error: String },
+}
+
+/// The result of importing a single keystore.
+pub enum KeystoreOutcome {
+    Imported,
+    WouldImport,
+    Skipped,
+    Failed(ImportError),
+}
+
+impl KeystoreOutcome {
+    pub fn is_imported(&self) -> bool {
+        matches!(
+            self,
+            KeystoreOutcome::Imported | KeystoreOutcome::WouldImport
+        )
+    }
+}
+
+/// Prints a table of the outcome for each keystore, with `--keep-going`.
+fn print_outcomes(outcomes: &[KeystoreReport]) {
+    eprintln!("");
+    eprintln!("Import summary:");
+    eprintln!("");
+    for KeystoreReport {
+        path,
+        pubkey,
+        outcome,
+    } in outcomes
+    {
+        let outcome = match outcome {
+            KeystoreOutcome::Imported => "imported".to_string(),
+            KeystoreOutcome::WouldImport => "would import".to_string(),
+            KeystoreOutcome::Skipped => "skipped".to_string(),
+            KeystoreOutcome::Failed(e) => format!("failed: {}", e),
+        };
+        eprintln!(" {} {:?} {}", pubkey, path, outcome);
+    }
+    eprintln!("");
+}
+
+/// Decrypts each keystore with its password from `passwords` or `default_password` (or, if
+/// `interactive`, a prompt), logging whether it passed. No files are modified.
+///
+/// Returns an error if any keystore failed.
+fn verify_keystores(
+    log: &Logger,
+    keystore_paths: &[PathBuf],
+    passwords: &Passwords,
+    default_password: Option<&ZeroizeString>,
+    interactive: bool,
+    stdin_passwords: bool,
+    prompt_timeout: Option<Duration>,
+) -> Result<(), String> {
+    let mut num_failed = 0;
+
+    for src_keystore in keystore_paths {
+        let keystore = Keystore::from_json_file(src_keystore)
+            .map_err(|e| format!("Unable to read keystore {:?}: {:?}", src_keystore, e))?;
+
+        let password = passwords
+            .get(keystore.pubkey())
+            .or_else(|| passwords.get(&keystore.uuid().to_string()))
+            .or(default_password)
+            .cloned();
+        let password = match password {
+            Some(password) => Ok(password),
+            None if interactive => {
+                eprintln!("");
+                eprintln!("Keystore found at {:?}:", src_keystore);
+                eprintln!("");
+                eprintln!(" - Public key: 0x{}", keystore.pubkey());
+                eprintln!(" - UUID: {}", keystore.uuid());
+                eprintln!("");
+                eprintln!("{}", PASSWORD_PROMPT);
+                match read_password_with_timeout(stdin_passwords, prompt_timeout) {
+                    Ok(password) => Ok(password),
+                    // The user may still answer the next prompt, so only this keystore fails.
+                    Err(e @ ImportError::PromptTimeout(_)) => Err(e.to_string()),
+                    Err(e) => return Err(e.to_string()),
+                }
+            }
+            None => Err("No password supplied".to_string()),
+        };
+
+        let result = password.and_then(|password| {
+            keystore
+                .decrypt_keypair(password.as_ref())
+                .map(|_| ())
+                .map_err(|e| match e {
+                    eth2_keystore::Error::InvalidPassword => "Incorrect password".to_string(),
+                    e => format!("Unable to decrypt keystore: {:?}", e),
+                })
+        });
+
+        match result {
+            Ok(()) => info!(
+                log,
+                "Keystore verified";
+                "pubkey" => format!("0x{}", keystore.pubkey()),
+                "path" => format!("{:?}", src_keystore)
+            ),
+            Err(e) => {
+                error!(
+                    log,
+                    "Keystore failed verification";
+                    "error" => e,
+                    "pubkey" => format!("0x{}", keystore.pubkey()),
+                    "path" => format!("{:?}", src_keystore)
+                );
+                num_failed += 1;
+            }
+        }
+    }
+
+    if num_failed > 0 {
+        return Err(format!(
+            "{} of {} keystores failed verification",
+            num_failed,
+            keystore_paths.len()
+        ));
+    }
+
+    info!(
+        log,
+        "Successfully verified keystores";
+        "count" => keystore_paths.len()
+    );
+
+    Ok(())
+}
+
+/// Writes the keystore to `staging_keystore`, returning the password that should be stored in the
+/// validator definition.
+///
+/// If `new_password` is supplied, the keystore is decrypted with `password_opt` and re-encrypted
+/// with `new_password`. Otherwise, `src_keystore` is copied as-is.
+fn stage_keystore(
+    log: &Logger,
+    src_keystore: &Path,
+    keystore: &Keystore,
+    staging_keystore: &Path,
+    password_opt: Option<ZeroizeString>,
+    new_password: Option<&ZeroizeString>,
+    io_retries: u32,
+) -> Result<Option<ZeroizeString>, ImportError> {
+    match (new_password, password_opt) {
+        // The keypair and original password are zeroized when dropped at the end of this arm.
+        (Some(new_password), Some(password)) => {
+            let keypair = keystore
+                .decrypt_keypair(password.as_ref())
+                .map_err(|error| ImportError::Decrypt {
+                    path: src_keystore.to_path_buf(),
+                    pubkey: keystore.pubkey().to_string(),
+                    error,
+                })?;
+            let reencrypt_error = |error| ImportError::Reencrypt {
+                path: src_keystore.to_path_buf(),
+                error,
+            };
+            let new_keystore =
+                KeystoreBuilder::new(&keypair, new_password.as_ref(), keystore.path().to_string())
+                    .and_then(|builder| builder.build())
+                    .map_err(reencrypt_error)?;
+
+            write_keystore_file(&new_keystore, staging_keystore)?;
+
+            info!(log, "Re-encrypted keystore with the new password");
+
+            Ok(Some(new_password.clone()))
+        }
+        (_, password_opt) => {
+            retry_io(log, io_retries, || fs::copy(src_keystore, staging_keystore)).map_err(
+                |error| ImportError::IoCopy {
+                    src: src_keystore.to_path_buf(),
+                    dest: staging_keystore.to_path_buf(),
+                    error,
+                },
+            )?;
+            File::open(staging_keystore)
+                .and_then(|file| {
+                    set_owner_only_permissions(&file)?;
+                    file.sync_all()
+                })
+                .map_err(|e| ImportError::io(staging_keystore, e))?;
+
+            Ok(password_opt)
+        }
+    }
+}
+
+/// Removes the directories left in the `STAGING_DIR` of `validator_dir` by an interrupted import.
+///
+/// Only directories are created in the `STAGING_DIR`, so any other files are left in place.
+fn remove_stale_staging_dirs(
+    log: &Logger,
+    io_retries: u32,
+    validator_dir: &Path,
+) -> Result<(), ImportError> {
+    let staging_root = validator_dir.join(STAGING_DIR);
+    if !staging_root.exists() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(&staging_root).map_err(|e| ImportError::io(&staging_root, e))? {
+        let path = entry.map_err(|e| ImportError::io(&staging_root, e))?.path();
+        if path.is_dir() {
+            warn!(
+                log,
+                "Removing staging directory from an interrupted import";
+                "path" => format!("{:?}", path)
+            );
+            retry_io(log, io_retries, || fs::remove_dir_all(&path))
+                .map_err(|e| ImportError::io(&path, e))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes a keystore to `file_name` in the new directory `dest_dir` using `write`, which is given
+/// the path to write to.
+///
+/// The keystore is first written to a directory in the `STAGING_DIR` of `validator_dir`, which is
+/// then moved to `dest_dir`. This ensures `dest_dir` is never left with a partially written
+/// keystore. The staging directory is removed if any step fails.
+pub(crate) fn write_staged<T, F>(
+    log: &Logger,
+    io_retries: u32,
+    validator_dir: &Path,
+    keystore: &Keystore,
+    dest_dir: &Path,
+    file_name: &str,
+    write: F,
+) -> Result<T, ImportError>
+where
+    F: FnOnce(&Path) -> Result<T, ImportError>,
+{
+    let staging_dir = validator_dir
+        .join(STAGING_DIR)
+        .join(keystore.uuid().to_string());
+
+    // Remove any staging directory left behind by an interrupted import.
+    if staging_dir.exists() {
+        retry_io(log, io_retries, || fs::remove_dir_all(&staging_dir))
+            .map_err(|e| ImportError::io(&staging_dir, e))?;
+    }
+
+    fs::create_dir_all(&staging_dir).map_err(|e| ImportError::io(&staging_dir, e))?;
+
+    let result = write(&staging_dir.join(file_name)).and_then(|value| {
+        move_staged_keystore(log, io_retries, &staging_dir, dest_dir)?;
+        if let Some(parent) = dest_dir.parent() {
+            sync_dir(parent)?;
+        }
+        Ok(value)
+    });
+
+    if result.is_err() {
+        let _ = retry_io(log, io_retries, || fs::remove_dir_all(&staging_dir));
+    }
+
+    result
+}
+
+/// Writes `keystore` to a new file at `path` which is only readable by its owner, and syncs it to
+/// disk.
+pub(crate) fn write_keystore_file(keystore: &Keystore, path: &Path) -> Result<(), ImportError> {
+    let mut file = File::create(path)
+        .and_then(|file| set_owner_only_permissions(&file).map(|()| file))
+        .map_err(|e| ImportError::io(path, e))?;
+    keystore
+        .to_json_writer(&mut file)
+        .map_err(|error| ImportError::KeystoreWrite {
+            path: path.to_path_buf(),
+            error,
+        })?;
+    file.sync_all().map_err(|e| ImportError::io(path, e))
+}
+
+/// Moves `staging_dir` to `dest_dir` with `fs::rename`, which is atomic.
+///
+/// If the two directories are on different filesystems (e.g., the staging directory is a mount
+/// point) the rename is not possible. Instead, the files are copied to `dest_dir` and synced
+/// before `staging_dir` is removed. If the copy fails, `dest_dir` is removed.
+fn move_staged_keystore(
+    log: &Logger,
+    io_retries: u32,
+    staging_dir: &Path,
+    dest_dir: &Path,
+) -> Result<(), ImportError> {
+    let copy_error = |error| ImportError::IoCopy {
+        src: staging_dir.to_path_buf(),
+        dest: dest_dir.to_path_buf(),
+        error,
+    };
+
+    match fs::rename(staging_dir, dest_dir) {
+        Ok(()) => Ok(()),
+        Err(e) if e.raw_os_error() == Some(libc::EXDEV) => {
+            copy_dir_synced(log, io_retries, staging_dir, dest_dir)
+                .map_err(|e| {
+                    let _ = retry_io(log, io_retries, || fs::remove_dir_all(dest_dir));
+                    copy_error(e)
+                })
+                .and_then(|()| {
+                    retry_io(log, io_retries, || fs::remove_dir_all(staging_dir))
+                        .map_err(|e| ImportError::io(staging_dir, e))
+                })
+        }
+        Err(e) => Err(copy_error(e)),
+    }
+}
+
+/// Copies each file in `src_dir` into a new `dest_dir`, syncing each file to disk.
+fn copy_dir_synced(
+    log: &Logger,
+    io_retries: u32,
+    src_dir: &Path,
+    dest_dir: &Path,
+) -> Result<(), io::Error> {
+    fs::create_dir(dest_dir)?;
+
+    for entry in fs::read_dir(src_dir)? {
+        let entry = entry?;
+        let dest_file = dest_dir.join(entry.file_name());
+        retry_io(log, io_retries, || fs::copy(entry.path(), &dest_file))?;
+        File::open(&dest_file)?.sync_all()?;
+    }
+
+    Ok(())
+}
+
+/// Runs `op`, retrying it up to `retries` times if it fails with a transient error. The delay
+/// between attempts starts at `IO_RETRY_INITIAL_DELAY` and doubles after each retry.
+///
+/// Permanent errors (e.g., `PermissionDenied`) and the error from the final attempt are returned
+/// immediately.
+fn retry_io<T, F>(log: &Logger, retries: u32, mut op: F) -> Result<T, io::Error>
+where
+    F: FnMut() -> Result<T, io::Error>,
+{
+    let mut delay = IO_RETRY_INITIAL_DELAY;
+    let mut attempt = 0;
+
+    loop {
+        match op() {
+            Err(e) if attempt < retries && is_transient_io_error(&e) => {
+                attempt += 1;
+                warn!(
+                    log,
+                    "Retrying filesystem operation";
+                    "delay_ms" => delay.as_millis() as u64,
+                    "attempt" => attempt,
+                    "error" => format!("{:?}", e)
+                );
+                sleep(delay);
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Returns `true` if `e` may not occur if the operation is retried (e.g., `EINTR` or `EAGAIN`).
+fn is_transient_io_error(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+/// Syncs the directory entries of `dir` to disk, ensuring that files created or renamed within it
+/// persist across a power loss.
+fn sync_dir(dir: &Path) -> Result<(), ImportError> {
+    File::open(dir)
+        .and_then(|file| file.sync_all())
+        .map_err(|e| ImportError::io(dir, e))
+}
+
+/// Prints an `ImportSummary` of `validators` to stdout as a single line of JSON.
+fn print_json_summary(validators: Vec<ImportedKeystore>) -> Result<(), String> {
+    let summary = ImportSummary {
+        count: validators.len(),
+        total_decrypt_time_ms: total_decrypt_time_ms(&validators),
+        validators,
+    };
+    let json = serde_json::to_string(&summary)
+        .map_err(|e| format!("Unable to encode JSON summary: {:?}", e))?;
+
+    println!("{}", json);
+
+    Ok(())
+}
+
+/// Prints the number of `keystore_paths` found with `--count-only`, or with `json`, a
+/// `DiscoveryReport`.
+///
+/// Only the `json` report reads the keystores, to find their public keys.
+fn print_discovery_report(keystore_paths: &[PathBuf], json: bool) -> Result<(), String> {
+    if !json {
+        println!("Found {} keystores", keystore_paths.len());
+        return Ok(());
+    }
+
+    let report = DiscoveryReport {
+        count: keystore_paths.len(),
+        keystores: keystore_paths
+            .iter()
+            .map(|path| DiscoveredKeystore {
+                path: path.clone(),
+                pubkey: Keystore::from_json_file(path)
+                    .ok()
+                    .map(|keystore| format!("0x{}", keystore.pubkey())),
+            })
+            .collect(),
+    };
+    let json = serde_json::to_string(&report)
+        .map_err(|e| format!("Unable to encode JSON report: {:?}", e))?;
+
+    println!("{}", json);
+
+    Ok(())
+}
+
+/// Prints the time taken to decrypt each of the `validators` and the KDF parameters responsible,
+/// unless none were decrypted.
+///
+/// The total is the sum of the individual times, so it exceeds the wall-clock time of the
+/// password checks when they run in parallel.
+fn print_decrypt_times(validators: &[ImportedKeystore]) {
+    if validators
+        .iter()
+        .all(|validator| validator.decrypt_time_ms.is_none())
+    {
+        return;
+    }
+
+    eprintln!("");
+    eprintln!("Decrypt times:");
+    eprintln!("");
+    for validator in validators {
+        let decrypt_time = validator
+            .decrypt_time_ms
+            .map_or_else(|| "-".to_string(), |time| format!("{} ms", time));
+        eprintln!(
+            " {} {} {}",
+            validator.pubkey, decrypt_time, validator.kdf_params
+        );
+    }
+    eprintln!("");
+    eprintln!(
+        "Total decrypt time: {} ms",
+        total_decrypt_time_ms(validators)
+    );
+}
+
+fn total_decrypt_time_ms(validators: &[ImportedKeystore]) -> u64 {
+    validators
+        .iter()
+        .filter_map(|validator| validator.decrypt_time_ms)
+        .sum()
+}
+
+/// Returns the name of the key derivation function, as it appears in the keystore JSON.
+fn kdf_name(kdf: &Kdf) -> &'static str {
+    match kdf {
+        Kdf::Scrypt(_) => "scrypt",
+        Kdf::Pbkdf2(_) => "pbkdf2",
+    }
+}
+
+/// Returns the cost of the key derivation function, which is the `n` parameter for scrypt and the
+/// `c` parameter for pbkdf2.
+fn kdf_cost(kdf: &Kdf) -> u32 {
+    match kdf {
+        Kdf::Scrypt(params) => params.n,
+        Kdf::Pbkdf2(params) => params.c,
+    }
+}
+
+/// Parses a `--log-level` value, as with the global `--debug-level` flag.
+fn parse_log_level(level: &str) -> Result<Level, String> {
+    match level {
+        "info" => Ok(Level::Info),
+        "debug" => Ok(Level::Debug),
+        "trace" => Ok(Level::Trace),
+        "warn" => Ok(Level::Warning),
+        "error" => Ok(Level::Error),
+        "crit" => Ok(Level::Critical),
+        unknown => Err(format!("Unknown --{}: {}", LOG_LEVEL_FLAG, unknown)),
+    }
+}
+
+/// Copies the validator definitions file to a new backup in the `validator_dir`, then removes the
+/// oldest backups so that at most `max_backups` remain.
+///
+/// The backup is named after the current unix time in nanoseconds. An existing backup is never
+/// overwritten, instead the time is incremented until the name is unused.
+fn backup_definitions_file(
+    validator_dir: &Path,
+    max_backups: Option<usize>,
+) -> Result<PathBuf, ImportError> {
+    let defs_path = validator_dir.join(CONFIG_FILENAME);
+    let defs = fs::read(&defs_path).map_err(|e| ImportError::io(&defs_path, e))?;
+    let mut time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(ImportError::SystemTime)?
+        .as_nanos();
+    let backup = loop {
+        let backup = validator_dir.join(format!("{}{}", BACKUP_PREFIX, time));
+        match create_new_with_600_perms(&backup, &defs) {
+            Ok(()) => break backup,
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => time += 1,
+            Err(e) => return Err(ImportError::io(&backup, e)),
+        }
+    };
+
+    if let Some(max_backups) = max_backups {
+        let mut backups = fs::read_dir(validator_dir)
+            .map_err(|e| ImportError::io(validator_dir, e))?
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let time = path
+                    .file_name()?
+                    .to_str()?
+                    .strip_prefix(BACKUP_PREFIX)?
+                    .parse::<u128>()
+                    .ok()?;
+                Some((time, path))
+            })
+            .collect::<Vec<_>>();
+        backups.sort();
+
+        let num_expired = backups.len().saturating_sub(max_backups.max(1));
+        for (_, path) in backups.into_iter().take(num_expired) {
+            fs::remove_file(&path).map_err(|e| ImportError::io(&path, e))?;
+        }
+    }
+
+    Ok(backup)
+}
+
+/// Writes `bytes` to a new file at `path`, which is only readable by its owner since it may contain
+/// passwords. Fails if `path` already exists.
+fn create_new_with_600_perms(path: &Path, bytes: &[u8]) -> Result<(), io::Error> {
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    set_owner_only_permissions(&file)?;
+    file.write_all(bytes)
+}
+
+/// Returns the modification time of the validator definitions file in `validator_dir`, if it
+/// exists.
+fn definitions_modified(validator_dir: &Path) -> Option<SystemTime> {
+    fs::metadata(validator_dir.join(CONFIG_FILENAME))
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Asks the user to confirm, via stdin, that the validator definitions file should be overwritten
+/// after it was modified by another process. Refuses without asking if not `interactive`.
+fn confirm_overwrite(
+    log: &Logger,
+    validator_dir: &Path,
+    interactive: bool,
+) -> Result<(), ImportError> {
+    warn!(
+        log,
+        "The validator definitions file was modified by another process";
+        "path" => format!("{:?}", validator_dir.join(CONFIG_FILENAME))
+    );
+
+    if !interactive {
+        return Err(ImportError::DefinitionsModified);
+    }
+
+    eprintln!("");
+    eprintln!(
+        "{} has been modified since the import started, possibly by a running validator \
+         client. Continuing will overwrite those changes.",
+        CONFIG_FILENAME
+    );
+    eprintln!("");
+    eprintln!("{}", OVERWRITE_PROMPT);
+
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| ImportError::UserInput(format!("Error reading from stdin: {}", e)))?;
+
+    if input.trim() == "yes" {
+        Ok(())
+    } else {
+        Err(ImportError::DefinitionsModified)
+    }
+}
+
+/// Takes the `DefinitionsLock` on `validator_dir`, failing if another process holds it.
+///
+/// If `force` is `true`, a lock held by another process is ignored with a warning and `None` is
+/// returned.
+fn lock_definitions(
+    log: &Logger,
+    validator_dir: &Path,
+    force: bool,
+) -> Result<Option<DefinitionsLock>, ImportError> {
+    let path = validator_dir.join(LOCKFILE_NAME);
+
+    match DefinitionsLock::try_acquire(validator_dir).map_err(|e| ImportError::io(&path, e))? {
+        Some(lock) => Ok(Some(lock)),
+        None if force => {
+            warn!(
+                log,
+                "Ignoring validator directory lock";
+                "path" => format!("{:?}", path)
+            );
+            Ok(None)
+        }
+        None => Err(ImportError::Locked(path)),
+    }
+}
+
+/// An append-only record of the keystores imported from their source paths, so that an interrupted
+/// import can be resumed.
+///
+/// Each line holds the public key of a keystore (without the `0x` prefix) and its canonical
+/// source path, separated by a space.
+struct Journal {
+    path: PathBuf,
+    file: Option<File>,
+    entries: HashSet<(String, String)>,
+}
+
+impl Journal {
+    /// Reads the journal at `path`, if it exists. If `writable`, the journal is created if
+    /// required and opened for appending.
+    fn open(path: &Path, writable: bool) -> Result<Self, ImportError> {
+        let entries = match fs::read_to_string(path) {
+            Ok(contents) => contents
+                .lines()
+                .filter_map(|line| {
+                    let separator = line.find(' ')?;
+                    let (pubkey, source) = line.split_at(separator);
+                    Some((pubkey.to_string(), source[1..].to_string()))
+                })
+                .collect(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HashSet::new(),
+            Err(e) => return Err(ImportError::io(path, e)),
+        };
+
+        let file = if writable {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| ImportError::io(path, e))?;
+            if let Some(parent) = path.parent().filter(|parent| parent.exists()) {
+                sync_dir(parent)?;
+            }
+            Some(file)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            entries,
+        })
+    }
+
+    /// Returns `true` if the keystore with `pubkey` at `source` has been imported.
+    fn contains(&self, source: &Path, pubkey: &str) -> bool {
+        self.entries
+            .contains(&(pubkey.to_string(), Self::source_key(source)))
+    }
+
+    /// Records the import of the keystore with `pubkey` at `source`, syncing it to disk.
+    fn append(&mut self, source: &Path, pubkey: &str) -> Result<(), ImportError> {
+        let source = Self::source_key(source);
+        if let Some(file) = &mut self.file {
+            writeln!(file, "{} {}", pubkey, source)
+                .and_then(|_| file.sync_data())
+                .map_err(|e| ImportError::io(&self.path, e))?;
+        }
+        self.entries.insert((pubkey.to_string(), source));
+        Ok(())
+    }
+
+    /// Returns the canonical form of `source`, so that it does not depend on the working
+    /// directory.
+    fn source_key(source: &Path) -> String {
+        fs::canonicalize(source)
+            .unwrap_or_else(|_| source.to_path_buf())
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+/// A temporary directory containing the keystores extracted from an archive or bundle.
+///
+/// The keystores are overwritten with zeros before the directory is removed on drop.
+struct ExtractedArchive {
+    dir: TempDir,
+}
+
+impl Drop for ExtractedArchive {
+    fn drop(&mut self) {
+        let mut keystores = vec![];
+        // Links are not followed, so that only files in the directory are overwritten.
+        let _ = recursively_find_voting_keystores(self.dir.path(), false, &mut keystores);
+
+        for keystore in keystores {
+            let _ = fs::metadata(&keystore).and_then(|metadata| {
+                let mut file = OpenOptions::new().write(true).open(&keystore)?;
+                file.write_all(&vec![0; metadata.len() as usize])?;
+                file.sync_all()
+            });
+        }
+    }
+}
+
+/// Extracts the voting keystores from the ZIP archive at `path` into a new temporary directory,
+/// preserving the directory structure within the archive. All other files are ignored.
+fn extract_archive(log: &Logger, path: &Path) -> Result<ExtractedArchive, String> {
+    let mut archive = File::open(path)
+        .map_err(|e| format!("Unable to open archive {:?}: {:?}", path, e))
+        .and_then(|file| {
+            ZipArchive::new(file).map_err(|e| format!("Unable to read archive {:?}: {:?}", path, e))
+        })?;
+
+    let extracted = tempfile::Builder::new()
+        .prefix("lighthouse-import-")
+        .tempdir()
+        .map(|dir| ExtractedArchive { dir })
+        .map_err(|e| format!("Unable to create temporary directory: {:?}", e))?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Unable to read archive {:?}: {:?}", path, e))?;
+
+        // The sanitized name cannot escape the temporary directory.
+        let name = entry.sanitized_name();
+        let is_keystore = name
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .map_or(false, is_voting_keystore);
+
+        if entry.is_dir() || !is_keystore {
+            continue;
+        }
+
+        let dest = extracted.dir.path().join(&name);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Unable to create directory {:?}: {:?}", parent, e))?;
+        }
+        File::create(&dest)
+            .and_then(|mut file| {
+                set_owner_only_permissions(&file)?;
+                io::copy(&mut entry, &mut file)
+            })
+            .map_err(|e| format!("Unable to extract {:?} from archive: {:?}", name, e))?;
+
+        debug!(
+            log,
+            "Extracted keystore from archive";
+            "entry" => format!("{:?}", name),
+            "path" => format!("{:?}", dest)
+        );
+    }
+
+    Ok(extracted)
+}
+
+/// Decrypts the `hardware_bundle::Bundle` at `path` and writes a keystore for each of its keys to
+/// a new temporary directory, returning the directory, the keystore paths and the bundle
+/// password.
+///
+/// The bundle is decrypted with `password` (i.e., from `--password-env`), if supplied. Otherwise,
+/// the password is requested from the user.
+#[cfg(feature = "hardware_bundle")]
+fn unbundle_keystores(
+    log: &Logger,
+    path: &Path,
+    password: Option<ZeroizeString>,
+    stdin_password: bool,
+) -> Result<(ExtractedArchive, Vec<PathBuf>, ZeroizeString), String> {
+    use super::hardware_bundle::{Bundle, Error as BundleError};
+
+    let bundle = Bundle::from_json_file(path)
+        .map_err(|e| format!("Unable to read bundle {:?}: {:?}", path, e))?;
+    let decrypt_error = |e| format!("Unable to decrypt bundle {:?}: {:?}", path, e);
+
+    let (password, keystores) = match password {
+        Some(password) => {
+            let keystores = bundle
+                .decrypt_keystores(password.as_ref())
+                .map_err(decrypt_error)?;
+            (password, keystores)
+        }
+        None => loop {
+            eprintln!("");
+            eprintln!("{}", BUNDLE_PASSWORD_PROMPT);
+
+            let password = read_password_from_user(stdin_password)?;
+
+            match bundle.decrypt_keystores(password.as_ref()) {
+                Ok(keystores) => break (password, keystores),
+                Err(BundleError::Crypto(eth2_keystore::Error::InvalidPassword)) => {
+                    eprintln!("Invalid password");
+                }
+                Err(e) => return Err(decrypt_error(e)),
+            }
+        },
+    };
+
+    let extracted = tempfile::Builder::new()
+        .prefix("lighthouse-import-")
+        .tempdir()
+        .map(|dir| ExtractedArchive { dir })
+        .map_err(|e| format!("Unable to create temporary directory: {:?}", e))?;
+
+    let mut keystore_paths = vec![];
+    for (i, keystore) in keystores.iter().enumerate() {
+        let dir = extracted.dir.path().join(i.to_string());
+        let dest = dir.join(validator_dir::VOTING_KEYSTORE_FILE);
+
+        fs::create_dir(&dir)
+            .and_then(|()| File::create(&dest))
+            .and_then(|file| set_owner_only_permissions(&file).map(|()| file))
+            .map_err(|e| format!("Unable to create {:?}: {:?}", dest, e))
+            .and_then(|file| {
+                keystore
+                    .to_json_writer(file)
+                    .map_err(|e| format!("Unable to write {:?}: {:?}", dest, e))
+            })?;
+
+        debug!(
+            log,
+            "Extracted keystore from bundle";
+            "pubkey" => format!("0x{}", keystore.pubkey()),
+            "path" => format!("{:?}", dest)
+        );
+        keystore_paths.push(dest);
+    }
+
+    Ok((extracted, keystore_paths, password))
+}
+
+/// Returns `true` if the file at `path` is readable by its group or other users.
+#[cfg(unix)]
+fn is_readable_by_others(path: &Path) -> Result<bool, ImportError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::metadata(path)
+        .map(|metadata| metadata.permissions().mode() & 0o044 != 0)
+        .map_err(|e| ImportError::io(path, e))
+}
+
+// TODO: add support for Windows ACLs
+#[cfg(not(unix))]
+fn is_readable_by_others(_path: &Path) -> Result<bool, ImportError> {
+    Ok(false)
+}
+
+/// Sets `file` to readable and writable only by its owner (0600).
+#[cfg(unix)]
+fn set_owner_only_permissions(file: &File) -> Result<(), io::Error> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut perm = file.metadata()?.permissions();
+    perm.set_mode(0o600);
+    file.set_permissions(perm)
+}
+
+// TODO: add support for Windows ACLs
+#[cfg(not(unix))]
+fn set_owner_only_permissions(_file: &File) -> Result<(), io::Error> {
+    Ok(())
+}
+
+/// Returns `true` if the validator with `pubkey` has been imported into `validator_dir`, i.e. it
+/// has a definition in the validator definitions file or a `0x<pubkey>` directory. The import
+/// rejects such a validator as a duplicate, unless `--skip-existing` is supplied.
+///
+/// The `0x` prefix of `pubkey` is optional.
+pub fn is_validator_imported(validator_dir: &Path, pubkey: &str) -> Result<bool, ImportError> {
+    let defs = if validator_dir.join(CONFIG_FILENAME).exists() {
+        ValidatorDefinitions::open(validator_dir).map_err(ImportError::DefinitionsOpen)?
+    } else {
+        ValidatorDefinitions::default()
     };
+    Ok(is_known_pubkey(
+        validator_dir,
+        &defined_pubkeys(&defs),
+        &normalize_pubkey(pubkey),
+    ))
+}
 
-    eprintln!("WARNING: {}", KEYSTORE_REUSE_WARNING);
+/// As `is_validator_imported`, where `known_pubkeys` are the `defined_pubkeys` of the validator
+/// definitions and `pubkey` is normalized.
+fn is_known_pubkey(validator_dir: &Path, known_pubkeys: &HashSet<String>, pubkey: &str) -> bool {
+    known_pubkeys.contains(pubkey) || validator_dir.join(pubkey).exists()
+}
 
-    // For each keystore:
-    //
-    // - Obtain the keystore password, if the user desires.
-    // - Copy the keystore into the `validator_dir`.
-    // - Add the keystore to the validator definitions file.
-    //
-    // Skip keystores that already exist, but exit early if any operation fails.
-    let mut num_imported_keystores = 0;
-    for src_keystore in &keystore_paths {
-        let keystore = Keystore::from_json_file(src_keystore)
-            .map_err(|e| format!("Unable to read keystore JSON {:?}: {:?}", src_keystore, e))?;
+/// Returns the `0x`-prefixed public keys of `defs`.
+fn defined_pubkeys(defs: &ValidatorDefinitions) -> HashSet<String> {
+    defs.as_slice()
+        .iter()
+        .map(|def| def.voting_public_key.to_hex_string())
+        .collect()
+}
 
-        eprintln!("");
-        eprintln!("Keystore found at {:?}:", src_keystore);
-        eprintln!("");
-        eprintln!(" - Public key: 0x{}", keystore.pubkey());
-        eprintln!(" - UUID: {}", keystore.uuid());
-        eprintln!("");
-        eprintln!(
-            "If you enter the password it will be stored as plain-text in {} so that it is not \
-             required each time the validator client starts.",
-            CONFIG_FILENAME
-        );
+/// Returns the `NETWORKS` named in a keystore `description`, as whole words in any case.
+fn network_hints(description: &str) -> Vec<&'static str> {
+    let description = description.to_lowercase();
+    let words = description
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .collect::<HashSet<_>>();
+    NETWORKS
+        .iter()
+        .copied()
+        .filter(|network| words.contains(network))
+        .collect()
+}
+
+/// Reads a password from the tty (or stdin), as `read_password_from_user`. If `timeout` is present
+/// and no password is entered in time, the terminal settings are restored and an error returned.
+///
+/// With a `timeout`, the password is read from the file descriptor of the tty (or stdin) as it
+/// becomes available, so nothing is left waiting for input after a timeout and further passwords
+/// can still be read.
+fn read_password_with_timeout(
+    use_stdin: bool,
+    timeout: Option<Duration>,
+) -> Result<ZeroizeString, ImportError> {
+    match timeout {
+        Some(timeout) => read_password_until(use_stdin, timeout),
+        None => read_password_from_user(use_stdin).map_err(ImportError::UserInput),
+    }
+}
+
+/// The longest password, in bytes, that `read_password_until` accepts.
+#[cfg(unix)]
+const MAX_PASSWORD_LEN: usize = 1024;
+
+/// Reads a line from the tty (or stdin) with echo disabled, or returns `ImportError::PromptTimeout`
+/// if the line is not complete within `timeout`.
+#[cfg(unix)]
+fn read_password_until(use_stdin: bool, timeout: Duration) -> Result<ZeroizeString, ImportError> {
+    use std::os::unix::io::AsRawFd;
+
+    let tty = if use_stdin {
+        None
+    } else {
+        let tty = File::open("/dev/tty")
+            .map_err(|e| ImportError::UserInput(format!("Error reading from tty: {}", e)))?;
+        Some(tty)
+    };
+    let fd = tty
+        .as_ref()
+        .map_or(libc::STDIN_FILENO, |tty| tty.as_raw_fd());
+
+    let terminal = TerminalState::save(fd);
+    if let Some(terminal) = &terminal {
+        terminal.disable_echo();
+    }
+    let result = read_line_until(fd, Instant::now() + timeout);
+    if let Some(terminal) = &terminal {
+        terminal.restore();
+    }
 
-        let password_opt = loop {
+    match result {
+        Ok(Some(password)) => std::str::from_utf8(password.as_bytes())
+            .map(|password| ZeroizeString::from(password.to_string()))
+            .map_err(|_| ImportError::UserInput("Password is not valid UTF-8".to_string())),
+        Ok(None) => {
             eprintln!("");
-            eprintln!("{}", PASSWORD_PROMPT);
+            Err(ImportError::PromptTimeout(timeout))
+        }
+        Err(e) => Err(ImportError::UserInput(format!(
+            "Error reading password: {}",
+            e
+        ))),
+    }
+}
 
-            let password = read_password_from_user(stdin_password)?;
+#[cfg(not(unix))]
+fn read_password_until(_use_stdin: bool, _timeout: Duration) -> Result<ZeroizeString, ImportError> {
+    Err(ImportError::UserInput(format!(
+        "--{} is not supported on this platform",
+        PROMPT_TIMEOUT_FLAG
+    )))
+}
+
+/// Reads bytes from `fd` until a newline (or the end of the input), returning `None` if `deadline`
+/// passes first.
+///
+/// A byte is only read once `poll` reports it is available, one at a time, so that nothing past
+/// the newline is consumed.
+#[cfg(unix)]
+fn read_line_until(fd: libc::c_int, deadline: Instant) -> Result<Option<PlainText>, io::Error> {
+    // A fixed size buffer, so that no copies of the password are left behind by reallocation.
+    let mut line = PlainText::zero(MAX_PASSWORD_LEN);
+    let mut len = 0;
 
-            if password.as_ref().is_empty() {
-                eprintln!("Continuing without password.");
-                sleep(Duration::from_secs(1)); // Provides nicer UX.
-                break None;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let mut pollfd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let millis = remaining.as_millis().min(libc::c_int::max_value() as u128) as libc::c_int;
+        // Safe since `pollfd` is a single, valid `pollfd`.
+        match unsafe { libc::poll(&mut pollfd, 1, millis) } {
+            0 => return Ok(None),
+            n if n < 0 => match io::Error::last_os_error() {
+                e if e.kind() == io::ErrorKind::Interrupted => continue,
+                e => return Err(e),
+            },
+            _ => {}
+        }
+
+        let mut byte = 0u8;
+        // Safe since `byte` is a valid, writable buffer of one byte.
+        match unsafe { libc::read(fd, &mut byte as *mut u8 as *mut libc::c_void, 1) } {
+            0 => break,
+            n if n < 0 => match io::Error::last_os_error() {
+                e if e.kind() == io::ErrorKind::Interrupted => continue,
+                e => return Err(e),
+            },
+            _ if byte == b'\n' => break,
+            _ if len == MAX_PASSWORD_LEN => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("password is longer than {} bytes", MAX_PASSWORD_LEN),
+                ))
+            }
+            _ => {
+                line.as_mut_bytes()[len] = byte;
+                len += 1;
             }
+        }
+    }
 
-            match keystore.decrypt_keypair(password.as_ref()) {
-                Ok(_) => {
-                    eprintln!("Password is correct.");
-                    eprintln!("");
-                    sleep(Duration::from_secs(1)); // Provides nicer UX.
-                    break Some(password);
+    // As `read_password_from_user`, a trailing carriage return is not part of the password.
+    if len > 0 && line.as_bytes()[len - 1] == b'\r' {
+        len -= 1;
+    }
+    Ok(Some(PlainText::from(line.as_bytes()[..len].to_vec())))
+}
+
+/// The settings of the terminal a password is read from.
+#[cfg(unix)]
+struct TerminalState {
+    fd: libc::c_int,
+    termios: libc::termios,
+}
+
+#[cfg(unix)]
+impl TerminalState {
+    /// Returns the current settings of `fd`, if it is a terminal.
+    fn save(fd: libc::c_int) -> Option<Self> {
+        let mut termios = std::mem::MaybeUninit::uninit();
+        // Safe since `fd` is open and `termios` is only read if it was initialized.
+        if unsafe { libc::tcgetattr(fd, termios.as_mut_ptr()) } != 0 {
+            return None;
+        }
+
+        Some(Self {
+            fd,
+            termios: unsafe { termios.assume_init() },
+        })
+    }
+
+    /// Stops the typed characters from being shown, except for the newline, as
+    /// `read_password_from_user`.
+    fn disable_echo(&self) {
+        let mut termios = self.termios;
+        termios.c_lflag &= !(libc::ECHO | libc::ECHOE | libc::ECHOK);
+        termios.c_lflag |= libc::ECHONL;
+        // Safe since `self.fd` is open. If this fails, the password is read with echo.
+        unsafe { libc::tcsetattr(self.fd, libc::TCSANOW, &termios) };
+    }
+
+    /// Restores the saved settings, re-enabling echo.
+    fn restore(&self) {
+        // Safe since `self.fd` is open. Nothing more can be done if this fails.
+        unsafe { libc::tcsetattr(self.fd, libc::TCSANOW, &self.termios) };
+    }
+}
+
+/// Lists the public key and path of each of `keystore_paths`, then returns those chosen by the
+/// user.
+fn select_keystore_paths(keystore_paths: Vec<PathBuf>) -> Result<Vec<PathBuf>, String> {
+    eprintln!("");
+    eprintln!("Found {} keystores:", keystore_paths.len());
+    eprintln!("");
+    for (i, path) in keystore_paths.iter().enumerate() {
+        let keystore = Keystore::from_json_file(path)
+            .map_err(|e| format!("Unable to read keystore JSON {:?}: {:?}", path, e))?;
+        eprintln!(" {}. 0x{} {:?}", i + 1, keystore.pubkey(), path);
+    }
+
+    loop {
+        eprintln!("");
+        eprintln!("{}", SELECTION_PROMPT);
+
+        let mut input = String::new();
+        let bytes_read = io::stdin()
+            .read_line(&mut input)
+            .map_err(|e| format!("Error reading from stdin: {}", e))?;
+        if bytes_read == 0 {
+            return Err("No keystores were selected".to_string());
+        }
+
+        match parse_selection(&input, keystore_paths.len()) {
+            Ok(selected) => {
+                return Ok(keystore_paths
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(i, _)| selected.contains(i))
+                    .map(|(_, path)| path)
+                    .collect())
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
+
+/// Parses a comma-separated list of 1-based keystore numbers (or `all`) into a set of 0-based
+/// indices below `count`.
+fn parse_selection(input: &str, count: usize) -> Result<HashSet<usize>, String> {
+    if input.trim() == "all" {
+        return Ok((0..count).collect());
+    }
+
+    let selected = input
+        .split(',')
+        .map(str::trim)
+        .filter(|number| !number.is_empty())
+        .map(|number| match number.parse::<usize>() {
+            Ok(n) if (1..=count).contains(&n) => Ok(n - 1),
+            _ => Err(format!(
+                "Invalid keystore number {:?}, expected 1 to {}",
+                number, count
+            )),
+        })
+        .collect::<Result<HashSet<_>, _>>()?;
+
+    if selected.is_empty() {
+        Err("No keystores were selected".to_string())
+    } else {
+        Ok(selected)
+    }
+}
+
+/// Reads newline-separated keystore paths from stdin, ignoring empty lines.
+fn read_stdin_paths() -> Result<Vec<PathBuf>, String> {
+    let mut paths = vec![];
+
+    for line in io::stdin().lock().lines() {
+        let line = line.map_err(|e| format!("Unable to read paths from stdin: {:?}", e))?;
+        let path = PathBuf::from(line.trim());
+
+        if line.trim().is_empty() {
+            continue;
+        } else if !path.is_file() {
+            return Err(format!(
+                "Keystore path from stdin is not a file: {:?}",
+                path
+            ));
+        }
+
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
+
+/// Maps normalized keystore public keys (or UUIDs) to passwords, see `password_file_key`.
+pub type Passwords = HashMap<String, ZeroizeString>;
+
+/// Per-keystore values from the `--password-file` which take precedence over the `--graffiti`
+/// and `--suggested-fee-recipient` flags.
+#[derive(Default)]
+pub struct Overrides {
+    pub graffiti: Option<String>,
+    pub suggested_fee_recipient: Option<Address>,
+}
+
+/// A value in a JSON `--password-file`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PasswordFileValue {
+    Password(ZeroizeString),
+    Entry(PasswordFileEntry),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PasswordFileEntry {
+    password: Option<ZeroizeString>,
+    graffiti: Option<String>,
+    suggested_fee_recipient: Option<String>,
+}
+
+/// Reads the file at `path` which maps keystore public keys (or UUIDs) to passwords, returning
+/// the passwords and any `Overrides`.
+///
+/// The file may either be a JSON object (e.g., `{"0xa5e8..": "password"}`) or contain one
+/// `pubkey:password` pair per line. The `0x` prefix on public keys is optional. Only the JSON
+/// object may contain overrides (e.g., `{"0xa5e8..": {"password": "..", "graffiti": ".."}}`).
+///
+/// The contents of the file are zeroized once they have been parsed.
+///
+/// A file encrypted with GPG or age is decrypted first, see `decrypt_password_file`.
+fn read_password_file(
+    path: &Path,
+    use_stdin: bool,
+) -> Result<(Passwords, HashMap<String, Overrides>), String> {
+    let contents = fs::read(path).map(PlainText::from).map_err(|e| {
+        format!(
+            "Unable to read --{} {:?}: {:?}",
+            PASSWORD_FILE_FLAG, path, e
+        )
+    })?;
+    let contents = decrypt_password_file(path, contents, use_stdin)?;
+    let contents_str = std::str::from_utf8(contents.as_bytes())
+        .map_err(|e| format!("--{} is not valid UTF-8: {:?}", PASSWORD_FILE_FLAG, e))?;
+
+    if contents_str.trim_start().starts_with('{') {
+        let map: HashMap<String, PasswordFileValue> = serde_json::from_str(contents_str)
+            .map_err(|e| format!("--{} is not a valid JSON map: {}", PASSWORD_FILE_FLAG, e))?;
+
+        let mut passwords = HashMap::new();
+        let mut overrides = HashMap::new();
+        for (key, value) in map {
+            let key = password_file_key(&key);
+            match value {
+                PasswordFileValue::Password(password) => {
+                    passwords.insert(key, password);
                 }
-                Err(eth2_keystore::Error::InvalidPassword) => {
-                    eprintln!("Invalid password");
+                PasswordFileValue::Entry(entry) => {
+                    let invalid =
+                        |e| format!("Invalid --{} entry for {}: {}", PASSWORD_FILE_FLAG, key, e);
+                    let entry_overrides = Overrides {
+                        graffiti: entry
+                            .graffiti
+                            .as_deref()
+                            .map(parse_graffiti)
+                            .transpose()
+                            .map_err(invalid)?,
+                        suggested_fee_recipient: entry
+                            .suggested_fee_recipient
+                            .as_deref()
+                            .map(parse_fee_recipient)
+                            .transpose()
+                            .map_err(invalid)?,
+                    };
+                    if let Some(password) = entry.password {
+                        passwords.insert(key.clone(), password);
+                    }
+                    overrides.insert(key, entry_overrides);
                 }
-                Err(e) => return Err(format!("Error whilst decrypting keypair: {:?}", e)),
             }
+        }
+
+        Ok((passwords, overrides))
+    } else {
+        contents_str
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                let separator = line.find(':').ok_or_else(|| {
+                    format!(
+                        "Line {} of --{} is not in the form 'pubkey:password'",
+                        i + 1,
+                        PASSWORD_FILE_FLAG
+                    )
+                })?;
+                let (key, password) = line.split_at(separator);
+
+                Ok((
+                    password_file_key(key),
+                    ZeroizeString::from(password[1..].trim_end_matches('\r').to_string()),
+                ))
+            })
+            .collect::<Result<_, String>>()
+            .map(|passwords| (passwords, HashMap::new()))
+    }
+}
+
+/// Returns the decrypted `contents` of the password file at `path`, if it is encrypted with GPG or
+/// age. Otherwise, `contents` is returned unchanged.
+///
+/// The passphrase for a GPG message is prompted for here (or read from stdin if `use_stdin`) and
+/// passed to `gpg`, whereas `age` prompts for its own passphrase on the tty.
+fn decrypt_password_file(
+    path: &Path,
+    contents: PlainText,
+    use_stdin: bool,
+) -> Result<PlainText, String> {
+    let bytes = contents.as_bytes();
+    let start = bytes
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or_else(|| bytes.len());
+    let header = &bytes[start..];
+
+    let (program, args, passphrase): (_, &[&str], _) =
+        if header.starts_with(GPG_ARMOR_HEADER.as_bytes()) {
+            eprintln!("");
+            eprintln!("{}", PASSWORD_FILE_PASSPHRASE_PROMPT);
+            let passphrase = read_password_from_user(use_stdin)?;
+            (
+                "gpg",
+                &[
+                    "--batch",
+                    "--quiet",
+                    "--pinentry-mode",
+                    "loopback",
+                    "--passphrase-fd",
+                    "0",
+                    "--decrypt",
+                ],
+                Some(passphrase),
+            )
+        } else if AGE_HEADERS
+            .iter()
+            .any(|age_header| header.starts_with(age_header.as_bytes()))
+        {
+            ("age", &["--decrypt"], None)
+        } else {
+            return Ok(contents);
         };
 
-        // The keystore is placed in a directory that matches the name of the public key. This
-        // provides some loose protection against adding the same keystore twice.
-        let dest_dir = validator_dir.join(format!("0x{}", keystore.pubkey()));
-        if dest_dir.exists() {
-            eprintln!(
-                "Skipping import of keystore for existing public key: {:?}",
-                src_keystore
+    let decrypt_error = |e| {
+        format!(
+            "Unable to decrypt --{} {:?} with {}: {}",
+            PASSWORD_FILE_FLAG, path, program, e
+        )
+    };
+    let mut child = Command::new(program)
+        .args(args)
+        .arg(path)
+        .stdin(if passphrase.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::inherit()
+        })
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| decrypt_error(format!("{:?}", e)))?;
+    if let (Some(passphrase), Some(mut stdin)) = (passphrase, child.stdin.take()) {
+        stdin
+            .write_all(passphrase.as_ref())
+            .and_then(|()| stdin.write_all(b"\n"))
+            .map_err(|e| decrypt_error(format!("{:?}", e)))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| decrypt_error(format!("{:?}", e)))?;
+    // The decrypted contents are zeroized when dropped, even on failure.
+    let decrypted = PlainText::from(output.stdout);
+
+    if output.status.success() {
+        Ok(decrypted)
+    } else {
+        Err(decrypt_error(output.status.to_string()))
+    }
+}
+
+/// An entry in a `deposit_data.json` file, as written by the deposit CLI. Only the public key is
+/// used.
+#[derive(Deserialize)]
+struct DepositDataEntry {
+    pubkey: String,
+}
+
+/// Reads the `deposit_data.json` file at `path`, returning its normalized public keys (see
+/// `password_file_key`) in order.
+fn read_deposit_data(path: &Path) -> Result<Vec<String>, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Unable to open --{} {:?}: {:?}", DEPOSIT_DATA_FLAG, path, e))?;
+    let entries: Vec<DepositDataEntry> = serde_json::from_reader(file).map_err(|e| {
+        format!(
+            "Unable to parse --{} {:?}: {:?}",
+            DEPOSIT_DATA_FLAG, path, e
+        )
+    })?;
+
+    Ok(entries
+        .iter()
+        .map(|entry| password_file_key(&entry.pubkey))
+        .collect())
+}
+
+/// Returns the keystores in `keystore_paths` with a public key in `deposit_pubkeys`, warning about
+/// any public key without a keystore.
+///
+/// Keystores which cannot be read are retained, so that the error is reported by the import.
+fn filter_deposit_data_keystores(
+    log: &Logger,
+    keystore_paths: Vec<PathBuf>,
+    deposit_pubkeys: &[String],
+) -> Vec<PathBuf> {
+    let mut found_pubkeys = HashSet::new();
+
+    let keystore_paths = keystore_paths
+        .into_iter()
+        .filter(|path| {
+            let keystore = match Keystore::from_json_file(path) {
+                Ok(keystore) => keystore,
+                Err(_) => return true,
+            };
+
+            if deposit_pubkeys
+                .iter()
+                .any(|pubkey| pubkey == keystore.pubkey())
+            {
+                found_pubkeys.insert(keystore.pubkey().to_string());
+                true
+            } else {
+                info!(
+                    log,
+                    "Skipping keystore which is not in the deposit data";
+                    "pubkey" => format!("0x{}", keystore.pubkey()),
+                    "path" => format!("{:?}", path)
+                );
+                false
+            }
+        })
+        .collect();
+
+    for pubkey in deposit_pubkeys {
+        if !found_pubkeys.contains(pubkey) {
+            warn!(
+                log,
+                "No keystore found for the deposit data";
+                "pubkey" => format!("0x{}", pubkey)
             );
-            continue;
         }
+    }
 
-        fs::create_dir_all(&dest_dir)
-            .map_err(|e| format!("Unable to create import directory: {:?}", e))?;
+    keystore_paths
+}
 
-        // Retain the keystore file name, but place it in the new directory.
-        let dest_keystore = src_keystore
-            .file_name()
-            .and_then(|file_name| file_name.to_str())
-            .map(|file_name_str| dest_dir.join(file_name_str))
-            .ok_or_else(|| format!("Badly formatted file name: {:?}", src_keystore))?;
+/// Returns the `keystore_paths` with a size of at most `max_size` bytes, logging a warning for
+/// each keystore which is skipped. Keystores which cannot be read are not skipped, so that the
+/// error is reported by the import.
+fn filter_large_keystores(
+    log: &Logger,
+    keystore_paths: Vec<PathBuf>,
+    max_size: u64,
+) -> Vec<PathBuf> {
+    keystore_paths
+        .into_iter()
+        .filter(|path| match fs::metadata(path) {
+            Ok(metadata) if metadata.len() > max_size => {
+                warn!(
+                    log,
+                    "Skipping keystore larger than --{}", MAX_KEYSTORE_SIZE_FLAG;
+                    "size" => metadata.len(),
+                    "path" => format!("{:?}", path)
+                );
+                false
+            }
+            _ => true,
+        })
+        .collect()
+}
+
+/// Returns the `keystore_paths` which were modified after `since`, logging how many were not.
+///
+/// Keystores whose modification time cannot be read are kept, so that the import reports the
+/// error.
+fn filter_keystores_modified_since(
+    log: &Logger,
+    keystore_paths: Vec<PathBuf>,
+    since: SystemTime,
+) -> Vec<PathBuf> {
+    let num_keystores = keystore_paths.len();
+    let keystore_paths = keystore_paths
+        .into_iter()
+        .filter(|path| {
+            fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .map_or(true, |modified| modified > since)
+        })
+        .collect::<Vec<_>>();
+
+    if keystore_paths.len() < num_keystores {
+        info!(
+            log,
+            "Skipping keystores not modified since --{}", SINCE_FLAG;
+            "count" => num_keystores - keystore_paths.len()
+        );
+    }
+
+    keystore_paths
+}
 
-        // Copy the keystore to the new location.
-        fs::copy(&src_keystore, &dest_keystore)
-            .map_err(|e| format!("Unable to copy keystore: {:?}", e))?;
+/// Parses an RFC 3339 timestamp, e.g. `2020-10-01T12:00:00+02:00`.
+fn parse_rfc3339(timestamp: &str) -> Result<SystemTime, String> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(SystemTime::from)
+        .map_err(|e| e.to_string())
+}
 
-        eprintln!("Successfully imported keystore.");
-        num_imported_keystores += 1;
+/// Reads the EIP-3076 interchange file at `path`, checking that it can be imported into a
+/// database for the network with `genesis_validators_root`.
+fn read_interchange(path: &Path, genesis_validators_root: Hash256) -> Result<Interchange, String> {
+    let file = File::open(path).map_err(|e| {
+        format!(
+            "Unable to open --{} {:?}: {:?}",
+            SLASHING_PROTECTION_FLAG, path, e
+        )
+    })?;
+    let interchange = Interchange::from_json_reader(file).map_err(|e| {
+        format!(
+            "Unable to parse --{} {:?}: {:?}",
+            SLASHING_PROTECTION_FLAG, path, e
+        )
+    })?;
 
-        let validator_def =
-            ValidatorDefinition::new_keystore_with_password(&dest_keystore, password_opt)
-                .map_err(|e| format!("Unable to create new validator definition: {:?}", e))?;
+    interchange
+        .check_compatible(genesis_validators_root)
+        .map_err(|e| match e {
+            e @ InterchangeError::GenesisValidatorsRootMismatch { .. } => {
+                ImportError::IncompatibleInterchange(e).to_string()
+            }
+            e => format!(
+                "Unable to use --{} {:?}: {:?}",
+                SLASHING_PROTECTION_FLAG, path, e
+            ),
+        })?;
 
-        defs.push(validator_def);
+    Ok(interchange)
+}
 
-        defs.save(&validator_dir)
-            .map_err(|e| format!("Unable to save {}: {:?}", CONFIG_FILENAME, e))?;
+/// Adds the records in `interchange` for `pubkeys` to the slashing protection database in the
+/// `validator_dir`, creating it if required. Any of `pubkeys` without a record is registered
+/// without any history.
+fn seed_slashing_protection(
+    log: &Logger,
+    validator_dir: &Path,
+    mut interchange: Interchange,
+    genesis_validators_root: Hash256,
+    pubkeys: &[PublicKey],
+) -> Result<(), ImportError> {
+    interchange
+        .data
+        .retain(|record| pubkeys.contains(&record.pubkey));
 
-        eprintln!("Successfully updated {}.", CONFIG_FILENAME);
+    let num_with_history = interchange.data.len();
+    for pubkey in pubkeys {
+        if !interchange
+            .data
+            .iter()
+            .any(|record| record.pubkey == *pubkey)
+        {
+            interchange.data.push(InterchangeData {
+                pubkey: pubkey.clone(),
+                signed_blocks: vec![],
+                signed_attestations: vec![],
+            });
+        }
     }
 
-    eprintln!("");
-    eprintln!(
-        "Successfully imported {} validators ({} skipped).",
-        num_imported_keystores,
-        keystore_paths.len() - num_imported_keystores
+    let path = validator_dir.join(SLASHING_PROTECTION_FILENAME);
+    let slashing_protection_error = |error| ImportError::SlashingProtection {
+        path: path.clone(),
+        error,
+    };
+    let db =
+        SlashingDatabase::open_or_create(&path).map_err(|e| slashing_protection_error(e.into()))?;
+    db.import_interchange_info(&interchange, genesis_validators_root)
+        .map_err(slashing_protection_error)?;
+
+    info!(
+        log,
+        "Imported slashing protection data";
+        "with_history" => num_with_history,
+        "without_history" => interchange.data.len() - num_with_history,
+        "path" => format!("{:?}", path)
     );
-    eprintln!("");
-    eprintln!("WARNING: {}", KEYSTORE_REUSE_WARNING);
 
     Ok(())
 }
+
+/// Returns the hex-encoded SHA-256 of the keystore file at `path`.
+///
+/// The contents of the file are zeroized once they have been hashed.
+fn keystore_sha256(path: &Path) -> Result<String, ImportError> {
+    fs::read(path)
+        .map(PlainText::from)
+        .map(|bytes| hex::encode(eth2_hashing::hash(bytes.as_bytes())))
+        .map_err(|e| ImportError::io(path, e))
+}
+
+/// Checks that `graffiti` fits in a block.
+fn parse_graffiti(graffiti: &str) -> Result<String, String> {
+    if graffiti.len() > GRAFFITI_BYTES_LEN {
+        Err(format!(
+            "graffiti is {} bytes, the maximum is {}",
+            graffiti.len(),
+            GRAFFITI_BYTES_LEN
+        ))
+    } else {
+        Ok(graffiti.to_string())
+    }
+}
+
+/// Prints a line such as `[17/340] importing 0x...` to stderr.
+fn print_progress(step: usize, total: usize, action: &str, pubkey: &str) {
+    eprintln!("[{}/{}] {} 0x{}", step, total, action, pubkey);
+}
+
+/// Checks that `name` can be used as the name of a single directory in the `validator_dir`.
+fn parse_wallet_name(name: &str) -> Result<String, String> {
+    if name.is_empty() {
+        Err("the name must not be empty".to_string())
+    } else if name.contains('/') || name.contains('\\') {
+        Err("the name must not contain a path separator".to_string())
+    } else if name.starts_with('.') {
+        // Also rejects "." and "..", and avoids clashing with the staging directory.
+        Err("the name must not start with '.'".to_string())
+    } else {
+        Ok(name.to_string())
+    }
+}
+
+/// Parses a 0x-prefixed, 20-byte hex address.
+fn parse_fee_recipient(address: &str) -> Result<Address, String> {
+    let bytes = address
+        .strip_prefix("0x")
+        .ok_or_else(|| "address must be 0x-prefixed".to_string())
+        .and_then(|hex| hex::decode(hex).map_err(|e| format!("address is not hex: {}", e)))?;
+
+    if bytes.len() == Address::len_bytes() {
+        Ok(Address::from_slice(&bytes))
+    } else {
+        Err(format!(
+            "address is {} bytes, expected {}",
+            bytes.len(),
+            Address::len_bytes()
+        ))
+    }
+}
+
+/// Reads a password from the environment variable `var`, then removes the variable so that it is
+/// not inherited by child processes.
+///
+/// The password is copied into a `ZeroizeString`, however the copy held by the environment is not
+/// zeroized.
+fn read_password_env(var: &str) -> Result<ZeroizeString, String> {
+    let password = env::var(var)
+        .map(ZeroizeString::from)
+        .map_err(|e| format!("Unable to read --{} {}: {}", PASSWORD_ENV_FLAG, var, e))?;
+    env::remove_var(var);
+
+    if password.as_ref().is_empty() {
+        Err(format!("--{} {} is empty", PASSWORD_ENV_FLAG, var))
+    } else {
+        Ok(password)
+    }
+}
+
+/// Normalizes a public key or UUID from a password file so it can be compared to the values
+/// returned from `Keystore::pubkey` and `Keystore::uuid`.
+fn password_file_key(key: &str) -> String {
+    key.trim().trim_start_matches("0x").to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use account_utils::eth2_keystore::{
+        json_keystore::{Pbkdf2, Prf},
+        DKLEN,
+    };
+    use slashing_protection::interchange::{InterchangeMetadata, INTERCHANGE_FORMAT_VERSION};
+    use tempfile::tempdir;
+    use types::Keypair;
+
+    const PASSWORD: &str = "cats";
+
+    /// Writes `n` keystores with a cheap KDF to `dir`, returning their paths and public keys.
+    fn write_keystores(dir: &Path, n: usize) -> (Vec<PathBuf>, Vec<String>) {
+        (0..n)
+            .map(|i| {
+                let keystore =
+                    KeystoreBuilder::new(&Keypair::random(), PASSWORD.as_bytes(), "".into())
+                        .unwrap()
+                        .kdf(Kdf::Pbkdf2(Pbkdf2 {
+                            c: 2,
+                            dklen: DKLEN,
+                            prf: Prf::HmacSha256,
+                            salt: vec![42; 32].into(),
+                        }))
+                        .build()
+                        .unwrap();
+                let path = dir.join(format!("keystore-m_12381_3600_{}_0_0-1595406747.json", i));
+                File::create(&path)
+                    .map(|mut file| keystore.to_json_writer(&mut file).unwrap())
+                    .unwrap();
+                (path, keystore.pubkey().to_string())
+            })
+            .unzip()
+    }
+
+    fn config(keystore_paths: Vec<PathBuf>, validator_dir: &Path) -> ImportConfig {
+        let mut config = ImportConfig::new(
+            keystore_paths,
+            validator_dir.to_path_buf(),
+            Logger::root(slog::Discard, o!()),
+        );
+        config.default_password = Some(ZeroizeString::from(PASSWORD.to_string()));
+        config
+    }
+
+    #[test]
+    fn import_keystores_with_passwords() {
+        let src_dir = tempdir().unwrap();
+        let dst_dir = tempdir().unwrap();
+        let (paths, pubkeys) = write_keystores(src_dir.path(), 2);
+
+        let report = import_keystores(config(paths, dst_dir.path())).unwrap();
+        assert_eq!(report.num_imported(), 2);
+        assert_eq!(report.num_failed(), 0);
+        assert!(report
+            .outcomes
+            .iter()
+            .all(|report| matches!(report.outcome, KeystoreOutcome::Imported)));
+
+        let defs = ValidatorDefinitions::open(dst_dir.path()).unwrap();
+        assert_eq!(defs.as_slice().len(), 2);
+        for (pubkey, imported) in pubkeys.iter().zip(&report.imported) {
+            assert_eq!(imported.pubkey, format!("0x{}", pubkey));
+            assert!(imported.password_saved);
+            assert!(imported.dest_path.exists());
+        }
+        assert!(
+            DefinitionsLock::try_acquire(dst_dir.path())
+                .unwrap()
+                .is_some(),
+            "the lock should be released after the import"
+        );
+    }
+
+    #[test]
+    fn import_keystores_events() {
+        let src_dir = tempdir().unwrap();
+        let dst_dir = tempdir().unwrap();
+        let (paths, pubkeys) = write_keystores(src_dir.path(), 2);
+
+        let mut events = vec![];
+        let report = import_keystores_with_events(config(paths.clone(), dst_dir.path()), |event| {
+            events.push(event)
+        })
+        .unwrap();
+        assert_eq!(report.num_imported(), 2);
+
+        let expected = paths
+            .iter()
+            .zip(&pubkeys)
+            .zip(&report.imported)
+            .enumerate()
+            .flat_map(|(index, ((path, pubkey), imported))| {
+                let pubkey = format!("0x{}", pubkey);
+                vec![
+                    ImportEvent::Started {
+                        index,
+                        total: 2,
+                        path: path.clone(),
+                        pubkey: pubkey.clone(),
+                    },
+                    ImportEvent::DecryptOk {
+                        pubkey: pubkey.clone(),
+                    },
+                    ImportEvent::Moved {
+                        pubkey: pubkey.clone(),
+                        path: imported.dest_path.clone(),
+                    },
+                    ImportEvent::DefinitionSaved { pubkey },
+                ]
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(events, expected);
+    }
+
+    #[test]
+    fn import_keystores_disabled() {
+        let src_dir = tempdir().unwrap();
+        let dst_dir = tempdir().unwrap();
+        let (paths, _) = write_keystores(src_dir.path(), 2);
+
+        let mut config = config(paths, dst_dir.path());
+        config.import_disabled = true;
+        let report = import_keystores(config).unwrap();
+        assert_eq!(report.num_imported(), 2);
+        assert!(report.imported.iter().all(|imported| !imported.enabled));
+
+        let defs = ValidatorDefinitions::open(dst_dir.path()).unwrap();
+        assert_eq!(defs.as_slice().len(), 2);
+        assert!(defs.as_slice().iter().all(|def| !def.enabled));
+    }
+
+    #[test]
+    fn import_keystores_dry_run() {
+        let src_dir = tempdir().unwrap();
+        let dst_dir = tempdir().unwrap();
+        let validator_dir = dst_dir.path().join("validators");
+        let (paths, _) = write_keystores(src_dir.path(), 2);
+
+        let mut config = config(paths, &validator_dir);
+        config.dry_run = true;
+        let report = import_keystores(config).unwrap();
+        assert_eq!(report.num_imported(), 2);
+        assert!(report
+            .outcomes
+            .iter()
+            .all(|report| matches!(report.outcome, KeystoreOutcome::WouldImport)));
+        assert!(!validator_dir.exists());
+    }
+
+    #[test]
+    fn import_keystores_duplicate_pubkeys() {
+        let src_dir = tempdir().unwrap();
+        let dst_dir = tempdir().unwrap();
+        let (mut paths, pubkeys) = write_keystores(src_dir.path(), 1);
+        let copy = src_dir.path().join("keystore-copy.json");
+        fs::copy(&paths[0], &copy).unwrap();
+        paths.push(copy);
+
+        match import_keystores(config(paths, dst_dir.path())) {
+            Err(ImportError::DuplicatePubkeys(duplicates)) => {
+                assert_eq!(duplicates, vec![format!("0x{}", pubkeys[0])])
+            }
+            _ => panic!("duplicate public keys should be rejected"),
+        }
+        assert!(!dst_dir.path().join(format!("0x{}", pubkeys[0])).exists());
+    }
+
+    #[test]
+    fn validator_imported() {
+        let src_dir = tempdir().unwrap();
+        let dst_dir = tempdir().unwrap();
+        let (paths, pubkeys) = write_keystores(src_dir.path(), 3);
+
+        // Nothing is imported, and no definitions file is created.
+        assert!(!is_validator_imported(dst_dir.path(), &pubkeys[0]).unwrap());
+        assert!(!dst_dir.path().join(CONFIG_FILENAME).exists());
+
+        import_keystores(config(vec![paths[0].clone()], dst_dir.path())).unwrap();
+        assert!(is_validator_imported(dst_dir.path(), &pubkeys[0]).unwrap());
+        assert!(
+            is_validator_imported(dst_dir.path(), &format!("0x{}", pubkeys[0].to_uppercase()))
+                .unwrap()
+        );
+        assert!(!is_validator_imported(dst_dir.path(), &pubkeys[1]).unwrap());
+
+        // A definition without a directory, or a directory without a definition, is imported.
+        fs::remove_dir_all(dst_dir.path().join(format!("0x{}", pubkeys[0]))).unwrap();
+        assert!(is_validator_imported(dst_dir.path(), &pubkeys[0]).unwrap());
+        fs::create_dir(dst_dir.path().join(format!("0x{}", pubkeys[1]))).unwrap();
+        assert!(is_validator_imported(dst_dir.path(), &pubkeys[1]).unwrap());
+
+        // The import agrees, and rejects the directory as a duplicate.
+        match import_keystores(config(vec![paths[1].clone()], dst_dir.path())) {
+            Err(ImportError::DuplicatePubkeys(duplicates)) => {
+                assert_eq!(duplicates, vec![format!("0x{}", pubkeys[1])])
+            }
+            _ => panic!("an imported validator should be rejected"),
+        }
+
+        // Keystores imported into a wallet directory are found by their definitions.
+        let mut wallet_config = config(vec![paths[2].clone()], dst_dir.path());
+        wallet_config.wallet_name = Some("wallet".into());
+        import_keystores(wallet_config).unwrap();
+        assert!(is_validator_imported(dst_dir.path(), &pubkeys[2]).unwrap());
+    }
+
+    #[test]
+    fn import_keystores_missing_passwords() {
+        let src_dir = tempdir().unwrap();
+        let dst_dir = tempdir().unwrap();
+        let (paths, pubkeys) = write_keystores(src_dir.path(), 2);
+
+        // Only the first keystore has a password, and there are no prompts.
+        let mut config = config(paths.clone(), dst_dir.path());
+        config.default_password = None;
+        config.passwords.insert(
+            pubkeys[0].clone(),
+            ZeroizeString::from(PASSWORD.to_string()),
+        );
+
+        match import_keystores(config) {
+            Err(ImportError::MissingPasswords(missing)) => {
+                assert_eq!(missing, vec![paths[1].clone()])
+            }
+            _ => panic!("keystores without a password should be rejected"),
+        }
+        assert!(!dst_dir.path().join(format!("0x{}", pubkeys[0])).exists());
+    }
+
+    #[test]
+    fn import_keystores_keep_going() {
+        let src_dir = tempdir().unwrap();
+        let (paths, _) = write_keystores(src_dir.path(), 2);
+        let failing_uuid = Keystore::from_json_file(&paths[0])
+            .unwrap()
+            .uuid()
+            .to_string();
+
+        // A file in the place of the first keystore's staging directory cannot be removed.
+        let import = |keep_going: bool| {
+            let dst_dir = tempdir().unwrap();
+            let staging_dir = dst_dir.path().join(STAGING_DIR);
+            fs::create_dir_all(&staging_dir).unwrap();
+            fs::write(staging_dir.join(&failing_uuid), "").unwrap();
+
+            let mut config = config(paths.clone(), dst_dir.path());
+            config.keep_going = keep_going;
+            import_keystores(config)
+        };
+
+        match import(false) {
+            Err(ImportError::Io { path, .. }) => assert!(path.ends_with(&failing_uuid)),
+            _ => panic!("the import should stop at the failure"),
+        }
+
+        let report = import(true).unwrap();
+        assert_eq!(report.num_failed(), 1);
+        assert_eq!(report.num_imported(), 1);
+        assert!(matches!(
+            report.outcomes[0].outcome,
+            KeystoreOutcome::Failed(ImportError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn import_keystores_removes_stale_staging_dirs() {
+        let src_dir = tempdir().unwrap();
+        let dst_dir = tempdir().unwrap();
+        let (paths, _) = write_keystores(src_dir.path(), 1);
+
+        let stale_dir = dst_dir.path().join(STAGING_DIR).join("interrupted");
+        fs::create_dir_all(&stale_dir).unwrap();
+        fs::write(stale_dir.join("keystore.json"), "").unwrap();
+
+        let report = import_keystores(config(paths, dst_dir.path())).unwrap();
+        assert_eq!(report.num_imported(), 1);
+        assert!(!stale_dir.exists());
+    }
+
+    #[test]
+    fn import_keystores_locked() {
+        let src_dir = tempdir().unwrap();
+        let dst_dir = tempdir().unwrap();
+        let (paths, _) = write_keystores(src_dir.path(), 1);
+        let lock = DefinitionsLock::try_acquire(dst_dir.path())
+            .unwrap()
+            .unwrap();
+
+        match import_keystores(config(paths.clone(), dst_dir.path())) {
+            Err(ImportError::Locked(path)) => {
+                assert_eq!(path, dst_dir.path().join(LOCKFILE_NAME))
+            }
+            _ => panic!("a locked validator directory should be rejected"),
+        }
+
+        let mut forced_config = config(paths.clone(), dst_dir.path());
+        forced_config.force = true;
+        assert_eq!(import_keystores(forced_config).unwrap().num_imported(), 1);
+
+        // A lockfile left behind after the lock is released does not lock the directory.
+        drop(lock);
+        let other_dir = tempdir().unwrap();
+        let (other_paths, _) = write_keystores(other_dir.path(), 1);
+        assert_eq!(
+            import_keystores(config(other_paths, dst_dir.path()))
+                .unwrap()
+                .num_imported(),
+            1
+        );
+    }
+
+    #[test]
+    fn import_keystores_incompatible_interchange() {
+        let src_dir = tempdir().unwrap();
+        let dst_dir = tempdir().unwrap();
+        let validator_dir = dst_dir.path().join("validators");
+        let (paths, _) = write_keystores(src_dir.path(), 1);
+
+        let interchange = Interchange {
+            metadata: InterchangeMetadata {
+                interchange_format_version: INTERCHANGE_FORMAT_VERSION.to_string(),
+                genesis_validators_root: Hash256::repeat_byte(1),
+            },
+            data: vec![],
+        };
+        let mut config = config(paths, &validator_dir);
+        config.slashing_protection = Some((interchange, Hash256::repeat_byte(2)));
+
+        match import_keystores(config) {
+            Err(e @ ImportError::IncompatibleInterchange(_)) => {
+                let message = e.to_string();
+                assert!(message.contains(&format!("{:?}", Hash256::repeat_byte(1))));
+                assert!(message.contains(&format!("{:?}", Hash256::repeat_byte(2))));
+            }
+            _ => panic!("an interchange for another network should be rejected"),
+        }
+        assert!(!validator_dir.exists());
+    }
+
+    #[test]
+    fn keystores_modified_since() {
+        let src_dir = tempdir().unwrap();
+        let (paths, _) = write_keystores(src_dir.path(), 2);
+        let log = Logger::root(slog::Discard, o!());
+
+        let before = parse_rfc3339("2020-01-01T00:00:00+01:00").unwrap();
+        assert_eq!(before, UNIX_EPOCH + Duration::from_secs(1_577_833_200));
+        assert_eq!(
+            filter_keystores_modified_since(&log, paths.clone(), before),
+            paths
+        );
+
+        let after = SystemTime::now() + Duration::from_secs(3600);
+        assert!(filter_keystores_modified_since(&log, paths, after).is_empty());
+
+        assert!(parse_rfc3339("2020-01-01").is_err());
+    }
+
+    #[test]
+    fn backup_definitions_file_is_unique() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join(CONFIG_FILENAME), "definitions").unwrap();
+
+        // Backups taken in quick succession must not overwrite each other.
+        let backups = (0..10)
+            .map(|_| backup_definitions_file(dir.path(), None).unwrap())
+            .collect::<HashSet<_>>();
+        assert_eq!(backups.len(), 10);
+        for backup in &backups {
+            assert_eq!(fs::read(backup).unwrap(), b"definitions");
+        }
+
+        backup_definitions_file(dir.path(), Some(3)).unwrap();
+        let remaining = fs::read_dir(dir.path())
+            .unwrap()
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .unwrap()
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(BACKUP_PREFIX)
+            })
+            .count();
+        assert_eq!(remaining, 3);
+    }
+}
@@ -1,9 +1,14 @@
 use crate::VALIDATOR_DIR_FLAG;
+use account_utils::validator_definitions::{
+    SigningDefinition, ValidatorDefinitions, CONFIG_FILENAME,
+};
 use clap::{App, Arg, ArgMatches};
+use serde_derive::Serialize;
 use std::path::PathBuf;
-use validator_dir::Manager as ValidatorManager;
+use validator_dir::{Manager as ValidatorManager, VOTING_KEYSTORE_FILE};
 
 pub const CMD: &str = "list";
+pub const JSON_FLAG: &str = "json";
 
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
     App::new(CMD)
@@ -17,7 +22,31 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 )
                 .takes_value(true),
         )
-        .about("Lists the names of all validators.")
+        .arg(
+            Arg::with_name(JSON_FLAG)
+                .long(JSON_FLAG)
+                .help("If present, print the validators as a JSON array.")
+                .takes_value(false),
+        )
+        .about(
+            "Lists all validators in the validator definitions file, followed by any validator \
+            directories which are not in the file (e.g., those made by `validator create`).",
+        )
+}
+
+/// A validator from the validator definitions file or the validator directory, as printed by
+/// `list --json`.
+#[derive(Serialize)]
+pub struct ListedValidator {
+    /// For a validator which is not defined, the name of its directory.
+    pub pubkey: String,
+    /// `true` if the validator is in the validator definitions file.
+    pub defined: bool,
+    /// Always `false` for a validator which is not defined.
+    pub enabled: bool,
+    pub keystore_path: PathBuf,
+    pub keystore_exists: bool,
+    pub password_stored: bool,
 }
 
 pub fn cli_run(matches: &ArgMatches<'_>) -> Result<(), String> {
@@ -26,15 +55,92 @@ pub fn cli_run(matches: &ArgMatches<'_>) -> Result<(), String> {
         VALIDATOR_DIR_FLAG,
         PathBuf::new().join(".lighthouse").join("validators"),
     )?;
+    let json = matches.is_present(JSON_FLAG);
+
+    // Listing must not create the definitions file, so a missing file is treated as empty.
+    let defs = if data_dir.join(CONFIG_FILENAME).exists() {
+        ValidatorDefinitions::open(&data_dir)
+            .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?
+    } else {
+        ValidatorDefinitions::default()
+    };
+
+    let mut validators = defs
+        .as_slice()
+        .iter()
+        .map(|def| match &def.signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                voting_keystore_password_path,
+                voting_keystore_password,
+            } => ListedValidator {
+                pubkey: def.voting_public_key.to_hex_string(),
+                defined: true,
+                enabled: def.enabled,
+                keystore_path: voting_keystore_path.clone(),
+                keystore_exists: voting_keystore_path.exists(),
+                password_stored: voting_keystore_password.is_some()
+                    || voting_keystore_password_path.is_some(),
+            },
+        })
+        .collect::<Vec<_>>();
 
-    let mgr = ValidatorManager::open(&data_dir)
+    // Validator directories (e.g., from `validator create`) which contain none of the defined
+    // keystores. Hidden directories, such as the staging directory of an import, are skipped.
+    let dir_names = ValidatorManager::open(&data_dir)
+        .and_then(|mgr| mgr.directory_names())
         .map_err(|e| format!("Unable to read --{}: {:?}", VALIDATOR_DIR_FLAG, e))?;
+    for (name, path) in dir_names {
+        let is_defined = validators
+            .iter()
+            .any(|validator| validator.defined && validator.keystore_path.starts_with(&path));
+        if name.starts_with('.') || is_defined {
+            continue;
+        }
+
+        let keystore_path = path.join(VOTING_KEYSTORE_FILE);
+        validators.push(ListedValidator {
+            pubkey: name,
+            defined: false,
+            enabled: false,
+            keystore_exists: keystore_path.exists(),
+            keystore_path,
+            password_stored: false,
+        });
+    }
+
+    if json {
+        let json = serde_json::to_string(&validators)
+            .map_err(|e| format!("Unable to encode JSON: {:?}", e))?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    for validator in &validators {
+        println!("{}", validator.pubkey);
+        if !validator.defined {
+            println!(" - Not in {}", CONFIG_FILENAME);
+            continue;
+        }
+        println!(" - Enabled: {}", validator.enabled);
+        println!(" - Keystore: {:?}", validator.keystore_path);
+        println!(" - Password stored: {}", validator.password_stored);
+        if !validator.keystore_exists {
+            println!(" - WARNING: The keystore file does not exist.");
+        }
+    }
 
-    for (name, _path) in mgr
-        .directory_names()
-        .map_err(|e| format!("Unable to list wallets: {:?}", e))?
-    {
-        println!("{}", name)
+    let num_missing = validators
+        .iter()
+        .filter(|v| v.defined && !v.keystore_exists)
+        .count();
+    if num_missing > 0 {
+        eprintln!(
+            "WARNING: {} of {} validators in {} have a missing keystore file.",
+            num_missing,
+            validators.iter().filter(|v| v.defined).count(),
+            CONFIG_FILENAME
+        );
     }
 
     Ok(())
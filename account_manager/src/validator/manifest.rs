@@ -0,0 +1,86 @@
+//! A record of the SHA-256 checksum of each file written by `validator import --manifest`, which
+//! `validator verify --manifest` checks the files against.
+
+use account_utils::PlainText;
+use serde_derive::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize, Deserialize)]
+pub struct Manifest {
+    /// The imported keystores.
+    pub keystores: Vec<ManifestEntry>,
+    /// The validator definitions file, as it was saved by the import.
+    pub definitions: ManifestEntry,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub path: PathBuf,
+    /// The hex-encoded SHA-256 of the file, without a `0x` prefix.
+    pub sha256: String,
+}
+
+impl ManifestEntry {
+    /// Returns an entry with the current checksum of the file at `path`.
+    pub fn new(path: PathBuf) -> Result<Self, String> {
+        let sha256 = file_sha256(&path)?;
+        Ok(Self { path, sha256 })
+    }
+
+    /// Checks that the file at `self.path` still has the checksum `self.sha256`.
+    pub fn check(&self) -> Result<(), String> {
+        let sha256 = file_sha256(&self.path)?;
+        if sha256 == self.sha256 {
+            Ok(())
+        } else {
+            Err(format!(
+                "Checksum is 0x{}, expected 0x{}",
+                sha256, self.sha256
+            ))
+        }
+    }
+}
+
+impl Manifest {
+    /// Returns a manifest with the current checksums of `keystore_paths` and `definitions_path`.
+    pub fn new(keystore_paths: &[PathBuf], definitions_path: PathBuf) -> Result<Self, String> {
+        Ok(Self {
+            keystores: keystore_paths
+                .iter()
+                .cloned()
+                .map(ManifestEntry::new)
+                .collect::<Result<_, _>>()?,
+            definitions: ManifestEntry::new(definitions_path)?,
+        })
+    }
+
+    pub fn from_json_file(path: &Path) -> Result<Self, String> {
+        File::open(path)
+            .map_err(|e| format!("Unable to open manifest {:?}: {:?}", path, e))
+            .and_then(|file| {
+                serde_json::from_reader(file)
+                    .map_err(|e| format!("Unable to parse manifest {:?}: {:?}", path, e))
+            })
+    }
+
+    pub fn to_json_file(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Unable to encode manifest: {:?}", e))?;
+        fs::write(path, json).map_err(|e| format!("Unable to write manifest {:?}: {:?}", path, e))
+    }
+
+    /// Returns every entry, keystores first.
+    pub fn entries(&self) -> impl Iterator<Item = &ManifestEntry> {
+        self.keystores
+            .iter()
+            .chain(std::iter::once(&self.definitions))
+    }
+}
+
+fn file_sha256(path: &Path) -> Result<String, String> {
+    fs::read(path)
+        .map(PlainText::from)
+        .map(|bytes| hex::encode(eth2_hashing::hash(bytes.as_bytes())))
+        .map_err(|e| format!("Unable to read {:?}: {:?}", path, e))
+}
@@ -1,9 +1,18 @@
 pub mod create;
 pub mod deposit;
+pub mod enable;
+pub mod export;
+pub mod hardware_bundle;
 pub mod import;
 pub mod list;
+pub mod manifest;
+pub mod modify;
+pub mod recover;
+pub mod reharden;
+pub mod slashing_protection;
+pub mod verify;
 
-use crate::common::base_wallet_dir;
+use crate::{common::base_wallet_dir, ExitError};
 use clap::{App, Arg, ArgMatches};
 use environment::Environment;
 use types::EthSpec;
@@ -22,21 +31,40 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         )
         .subcommand(create::cli_app())
         .subcommand(deposit::cli_app())
+        .subcommand(enable::cli_app(true))
+        .subcommand(enable::cli_app(false))
+        .subcommand(export::cli_app())
         .subcommand(import::cli_app())
         .subcommand(list::cli_app())
+        .subcommand(modify::cli_app())
+        .subcommand(recover::cli_app())
+        .subcommand(reharden::cli_app())
+        .subcommand(slashing_protection::cli_app())
+        .subcommand(verify::cli_app())
 }
 
-pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<(), String> {
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<(), ExitError> {
     let base_wallet_dir = base_wallet_dir(matches, "base-dir")?;
 
     match matches.subcommand() {
-        (create::CMD, Some(matches)) => create::cli_run::<T>(matches, env, base_wallet_dir),
-        (deposit::CMD, Some(matches)) => deposit::cli_run::<T>(matches, env),
-        (import::CMD, Some(matches)) => import::cli_run(matches),
-        (list::CMD, Some(matches)) => list::cli_run(matches),
-        (unknown, _) => Err(format!(
-            "{} does not have a {} command. See --help",
-            CMD, unknown
-        )),
+        (create::CMD, Some(matches)) => create::cli_run::<T>(matches, env, base_wallet_dir)?,
+        (deposit::CMD, Some(matches)) => deposit::cli_run::<T>(matches, env)?,
+        (enable::ENABLE_CMD, Some(matches)) => enable::cli_run(matches, true)?,
+        (enable::DISABLE_CMD, Some(matches)) => enable::cli_run(matches, false)?,
+        (export::CMD, Some(matches)) => export::cli_run(matches)?,
+        (import::CMD, Some(matches)) => import::cli_run::<T>(matches, env)?,
+        (list::CMD, Some(matches)) => list::cli_run(matches)?,
+        (modify::CMD, Some(matches)) => modify::cli_run(matches)?,
+        (recover::CMD, Some(matches)) => recover::cli_run::<T>(matches, env)?,
+        (reharden::CMD, Some(matches)) => reharden::cli_run(matches)?,
+        (slashing_protection::CMD, Some(matches)) => {
+            slashing_protection::cli_run::<T>(matches, env)?
+        }
+        (verify::CMD, Some(matches)) => verify::cli_run(matches)?,
+        (unknown, _) => {
+            return Err(format!("{} does not have a {} command. See --help", CMD, unknown).into())
+        }
     }
+
+    Ok(())
 }
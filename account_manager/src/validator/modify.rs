@@ -0,0 +1,132 @@
+use crate::{common::lock_validator_dir, VALIDATOR_DIR_FLAG};
+use account_utils::{
+    eth2_keystore::Keystore,
+    read_password_from_user,
+    validator_definitions::{SigningDefinition, ValidatorDefinitions, CONFIG_FILENAME},
+};
+use clap::{App, Arg, ArgMatches};
+use std::path::PathBuf;
+
+pub const CMD: &str = "modify";
+pub const PUBKEY_FLAG: &str = "pubkey";
+pub const ALL_FLAG: &str = "all";
+pub const STDIN_PASSWORD_FLAG: &str = "stdin-passwords";
+
+pub const PASSWORD_PROMPT: &str = "Enter the new keystore password:";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Updates the password stored in the validator definitions file, for keystores \
+            whose password was changed externally. Each new password is checked against the \
+            keystore before it is stored.",
+        )
+        .arg(
+            Arg::with_name(PUBKEY_FLAG)
+                .long(PUBKEY_FLAG)
+                .value_name("PUBKEY")
+                .help("The public key of the validator to modify.")
+                .takes_value(true)
+                .required_unless(ALL_FLAG),
+        )
+        .arg(
+            Arg::with_name(ALL_FLAG)
+                .long(ALL_FLAG)
+                .help("If present, modify every validator in the validator definitions file.")
+                .conflicts_with(PUBKEY_FLAG)
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_DIR_FLAG)
+                .long(VALIDATOR_DIR_FLAG)
+                .value_name("VALIDATOR_DIRECTORY")
+                .help(
+                    "The path to search for validator directories. \
+                    Defaults to ~/.lighthouse/validators",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STDIN_PASSWORD_FLAG)
+                .long(STDIN_PASSWORD_FLAG)
+                .help("If present, read passwords from stdin instead of tty."),
+        )
+}
+
+pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
+    let validator_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        VALIDATOR_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("validators"),
+    )?;
+    let pubkey = matches.value_of(PUBKEY_FLAG);
+    let stdin_password = matches.is_present(STDIN_PASSWORD_FLAG);
+
+    let _lock = lock_validator_dir(&validator_dir)?;
+    let mut defs = ValidatorDefinitions::open(&validator_dir)
+        .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?;
+
+    let mut num_modified = 0;
+
+    let selected = defs
+        .select_mut(pubkey)
+        .map_err(|e| format!("Unable to select validators: {:?}", e))?;
+
+    for def in selected {
+        let def_pubkey = def.voting_public_key.to_hex_string();
+
+        match &mut def.signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                voting_keystore_password_path,
+                voting_keystore_password,
+            } => {
+                let keystore = Keystore::from_json_file(&voting_keystore_path)
+                    .map_err(|e| format!("Unable to read {:?}: {:?}", voting_keystore_path, e))?;
+
+                eprintln!("");
+                eprintln!("Keystore found at {:?}:", voting_keystore_path);
+                eprintln!("");
+                eprintln!(" - Public key: {}", def_pubkey);
+                eprintln!(" - UUID: {}", keystore.uuid());
+
+                let password = loop {
+                    eprintln!("");
+                    eprintln!("{}", PASSWORD_PROMPT);
+
+                    let password = read_password_from_user(stdin_password)?;
+
+                    if password.as_ref().is_empty() {
+                        return Err(format!("No password entered for {}", def_pubkey));
+                    }
+
+                    match keystore.decrypt_keypair(password.as_ref()) {
+                        Ok(_) => {
+                            eprintln!("Password is correct.");
+                            break password;
+                        }
+                        Err(eth2_keystore::Error::InvalidPassword) => {
+                            eprintln!("Invalid password");
+                        }
+                        Err(e) => return Err(format!("Error whilst decrypting keypair: {:?}", e)),
+                    }
+                };
+
+                // The stored password takes precedence in the validator client, so a password
+                // path would no longer be used.
+                *voting_keystore_password_path = None;
+                *voting_keystore_password = Some(password);
+            }
+        }
+
+        num_modified += 1;
+    }
+
+    defs.save(&validator_dir)
+        .map_err(|e| format!("Unable to save {}: {:?}", CONFIG_FILENAME, e))?;
+
+    eprintln!("");
+    eprintln!("Successfully modified {} validators.", num_modified);
+
+    Ok(())
+}
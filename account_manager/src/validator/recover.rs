@@ -0,0 +1,195 @@
+use super::import::{write_keystore_file, write_staged};
+use crate::{
+    common::{ensure_dir_exists, lock_validator_dir, read_new_password},
+    ALLOW_WEAK_PASSWORD_FLAG, VALIDATOR_DIR_FLAG,
+};
+use account_utils::{
+    eth2_keystore::{keypair_from_secret, KeystoreBuilder},
+    validator_definitions::{ValidatorDefinition, ValidatorDefinitions, CONFIG_FILENAME},
+    ZeroizeString,
+};
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use eth2_wallet::{
+    bip39::{Language, Mnemonic, Seed as Bip39Seed},
+    DerivedKey, KeyType, ValidatorPath,
+};
+use std::fs;
+use std::path::PathBuf;
+use types::EthSpec;
+use validator_dir::VOTING_KEYSTORE_FILE;
+
+pub const CMD: &str = "recover";
+pub const MNEMONIC_FLAG: &str = "mnemonic-file";
+pub const COUNT_FLAG: &str = "count";
+pub const FIRST_INDEX_FLAG: &str = "first-index";
+pub const STDIN_PASSWORD_FLAG: &str = "stdin-passwords";
+
+pub const PASSWORD_PROMPT: &str = "Enter a password for the recovered keystores:";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Recovers EIP-2335 voting keystores from a BIP-39 mnemonic using the EIP-2334 \
+            derivation path and imports them into a Lighthouse VC directory. Withdrawal \
+            keystores and deposit data are not created.",
+        )
+        .arg(
+            Arg::with_name(MNEMONIC_FLAG)
+                .long(MNEMONIC_FLAG)
+                .value_name("MNEMONIC_PATH")
+                .help("Path to a file containing the BIP-39 mnemonic. DO NOT SHARE THE MNEMONIC.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(COUNT_FLAG)
+                .long(COUNT_FLAG)
+                .value_name("VALIDATOR_COUNT")
+                .help("The number of validators to recover.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(FIRST_INDEX_FLAG)
+                .long(FIRST_INDEX_FLAG)
+                .value_name("FIRST_INDEX")
+                .help("The index of the first validator to recover. Defaults to 0.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_DIR_FLAG)
+                .long(VALIDATOR_DIR_FLAG)
+                .value_name("VALIDATOR_DIRECTORY")
+                .help(
+                    "The path where the validator directories will be created. \
+                    Defaults to ~/.lighthouse/validators",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STDIN_PASSWORD_FLAG)
+                .long(STDIN_PASSWORD_FLAG)
+                .help("If present, read passwords from stdin instead of tty."),
+        )
+        .arg(
+            Arg::with_name(ALLOW_WEAK_PASSWORD_FLAG)
+                .long(ALLOW_WEAK_PASSWORD_FLAG)
+                .help(
+                    "If present, do not check the strength of the new password for the \
+                    recovered keystores.",
+                )
+                .takes_value(false),
+        )
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<(), String> {
+    let log = env.core_context().log().clone();
+    let mnemonic_path: PathBuf = clap_utils::parse_required(matches, MNEMONIC_FLAG)?;
+    let count: u32 = clap_utils::parse_required(matches, COUNT_FLAG)?;
+    let first_index: u32 = clap_utils::parse_optional(matches, FIRST_INDEX_FLAG)?.unwrap_or(0);
+    let validator_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        VALIDATOR_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("validators"),
+    )?;
+    let stdin_password = matches.is_present(STDIN_PASSWORD_FLAG);
+    let allow_weak_password = matches.is_present(ALLOW_WEAK_PASSWORD_FLAG);
+
+    let last_index = first_index
+        .checked_add(count)
+        .ok_or_else(|| format!("--{} + --{} is too large", FIRST_INDEX_FLAG, COUNT_FLAG))?;
+
+    ensure_dir_exists(&validator_dir)?;
+
+    let _lock = lock_validator_dir(&validator_dir)?;
+    let mut defs = ValidatorDefinitions::open_or_create(&validator_dir)
+        .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?;
+
+    let phrase = fs::read_to_string(&mnemonic_path)
+        .map(ZeroizeString::from)
+        .map_err(|e| format!("Unable to read {:?}: {:?}", mnemonic_path, e))?;
+    let phrase_str = std::str::from_utf8(phrase.as_ref())
+        .map_err(|e| format!("Mnemonic is not valid UTF-8: {:?}", e))?;
+    let mnemonic = Mnemonic::from_phrase(phrase_str.trim(), Language::English)
+        .map_err(|e| format!("Unable to parse mnemonic: {:?}", e))?;
+
+    // TODO: `bip39` does not use zeroize. Perhaps we should make a PR upstream?
+    let seed = Bip39Seed::new(&mnemonic, "");
+    let master = DerivedKey::from_seed(seed.as_bytes())
+        .map_err(|()| "Unable to derive master key from mnemonic".to_string())?;
+
+    let password = read_new_password(PASSWORD_PROMPT, stdin_password, allow_weak_password)?;
+
+    eprintln!("");
+    eprintln!(
+        "The password will be stored as plain-text in {} so that it is not required each \
+         time the validator client starts.",
+        CONFIG_FILENAME
+    );
+    eprintln!("");
+
+    let mut num_recovered = 0;
+    for index in first_index..last_index {
+        let path = ValidatorPath::new(index, KeyType::Voting);
+        // `DerivedKey` is not `Clone`, so the first child is derived from a reference to `master`.
+        let secret = path
+            .iter_nodes()
+            .fold(None, |dk: Option<DerivedKey>, i| {
+                Some(dk.as_ref().unwrap_or(&master).child(*i))
+            })
+            .ok_or_else(|| format!("Empty derivation path for index {}", index))?;
+        let keypair = keypair_from_secret(secret.secret())
+            .map_err(|e| format!("Unable to derive keypair: {:?}", e))?;
+
+        let keystore = KeystoreBuilder::new(&keypair, password.as_ref(), format!("{}", path))
+            .and_then(|builder| builder.build())
+            .map_err(|e| format!("Unable to build keystore: {:?}", e))?;
+
+        // The keystore is placed in a directory that matches the name of the public key, like
+        // the `import` command.
+        let dest_dir = validator_dir.join(format!("0x{}", keystore.pubkey()));
+        if dest_dir.exists() {
+            eprintln!(
+                "Skipping recovery of existing public key at index {}: 0x{}",
+                index,
+                keystore.pubkey()
+            );
+            continue;
+        }
+
+        // The keystore is written as by `import`, without retrying failed filesystem operations.
+        write_staged(
+            &log,
+            0,
+            &validator_dir,
+            &keystore,
+            &dest_dir,
+            VOTING_KEYSTORE_FILE,
+            |staging_keystore| write_keystore_file(&keystore, staging_keystore),
+        )
+        .map_err(|e| e.to_string())?;
+
+        let dest_keystore = dest_dir.join(VOTING_KEYSTORE_FILE);
+        let validator_def =
+            ValidatorDefinition::new_keystore_with_password(&dest_keystore, Some(password.clone()))
+                .map_err(|e| format!("Unable to create new validator definition: {:?}", e))?;
+
+        defs.push(validator_def);
+
+        defs.save(&validator_dir)
+            .map_err(|e| format!("Unable to save {}: {:?}", CONFIG_FILENAME, e))?;
+
+        println!("{}\t0x{}", index, keystore.pubkey());
+        num_recovered += 1;
+    }
+
+    eprintln!("");
+    eprintln!(
+        "Successfully recovered {} validators ({} skipped).",
+        num_recovered,
+        count - num_recovered
+    );
+
+    Ok(())
+}
@@ -0,0 +1,241 @@
+use crate::{
+    common::{kdf_description, lock_validator_dir},
+    VALIDATOR_DIR_FLAG,
+};
+use account_utils::{
+    create_with_600_perms,
+    eth2_keystore::{
+        json_keystore::{Kdf, Scrypt},
+        Keystore, KeystoreBuilder, DKLEN, SALT_SIZE,
+    },
+    validator_definitions::{SigningDefinition, ValidatorDefinitions, CONFIG_FILENAME},
+    PlainText,
+};
+use clap::{App, Arg, ArgMatches};
+use rand::Rng;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+pub const CMD: &str = "reharden";
+pub const PUBKEY_FLAG: &str = "pubkey";
+pub const KDF_COST_FLAG: &str = "kdf-cost";
+pub const DRY_RUN_FLAG: &str = "dry-run";
+
+/// The scrypt `n` of keystores created by Lighthouse, see `eth2_keystore::default_kdf`.
+pub const DEFAULT_KDF_COST: &str = "262144";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Re-encrypts the keystore of each validator in the validator definitions file with \
+            a stronger scrypt KDF, using the stored password. The public key, UUID and path of \
+            each keystore are retained, so the validator definitions file is not modified.",
+        )
+        .arg(
+            Arg::with_name(PUBKEY_FLAG)
+                .long(PUBKEY_FLAG)
+                .value_name("PUBKEY")
+                .help("If present, only re-encrypt the keystore of the validator with PUBKEY.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(KDF_COST_FLAG)
+                .long(KDF_COST_FLAG)
+                .value_name("N")
+                .help(
+                    "The scrypt cost parameter (n) of the re-encrypted keystores, which must be \
+                    a power of two. Keystores which already use scrypt with at least this cost \
+                    are left unchanged.",
+                )
+                .default_value(DEFAULT_KDF_COST)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(DRY_RUN_FLAG)
+                .long(DRY_RUN_FLAG)
+                .help(
+                    "If present, print the current and target KDF parameters of each keystore \
+                    without modifying it.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_DIR_FLAG)
+                .long(VALIDATOR_DIR_FLAG)
+                .value_name("VALIDATOR_DIRECTORY")
+                .help(
+                    "The path to search for validator directories. \
+                    Defaults to ~/.lighthouse/validators",
+                )
+                .takes_value(true),
+        )
+}
+
+pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
+    let validator_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        VALIDATOR_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("validators"),
+    )?;
+    let pubkey = matches.value_of(PUBKEY_FLAG);
+    let kdf_cost: u32 = clap_utils::parse_required(matches, KDF_COST_FLAG)?;
+    let dry_run = matches.is_present(DRY_RUN_FLAG);
+
+    if kdf_cost <= 1 || !kdf_cost.is_power_of_two() {
+        return Err(format!(
+            "--{} must be a power of two greater than 1",
+            KDF_COST_FLAG
+        ));
+    }
+
+    let _lock = if dry_run {
+        None
+    } else {
+        Some(lock_validator_dir(&validator_dir)?)
+    };
+    let defs = ValidatorDefinitions::open(&validator_dir)
+        .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?;
+
+    let mut num_rehardened = 0;
+
+    let selected = defs
+        .select(pubkey)
+        .map_err(|e| format!("Unable to select validators: {:?}", e))?;
+
+    for def in selected {
+        let def_pubkey = def.voting_public_key.to_hex_string();
+
+        match &def.signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                ..
+            } => {
+                let keystore = Keystore::from_json_file(voting_keystore_path)
+                    .map_err(|e| format!("Unable to read {:?}: {:?}", voting_keystore_path, e))?;
+                let target_kdf = target_kdf(kdf_cost);
+                let target_description = kdf_description(&target_kdf);
+                let is_rehardened = needs_reharden(keystore.kdf(), kdf_cost);
+
+                if dry_run {
+                    println!(
+                        "{}\t{}\t{}",
+                        def_pubkey,
+                        kdf_description(keystore.kdf()),
+                        if is_rehardened {
+                            &target_description
+                        } else {
+                            "unchanged"
+                        }
+                    );
+                    continue;
+                } else if !is_rehardened {
+                    continue;
+                }
+
+                let password = def
+                    .signing_definition
+                    .voting_keystore_password()
+                    .map_err(|e| format!("Unable to read password: {:?}", e))?
+                    .ok_or_else(|| {
+                        format!(
+                            "No password is stored for {}, use `validator modify` to store it",
+                            def_pubkey
+                        )
+                    })?;
+
+                reharden_keystore(voting_keystore_path, &keystore, &password, target_kdf)?;
+
+                eprintln!(
+                    "Re-encrypted the keystore for {} with {}",
+                    def_pubkey, target_description
+                );
+                num_rehardened += 1;
+            }
+        }
+    }
+
+    if !dry_run {
+        eprintln!("");
+        eprintln!("Successfully re-encrypted {} keystores.", num_rehardened);
+    }
+
+    Ok(())
+}
+
+/// Returns `true` if `kdf` is not scrypt with a cost of at least `kdf_cost`.
+fn needs_reharden(kdf: &Kdf, kdf_cost: u32) -> bool {
+    match kdf {
+        Kdf::Scrypt(params) => params.n < kdf_cost,
+        Kdf::Pbkdf2(_) => true,
+    }
+}
+
+/// Returns the parameters of `eth2_keystore::default_kdf` with a new salt and `n == kdf_cost`.
+fn target_kdf(kdf_cost: u32) -> Kdf {
+    let salt = rand::thread_rng().gen::<[u8; SALT_SIZE]>();
+
+    Kdf::Scrypt(Scrypt {
+        dklen: DKLEN,
+        n: kdf_cost,
+        p: 1,
+        r: 8,
+        salt: salt.to_vec().into(),
+    })
+}
+
+/// Replaces the keystore at `path` with `keystore` re-encrypted with `kdf`.
+///
+/// The new keystore is written to a temporary file, which is checked and synced to disk before
+/// it is renamed over `path`, so that the keystore is never partially written.
+fn reharden_keystore(
+    path: &Path,
+    keystore: &Keystore,
+    password: &PlainText,
+    kdf: Kdf,
+) -> Result<(), String> {
+    let keypair = keystore
+        .decrypt_keypair(password.as_bytes())
+        .map_err(|e| format!("Unable to decrypt {:?}: {:?}", path, e))?;
+    let new_keystore =
+        KeystoreBuilder::new(&keypair, password.as_bytes(), keystore.path().to_string())
+            .and_then(|builder| builder.kdf(kdf).uuid(*keystore.uuid()).build())
+            .map_err(|e| format!("Unable to re-encrypt {:?}: {:?}", path, e))?;
+    let json = new_keystore
+        .to_json_string()
+        .map_err(|e| format!("Unable to encode {:?}: {:?}", path, e))?;
+
+    let file_name = path
+        .file_name()
+        .and_then(|file_name| file_name.to_str())
+        .ok_or_else(|| format!("Invalid keystore path {:?}", path))?;
+    let temp_path = path.with_file_name(format!(".{}.tmp", file_name));
+
+    let result = create_with_600_perms(&temp_path, json.as_bytes())
+        .and_then(|()| File::open(&temp_path)?.sync_all())
+        .map_err(|e| format!("Unable to write {:?}: {:?}", temp_path, e))
+        .and_then(|()| {
+            // The keystore is checked before the original is replaced, since it holds the only
+            // copy of the secret key in the validator directory.
+            let written = Keystore::from_json_file(&temp_path)
+                .and_then(|written| written.decrypt_keypair(password.as_bytes()))
+                .map_err(|e| format!("Unable to check {:?}: {:?}", temp_path, e))?;
+            if written.pk != keypair.pk {
+                return Err(format!("Re-encrypted keystore {:?} is invalid", temp_path));
+            }
+            fs::rename(&temp_path, path)
+                .map_err(|e| format!("Unable to replace {:?}: {:?}", path, e))
+        });
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result?;
+
+    if let Some(dir) = path.parent() {
+        File::open(dir)
+            .and_then(|dir| dir.sync_all())
+            .map_err(|e| format!("Unable to sync {:?}: {:?}", dir, e))?;
+    }
+
+    Ok(())
+}
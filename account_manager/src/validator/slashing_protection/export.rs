@@ -0,0 +1,116 @@
+use crate::{common::genesis_validators_root, VALIDATOR_DIR_FLAG};
+use account_utils::normalize_pubkey;
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+use std::fs::File;
+use std::path::PathBuf;
+use types::EthSpec;
+
+pub const CMD: &str = "export";
+pub const EXPORT_FILE_ARG: &str = "EXPORT-FILE";
+pub const PUBKEYS_FLAG: &str = "pubkeys";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Writes the slashing protection data of the validators in the validator directory \
+            to an EIP-3076 interchange file, so that they can be moved to another client.",
+        )
+        .arg(
+            Arg::with_name(EXPORT_FILE_ARG)
+                .takes_value(true)
+                .value_name("FILE")
+                .help("The path to write the interchange file to.")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(PUBKEYS_FLAG)
+                .long(PUBKEYS_FLAG)
+                .value_name("PUBKEYS")
+                .help(
+                    "A comma-separated list of the public keys of the validators to export. \
+                    Defaults to every validator in the slashing protection database.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_DIR_FLAG)
+                .long(VALIDATOR_DIR_FLAG)
+                .value_name("VALIDATOR_DIRECTORY")
+                .help(
+                    "The path to search for validator directories. \
+                    Defaults to ~/.lighthouse/validators",
+                )
+                .takes_value(true),
+        )
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<(), String> {
+    let export_file: PathBuf = clap_utils::parse_required(matches, EXPORT_FILE_ARG)?;
+    let validator_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        VALIDATOR_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("validators"),
+    )?;
+    let pubkeys = matches
+        .value_of(PUBKEYS_FLAG)
+        .map(|pubkeys| pubkeys.split(',').map(normalize_pubkey).collect::<Vec<_>>());
+
+    let genesis_validators_root = genesis_validators_root(&env)?;
+
+    let db_path = validator_dir.join(SLASHING_PROTECTION_FILENAME);
+    if !db_path.exists() {
+        return Err(format!(
+            "No slashing protection database exists at {:?}",
+            db_path
+        ));
+    }
+    let db = SlashingDatabase::open(&db_path).map_err(|e| {
+        format!(
+            "Unable to open slashing protection database {:?}: {:?}",
+            db_path, e
+        )
+    })?;
+
+    let mut interchange = db
+        .export_interchange_info(genesis_validators_root)
+        .map_err(|e| format!("Unable to export slashing protection data: {:?}", e))?;
+
+    if let Some(pubkeys) = &pubkeys {
+        let unknown = pubkeys
+            .iter()
+            .filter(|pubkey| {
+                !interchange
+                    .data
+                    .iter()
+                    .any(|record| record.pubkey.to_hex_string() == **pubkey)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if !unknown.is_empty() {
+            return Err(format!(
+                "No slashing protection data for: {}",
+                unknown.join(", ")
+            ));
+        }
+
+        interchange
+            .data
+            .retain(|record| pubkeys.contains(&record.pubkey.to_hex_string()));
+    }
+
+    let file = File::create(&export_file)
+        .map_err(|e| format!("Unable to create {:?}: {:?}", export_file, e))?;
+    interchange
+        .to_json_writer(file)
+        .map_err(|e| format!("Unable to write {:?}: {:?}", export_file, e))?;
+
+    eprintln!(
+        "Exported slashing protection data for {} validators to {:?}",
+        interchange.data.len(),
+        export_file
+    );
+
+    Ok(())
+}
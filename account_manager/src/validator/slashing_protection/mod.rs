@@ -0,0 +1,27 @@
+pub mod export;
+
+use clap::{App, ArgMatches};
+use environment::Environment;
+use types::EthSpec;
+
+pub const CMD: &str = "slashing-protection";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Export slashing protection data to another client, using the EIP-3076 \
+            interchange format. To import slashing protection data, use the \
+            --slashing-protection flag of `validator import`.",
+        )
+        .subcommand(export::cli_app())
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<(), String> {
+    match matches.subcommand() {
+        (export::CMD, Some(matches)) => export::cli_run::<T>(matches, env),
+        (unknown, _) => Err(format!(
+            "{} does not have a {} command. See --help",
+            CMD, unknown
+        )),
+    }
+}
@@ -0,0 +1,158 @@
+use super::manifest::Manifest;
+use crate::VALIDATOR_DIR_FLAG;
+use account_utils::{
+    eth2_keystore::Keystore,
+    validator_definitions::{
+        SigningDefinition, ValidatorDefinition, ValidatorDefinitions, CONFIG_FILENAME,
+    },
+};
+use clap::{App, Arg, ArgMatches};
+use std::path::{Path, PathBuf};
+
+pub const CMD: &str = "verify";
+pub const MANIFEST_FLAG: &str = "manifest";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .arg(
+            Arg::with_name(VALIDATOR_DIR_FLAG)
+                .long(VALIDATOR_DIR_FLAG)
+                .value_name("VALIDATOR_DIRECTORY")
+                .help(
+                    "The path to search for validator directories. \
+                    Defaults to ~/.lighthouse/validators",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(MANIFEST_FLAG)
+                .long(MANIFEST_FLAG)
+                .value_name("MANIFEST_FILE")
+                .help(
+                    "If present, instead check that each file in MANIFEST_FILE, as written by \
+                    `validator import --manifest`, still has the recorded SHA-256 checksum.",
+                )
+                .conflicts_with(VALIDATOR_DIR_FLAG)
+                .takes_value(true),
+        )
+        .about(
+            "Checks that the keystore of each enabled validator in the validator definitions \
+            file can be read and matches the public key in the definition. If the password is \
+            stored, the keystore is also decrypted. Exits with an error if any validator fails.",
+        )
+}
+
+pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
+    let manifest: Option<PathBuf> = clap_utils::parse_optional(matches, MANIFEST_FLAG)?;
+    if let Some(manifest) = manifest {
+        return verify_manifest(&manifest);
+    }
+
+    let validator_dir = clap_utils::parse_path_with_default_in_home_dir(
+        matches,
+        VALIDATOR_DIR_FLAG,
+        PathBuf::new().join(".lighthouse").join("validators"),
+    )?;
+
+    let defs = ValidatorDefinitions::open(&validator_dir)
+        .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?;
+
+    let mut num_verified = 0;
+    let mut num_failed = 0;
+
+    for def in defs.as_slice().iter().filter(|def| def.enabled) {
+        match verify_definition(def) {
+            Ok(()) => {
+                println!("OK\t{}", def.voting_public_key.to_hex_string());
+                num_verified += 1;
+            }
+            Err(e) => {
+                println!("FAILED\t{}\t{}", def.voting_public_key.to_hex_string(), e);
+                num_failed += 1;
+            }
+        }
+    }
+
+    if num_failed > 0 {
+        return Err(format!(
+            "{} of {} enabled validators failed verification",
+            num_failed,
+            num_verified + num_failed
+        ));
+    }
+
+    eprintln!("Successfully verified {} enabled validators.", num_verified);
+
+    Ok(())
+}
+
+/// Checks the checksum of each file in the manifest at `path`.
+fn verify_manifest(path: &Path) -> Result<(), String> {
+    let manifest = Manifest::from_json_file(path)?;
+
+    let mut num_verified = 0;
+    let mut num_failed = 0;
+
+    for entry in manifest.entries() {
+        match entry.check() {
+            Ok(()) => {
+                println!("OK\t{:?}", entry.path);
+                num_verified += 1;
+            }
+            Err(e) => {
+                println!("FAILED\t{:?}\t{}", entry.path, e);
+                num_failed += 1;
+            }
+        }
+    }
+
+    if num_failed > 0 {
+        return Err(format!(
+            "{} of {} files in the manifest failed verification",
+            num_failed,
+            num_verified + num_failed
+        ));
+    }
+
+    eprintln!("Successfully verified {} files.", num_verified);
+
+    Ok(())
+}
+
+/// Checks that the keystore for `def` can be parsed and that its public key matches the one in
+/// the definition. If a password is available, the keystore is also decrypted.
+fn verify_definition(def: &ValidatorDefinition) -> Result<(), String> {
+    match &def.signing_definition {
+        SigningDefinition::LocalKeystore {
+            voting_keystore_path,
+            ..
+        } => {
+            let keystore = Keystore::from_json_file(voting_keystore_path)
+                .map_err(|e| format!("Unable to read {:?}: {:?}", voting_keystore_path, e))?;
+
+            if keystore.public_key().as_ref() != Some(&def.voting_public_key) {
+                return Err(format!(
+                    "Keystore public key 0x{} does not match the definition",
+                    keystore.pubkey()
+                ));
+            }
+
+            let password = def
+                .signing_definition
+                .voting_keystore_password()
+                .map_err(|e| format!("Unable to read password: {:?}", e))?;
+
+            if let Some(password) = password {
+                let keypair = keystore
+                    .decrypt_keypair(password.as_bytes())
+                    .map_err(|e| format!("Unable to decrypt keystore: {:?}", e))?;
+
+                if keypair.pk != def.voting_public_key {
+                    return Err("Decrypted public key does not match the definition".to_string());
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
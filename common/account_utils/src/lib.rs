@@ -24,6 +24,14 @@ pub use eth2_wallet::PlainText;
 /// array of length 32.
 const DEFAULT_PASSWORD_LEN: usize = 48;
 
+/// The minimum length of a password chosen by a user.
+pub const MINIMUM_PASSWORD_LEN: usize = 12;
+
+/// The minimum estimated entropy (in bits) of a password chosen by a user.
+///
+/// See `is_password_sufficiently_complex` for how the entropy is estimated.
+pub const MINIMUM_PASSWORD_ENTROPY_BITS: f64 = 50.0;
+
 /// Returns the "default" path where a wallet should store its password file.
 pub fn default_wallet_password_path<P: AsRef<Path>>(wallet_name: &str, secrets_dir: P) -> PathBuf {
     secrets_dir.as_ref().join(format!("{}.pass", wallet_name))
@@ -49,6 +57,9 @@ pub fn default_keystore_password_path<P: AsRef<Path>>(
 }
 
 /// Reads a password file into a Zeroize-ing `PlainText` struct, with new-lines removed.
+///
+/// Only the trailing new-lines are removed (see `strip_off_newlines`), so a password may start or
+/// end with spaces.
 pub fn read_password<P: AsRef<Path>>(path: P) -> Result<PlainText, io::Error> {
     fs::read(path).map(strip_off_newlines).map(Into::into)
 }
@@ -70,6 +81,15 @@ pub fn create_with_600_perms<P: AsRef<Path>>(path: P, bytes: &[u8]) -> Result<()
     Ok(())
 }
 
+/// Returns `pubkey` as a lower-case, `0x`-prefixed hex string, the form returned by
+/// `PublicKey::to_hex_string`. The `0x` prefix of `pubkey` is optional.
+pub fn normalize_pubkey(pubkey: &str) -> String {
+    format!(
+        "0x{}",
+        pubkey.trim().trim_start_matches("0x").to_lowercase()
+    )
+}
+
 /// Generates a random alphanumeric password of length `DEFAULT_PASSWORD_LEN`.
 pub fn random_password() -> PlainText {
     rand::thread_rng()
@@ -80,7 +100,53 @@ pub fn random_password() -> PlainText {
         .into()
 }
 
+/// Returns `Ok(())` if `password` is at least `MINIMUM_PASSWORD_LEN` characters long and has an
+/// estimated entropy of at least `MINIMUM_PASSWORD_ENTROPY_BITS`. Otherwise, returns an
+/// explanation that can be shown to the user.
+///
+/// The entropy is estimated as `len * log2(pool)`, where `pool` is the total size of the character
+/// classes (lower-case, upper-case, digits and other) which appear in the password. This is a
+/// coarse estimate that is only intended to reject obviously weak passwords.
+pub fn is_password_sufficiently_complex(password: &[u8]) -> Result<(), String> {
+    let password =
+        std::str::from_utf8(password).map_err(|_| "Password is not valid UTF-8.".to_string())?;
+    let len = password.chars().count();
+
+    if len < MINIMUM_PASSWORD_LEN {
+        return Err(format!(
+            "Password must be at least {} characters long.",
+            MINIMUM_PASSWORD_LEN
+        ));
+    }
+
+    let has_class = |f: fn(&char) -> bool| password.chars().any(|c| f(&c));
+    let pool = [
+        (has_class(char::is_ascii_lowercase), 26),
+        (has_class(char::is_ascii_uppercase), 26),
+        (has_class(char::is_ascii_digit), 10),
+        (has_class(|c| !c.is_ascii_alphanumeric()), 33),
+    ]
+    .iter()
+    .filter(|(present, _)| *present)
+    .map(|(_, size)| size)
+    .sum::<u32>();
+
+    let entropy_bits = len as f64 * f64::from(pool).log2();
+
+    if entropy_bits < MINIMUM_PASSWORD_ENTROPY_BITS {
+        return Err(format!(
+            "Password is too predictable, use a longer password or a wider range of characters \
+             (estimated {:.0} bits of entropy, {:.0} required).",
+            entropy_bits, MINIMUM_PASSWORD_ENTROPY_BITS
+        ));
+    }
+
+    Ok(())
+}
+
 /// Remove any number of newline or carriage returns from the end of a vector of bytes.
+///
+/// Other whitespace (e.g., spaces and tabs) is retained.
 pub fn strip_off_newlines(mut bytes: Vec<u8>) -> Vec<u8> {
     let mut strip_off = 0;
     for (i, byte) in bytes.iter().rev().enumerate() {
@@ -95,6 +161,10 @@ pub fn strip_off_newlines(mut bytes: Vec<u8>) -> Vec<u8> {
 }
 
 /// Reads a password from TTY or stdin if `use_stdin == true`.
+///
+/// The password is read up to the end of the line, and the single `\n` or `\r\n` which ends the
+/// line is removed. Any other whitespace is part of the password, so a password which is only
+/// spaces is not empty.
 pub fn read_password_from_user(use_stdin: bool) -> Result<ZeroizeString, String> {
     let result = if use_stdin {
         rpassword::prompt_password_stderr("")
@@ -129,7 +199,18 @@ impl AsRef<[u8]> for ZeroizeString {
 
 #[cfg(test)]
 mod test {
-    use super::strip_off_newlines;
+    use super::{is_password_sufficiently_complex, read_password, strip_off_newlines};
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn password_complexity() {
+        assert!(is_password_sufficiently_complex(b"").is_err());
+        assert!(is_password_sufficiently_complex(b"cats").is_err());
+        assert!(is_password_sufficiently_complex(b"12345678901234").is_err());
+        assert!(is_password_sufficiently_complex(b"correcthorse").is_ok());
+        assert!(is_password_sufficiently_complex(b"Tr0ub4dor&3xyz").is_ok());
+    }
 
     #[test]
     fn test_strip_off() {
@@ -164,4 +245,26 @@ mod test {
             expected
         );
     }
+
+    #[test]
+    fn strip_off_keeps_spaces() {
+        assert_eq!(
+            strip_off_newlines(b" hello world \t\r\n".to_vec()),
+            b" hello world \t".to_vec()
+        );
+        assert_eq!(
+            strip_off_newlines(b"hello\nworld\n".to_vec()),
+            b"hello\nworld".to_vec()
+        );
+        assert_eq!(strip_off_newlines(b"  \n".to_vec()), b"  ".to_vec());
+    }
+
+    #[test]
+    fn read_password_with_spaces() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("password");
+        fs::write(&path, " hello world \n").unwrap();
+
+        assert_eq!(read_password(&path).unwrap().as_bytes(), b" hello world ");
+    }
 }
@@ -3,21 +3,32 @@
 //! Serves as the source-of-truth of which validators this validator client should attempt (or not
 //! attempt) to load into the `crate::intialized_validators::InitializedValidators` struct.
 
-use crate::{create_with_600_perms, default_keystore_password_path, ZeroizeString};
+use crate::{
+    create_with_600_perms, default_keystore_password_path, normalize_pubkey, read_password,
+    PlainText, ZeroizeString,
+};
 use eth2_keystore::Keystore;
 use regex::Regex;
 use serde_derive::{Deserialize, Serialize};
 use slog::{error, Logger};
-use std::collections::HashSet;
-use std::fs::{self, OpenOptions};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File, OpenOptions};
 use std::io;
 use std::iter::FromIterator;
 use std::path::{Path, PathBuf};
-use types::PublicKey;
+use types::{Address, PublicKey};
 use validator_dir::VOTING_KEYSTORE_FILE;
 
 /// The file name for the serialized `ValidatorDefinitions` struct.
 pub const CONFIG_FILENAME: &str = "validator_definitions.yml";
+/// The file which is written and then renamed to `CONFIG_FILENAME` when saving.
+pub const CONFIG_TEMP_FILENAME: &str = ".validator_definitions.yml.tmp";
+/// The directory in the validators directory where keystores are written before being moved into
+/// their final location. It is never searched for keystores.
+pub const STAGING_DIR: &str = ".staging";
+/// Locked by the validator client and by each account manager command which modifies the
+/// validator definitions in a validator directory.
+pub const LOCKFILE_NAME: &str = ".lock";
 
 #[derive(Debug)]
 pub enum Error {
@@ -31,10 +42,16 @@ pub enum Error {
     UnableToEncodeFile(serde_yaml::Error),
     /// The config file could not be written to the filesystem.
     UnableToWriteFile(io::Error),
+    /// The config file could not be synced to disk.
+    UnableToSyncFile(io::Error),
     /// The public key from the keystore is invalid.
     InvalidKeystorePubkey,
     /// The keystore was unable to be opened.
     UnableToOpenKeystore(eth2_keystore::Error),
+    /// The file at the `voting_keystore_password_path` could not be read.
+    UnableToReadPassword { path: PathBuf, error: io::Error },
+    /// There is no definition with the given voting public key.
+    UnknownPubkey(String),
 }
 
 /// Defines how the validator client should attempt to sign messages for this validator.
@@ -55,6 +72,33 @@ pub enum SigningDefinition {
     },
 }
 
+impl SigningDefinition {
+    /// Returns the password of the voting keystore, if it is stored in the definition or in the
+    /// file at `voting_keystore_password_path`.
+    ///
+    /// As in the validator client, a stored password takes precedence over a password path.
+    pub fn voting_keystore_password(&self) -> Result<Option<PlainText>, Error> {
+        match self {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_password_path,
+                voting_keystore_password,
+                ..
+            } => match (voting_keystore_password_path, voting_keystore_password) {
+                (_, Some(password)) => Ok(Some(PlainText::from(password.as_ref().to_vec()))),
+                (Some(path), None) => {
+                    read_password(path)
+                        .map(Some)
+                        .map_err(|error| Error::UnableToReadPassword {
+                            path: path.clone(),
+                            error,
+                        })
+                }
+                (None, None) => Ok(None),
+            },
+        }
+    }
+}
+
 /// A validator that may be initialized by this validator client.
 ///
 /// Presently there is only a single variant, however we expect more variants to arise (e.g.,
@@ -63,8 +107,62 @@ pub enum SigningDefinition {
 pub struct ValidatorDefinition {
     pub enabled: bool,
     pub voting_public_key: PublicKey,
+    /// The graffiti to include in blocks proposed by this validator. Not yet used by the
+    /// validator client.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graffiti: Option<String>,
+    /// The address suggested to receive transaction fees for this validator. Not yet used by the
+    /// validator client.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggested_fee_recipient: Option<Address>,
+    /// The hex-encoded SHA-256 of the keystore file this validator was imported from, used to
+    /// skip the keystore if the import is run again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub imported_keystore_sha256: Option<String>,
+    /// If `true`, the paths in the `signing_definition` are saved relative to the directory of the
+    /// validator definitions file, so that the directory can be moved. The paths are always
+    /// resolved against that directory by `ValidatorDefinitions::open`.
+    #[serde(skip)]
+    pub relative_paths: bool,
     #[serde(flatten)]
     pub signing_definition: SigningDefinition,
+    /// Fields which are not known to this version of Lighthouse, which are kept so that they are
+    /// saved again, e.g. annotations added by the user or fields written by a newer version.
+    #[serde(flatten, deserialize_with = "deserialize_unknown_fields")]
+    pub unknown_fields: BTreeMap<String, serde_yaml::Value>,
+}
+
+/// Deserializes the fields of a `ValidatorDefinition` which are not otherwise known.
+///
+/// A flattened map is given every field not consumed by the struct, which includes the fields
+/// of the (also flattened) `SigningDefinition`. Those are the fields that the `SigningDefinition`
+/// deserialized from the map is serialized to, so they are removed.
+fn deserialize_unknown_fields<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<String, serde_yaml::Value>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+
+    let mut fields: BTreeMap<String, serde_yaml::Value> =
+        serde::Deserialize::deserialize(deserializer)?;
+
+    let mapping = fields
+        .iter()
+        .map(|(name, value)| (serde_yaml::Value::from(name.clone()), value.clone()))
+        .collect();
+    let signing_definition: SigningDefinition =
+        serde_yaml::from_value(serde_yaml::Value::Mapping(mapping)).map_err(D::Error::custom)?;
+    if let serde_yaml::Value::Mapping(known) =
+        serde_yaml::to_value(&signing_definition).map_err(D::Error::custom)?
+    {
+        for name in known.keys().filter_map(serde_yaml::Value::as_str) {
+            fields.remove(name);
+        }
+    }
+
+    Ok(fields)
 }
 
 impl ValidatorDefinition {
@@ -88,11 +186,16 @@ impl ValidatorDefinition {
         Ok(ValidatorDefinition {
             enabled: true,
             voting_public_key,
+            graffiti: None,
+            suggested_fee_recipient: None,
+            imported_keystore_sha256: None,
+            relative_paths: false,
             signing_definition: SigningDefinition::LocalKeystore {
                 voting_keystore_path,
                 voting_keystore_password_path: None,
                 voting_keystore_password,
             },
+            unknown_fields: BTreeMap::new(),
         })
     }
 }
@@ -114,6 +217,9 @@ impl ValidatorDefinitions {
     }
 
     /// Open an existing file, returning an error if the file does not exist.
+    ///
+    /// Relative paths in the file are joined to `validators_dir`, and the `relative_paths` of
+    /// their definition is set so that `save` keeps them relative.
     pub fn open<P: AsRef<Path>>(validators_dir: P) -> Result<Self, Error> {
         let config_path = validators_dir.as_ref().join(CONFIG_FILENAME);
         let file = OpenOptions::new()
@@ -122,7 +228,23 @@ impl ValidatorDefinitions {
             .create_new(false)
             .open(&config_path)
             .map_err(Error::UnableToOpenFile)?;
-        serde_yaml::from_reader(file).map_err(Error::UnableToParseFile)
+        let mut this: Self = serde_yaml::from_reader(file).map_err(Error::UnableToParseFile)?;
+
+        for def in this.0.iter_mut() {
+            let SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                voting_keystore_password_path,
+                ..
+            } = &mut def.signing_definition;
+            for path in std::iter::once(voting_keystore_path).chain(voting_keystore_password_path) {
+                if path.is_relative() {
+                    *path = validators_dir.as_ref().join(&path);
+                    def.relative_paths = true;
+                }
+            }
+        }
+
+        Ok(this)
     }
 
     /// Perform a recursive, exhaustive search through `validators_dir` and add any keystores
@@ -144,7 +266,7 @@ impl ValidatorDefinitions {
         log: &Logger,
     ) -> Result<usize, Error> {
         let mut keystore_paths = vec![];
-        recursively_find_voting_keystores(validators_dir, &mut keystore_paths)
+        recursively_find_voting_keystores(validators_dir, false, &mut keystore_paths)
             .map_err(Error::UnableToSearchForKeystores)?;
 
         let known_paths: HashSet<&PathBuf> =
@@ -205,11 +327,16 @@ impl ValidatorDefinitions {
                 Some(ValidatorDefinition {
                     enabled: true,
                     voting_public_key,
+                    graffiti: None,
+                    suggested_fee_recipient: None,
+                    imported_keystore_sha256: None,
+                    relative_paths: false,
                     signing_definition: SigningDefinition::LocalKeystore {
                         voting_keystore_path,
                         voting_keystore_password_path,
                         voting_keystore_password: None,
                     },
+                    unknown_fields: BTreeMap::new(),
                 })
             })
             .collect::<Vec<_>>();
@@ -225,15 +352,48 @@ impl ValidatorDefinitions {
     /// `validators_dir` directory.
     ///
     /// Will create a new file if it does not exist or over-write any existing file.
+    ///
+    /// If the only change to an existing file is that definitions were added to the end, the new
+    /// definitions are appended to it, so that any comments in the file are kept. Otherwise the
+    /// whole file is re-encoded, which removes its comments but not the `unknown_fields` of any
+    /// definition.
+    ///
+    /// The file is written to `CONFIG_TEMP_FILENAME` and then renamed, so that the existing file
+    /// is never partially written. The file is synced to disk before this function returns.
     pub fn save<P: AsRef<Path>>(&self, validators_dir: P) -> Result<(), Error> {
         let config_path = validators_dir.as_ref().join(CONFIG_FILENAME);
-        let bytes = serde_yaml::to_vec(self).map_err(Error::UnableToEncodeFile)?;
+        let temp_path = validators_dir.as_ref().join(CONFIG_TEMP_FILENAME);
 
-        if config_path.exists() {
-            fs::write(config_path, &bytes).map_err(Error::UnableToWriteFile)
-        } else {
-            create_with_600_perms(&config_path, &bytes).map_err(Error::UnableToWriteFile)
+        // Paths outside of `validators_dir` remain absolute, even with `relative_paths`.
+        let mut saved = Self(self.0.clone());
+        for def in saved.0.iter_mut().filter(|def| def.relative_paths) {
+            let SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                voting_keystore_password_path,
+                ..
+            } = &mut def.signing_definition;
+            for path in std::iter::once(voting_keystore_path).chain(voting_keystore_password_path) {
+                if let Ok(relative) = path.strip_prefix(validators_dir.as_ref()) {
+                    *path = relative.to_path_buf();
+                }
+            }
         }
+        let bytes = match appended_yaml(&config_path, &saved.0) {
+            Some(yaml) => yaml.into_bytes(),
+            None => serde_yaml::to_vec(&saved).map_err(Error::UnableToEncodeFile)?,
+        };
+
+        create_with_600_perms(&temp_path, &bytes).map_err(Error::UnableToWriteFile)?;
+
+        File::open(&temp_path)
+            .and_then(|file| file.sync_all())
+            .map_err(Error::UnableToSyncFile)?;
+
+        fs::rename(&temp_path, &config_path).map_err(Error::UnableToWriteFile)?;
+
+        File::open(validators_dir.as_ref())
+            .and_then(|dir| dir.sync_all())
+            .map_err(Error::UnableToSyncFile)
     }
 
     /// Adds a new `ValidatorDefinition` to `self`.
@@ -250,29 +410,203 @@ impl ValidatorDefinitions {
     pub fn as_mut_slice(&mut self) -> &mut [ValidatorDefinition] {
         self.0.as_mut_slice()
     }
+
+    /// Returns the definitions in `self` with the voting public key `pubkey`, or every definition
+    /// if `pubkey` is `None`. The `0x` prefix of `pubkey` is optional, see `normalize_pubkey`.
+    ///
+    /// Returns an error if `pubkey` is supplied but no definition has it.
+    pub fn select(&self, pubkey: Option<&str>) -> Result<Vec<&ValidatorDefinition>, Error> {
+        let pubkey = pubkey.map(normalize_pubkey);
+        let selected = self
+            .0
+            .iter()
+            .filter(|def| has_pubkey(def, pubkey.as_deref()))
+            .collect::<Vec<_>>();
+        match pubkey {
+            Some(pubkey) if selected.is_empty() => Err(Error::UnknownPubkey(pubkey)),
+            _ => Ok(selected),
+        }
+    }
+
+    /// As `select`, but returns mutable references.
+    pub fn select_mut(
+        &mut self,
+        pubkey: Option<&str>,
+    ) -> Result<Vec<&mut ValidatorDefinition>, Error> {
+        let pubkey = pubkey.map(normalize_pubkey);
+        let selected = self
+            .0
+            .iter_mut()
+            .filter(|def| has_pubkey(def, pubkey.as_deref()))
+            .collect::<Vec<_>>();
+        match pubkey {
+            Some(pubkey) if selected.is_empty() => Err(Error::UnknownPubkey(pubkey)),
+            _ => Ok(selected),
+        }
+    }
+}
+
+/// Returns `true` if `pubkey` is `None` or the normalized voting public key of `def`.
+fn has_pubkey(def: &ValidatorDefinition, pubkey: Option<&str>) -> bool {
+    pubkey.map_or(true, |pubkey| {
+        def.voting_public_key.to_hex_string() == pubkey
+    })
+}
+
+/// An exclusive advisory lock on the `LOCKFILE_NAME` in a validator directory.
+///
+/// The lock is released when this is dropped, or by the OS if the process exits, so a lockfile
+/// left in the directory does not lock it.
+pub struct DefinitionsLock {
+    _file: File,
+}
+
+impl DefinitionsLock {
+    /// Takes the lock on `validator_dir`, returning `None` if another process holds it.
+    pub fn try_acquire(validator_dir: &Path) -> Result<Option<Self>, io::Error> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .open(validator_dir.join(LOCKFILE_NAME))?;
+
+        if try_lock_exclusive(&file)? {
+            Ok(Some(Self { _file: file }))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(unix)]
+fn try_lock_exclusive(file: &File) -> Result<bool, io::Error> {
+    use std::os::unix::io::AsRawFd;
+
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } == 0 {
+        return Ok(true);
+    }
+    match io::Error::last_os_error() {
+        e if e.kind() == io::ErrorKind::WouldBlock => Ok(false),
+        e => Err(e),
+    }
+}
+
+#[cfg(not(unix))]
+fn try_lock_exclusive(_file: &File) -> Result<bool, io::Error> {
+    Ok(true)
+}
+
+/// Returns the contents of the file at `config_path` with the definitions of `defs` which are not
+/// already in it appended, or `None` if the file cannot be extended in this way because it does
+/// not exist, is empty or differs from the start of `defs`.
+fn appended_yaml(config_path: &Path, defs: &[ValidatorDefinition]) -> Option<String> {
+    // Definitions are compared as YAML, since `relative_paths` is not saved.
+    let to_yaml = |defs: &[ValidatorDefinition]| serde_yaml::to_string(defs).ok();
+
+    let mut yaml = fs::read_to_string(config_path).ok()?;
+    let existing: Vec<ValidatorDefinition> = serde_yaml::from_str(&yaml).ok()?;
+    if existing.is_empty()
+        || existing.len() > defs.len()
+        || to_yaml(&existing)? != to_yaml(&defs[..existing.len()])?
+    {
+        return None;
+    }
+
+    if existing.len() < defs.len() {
+        let appended = to_yaml(&defs[existing.len()..])?;
+        if !yaml.ends_with('\n') {
+            yaml.push('\n');
+        }
+        yaml.push_str(appended.trim_start_matches("---\n"));
+        yaml.push('\n');
+    }
+
+    // Appending is only valid for a block sequence, which is checked by parsing the result.
+    let appended: Vec<ValidatorDefinition> = serde_yaml::from_str(&yaml).ok()?;
+    if to_yaml(&appended)? == to_yaml(defs)? {
+        Some(yaml)
+    } else {
+        None
+    }
 }
 
 /// Perform an exhaustive tree search of `dir`, adding any discovered voting keystore paths to
 /// `matches`.
 ///
+/// Symbolic links are skipped unless `follow_symlinks` is `true`, in which case each directory is
+/// searched at most once, even if it is reachable through more than one link.
+///
 /// ## Errors
 ///
 /// Returns with an error immediately if any filesystem error is raised.
 pub fn recursively_find_voting_keystores<P: AsRef<Path>>(
     dir: P,
+    follow_symlinks: bool,
     matches: &mut Vec<PathBuf>,
 ) -> Result<(), io::Error> {
+    recursively_find_files(
+        dir.as_ref(),
+        &is_voting_keystore,
+        follow_symlinks,
+        &mut HashSet::new(),
+        matches,
+    )
+}
+
+/// Perform an exhaustive tree search of `dir`, adding the path of any file with a name that
+/// matches `pattern` to `matches`.
+///
+/// This is an alternative to `recursively_find_voting_keystores` for keystores that do not follow
+/// a known naming scheme.
+///
+/// ## Errors
+///
+/// Returns with an error immediately if any filesystem error is raised.
+pub fn recursively_find_voting_keystores_matching<P: AsRef<Path>>(
+    dir: P,
+    pattern: &Regex,
+    follow_symlinks: bool,
+    matches: &mut Vec<PathBuf>,
+) -> Result<(), io::Error> {
+    recursively_find_files(
+        dir.as_ref(),
+        &|file_name| pattern.is_match(file_name),
+        follow_symlinks,
+        &mut HashSet::new(),
+        matches,
+    )
+}
+
+/// Perform an exhaustive tree search of `dir`, adding the path of any file with a name that
+/// satisfies `is_match` to `matches`. Directories named `STAGING_DIR` are skipped.
+///
+/// With `follow_symlinks`, the canonical path of each directory searched is added to `visited`,
+/// so that a link to a parent directory does not cause an infinite loop.
+fn recursively_find_files(
+    dir: &Path,
+    is_match: &dyn Fn(&str) -> bool,
+    follow_symlinks: bool,
+    visited: &mut HashSet<PathBuf>,
+    matches: &mut Vec<PathBuf>,
+) -> Result<(), io::Error> {
+    if follow_symlinks && !visited.insert(fs::canonicalize(dir)?) {
+        return Ok(());
+    }
+
     fs::read_dir(dir)?.try_for_each(|dir_entry| {
         let dir_entry = dir_entry?;
-        let file_type = dir_entry.file_type()?;
-        if file_type.is_dir() {
-            recursively_find_voting_keystores(dir_entry.path(), matches)?
-        } else if file_type.is_file()
-            && dir_entry
-                .file_name()
-                .to_str()
-                .map_or(false, is_voting_keystore)
-        {
+        let mut file_type = dir_entry.file_type()?;
+        if file_type.is_symlink() && follow_symlinks {
+            file_type = fs::metadata(dir_entry.path())?.file_type();
+        }
+        if file_type.is_dir() && dir_entry.file_name() != STAGING_DIR {
+            recursively_find_files(
+                &dir_entry.path(),
+                is_match,
+                follow_symlinks,
+                visited,
+                matches,
+            )?
+        } else if file_type.is_file() && dir_entry.file_name().to_str().map_or(false, is_match) {
             matches.push(dir_entry.path())
         }
         Ok(())
@@ -280,7 +614,7 @@ pub fn recursively_find_voting_keystores<P: AsRef<Path>>(
 }
 
 /// Returns `true` if we should consider the `file_name` to represent a voting keystore.
-fn is_voting_keystore(file_name: &str) -> bool {
+pub fn is_voting_keystore(file_name: &str) -> bool {
     // All formats end with `.json`.
     if !file_name.ends_with(".json") {
         return false;
@@ -316,6 +650,7 @@ fn is_voting_keystore(file_name: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tempfile::tempdir;
 
     #[test]
     fn voting_keystore_filename() {
@@ -337,4 +672,289 @@ mod tests {
             "keystore-m_12381_3600_1_0-1593476250.json"
         ));
     }
+
+    #[test]
+    fn definitions_lock() {
+        let dir = tempdir().unwrap();
+
+        let lock = DefinitionsLock::try_acquire(dir.path()).unwrap();
+        assert!(lock.is_some(), "an unlocked directory should be locked");
+        assert!(
+            DefinitionsLock::try_acquire(dir.path()).unwrap().is_none(),
+            "a locked directory should not be locked again"
+        );
+
+        drop(lock);
+        assert!(
+            DefinitionsLock::try_acquire(dir.path()).unwrap().is_some(),
+            "the lock should be released when dropped"
+        );
+    }
+
+    #[test]
+    fn save_replaces_file() {
+        let dir = tempdir().unwrap();
+
+        let mut defs = ValidatorDefinitions::default();
+        defs.save(dir.path()).unwrap();
+
+        let keystore_path = dir.path().join(VOTING_KEYSTORE_FILE);
+        let keypair = types::Keypair::random();
+        let keystore = eth2_keystore::KeystoreBuilder::new(&keypair, b"cats", "".into())
+            .unwrap()
+            .build()
+            .unwrap();
+        File::create(&keystore_path)
+            .map(|mut file| keystore.to_json_writer(&mut file).unwrap())
+            .unwrap();
+        defs.push(ValidatorDefinition::new_keystore_with_password(&keystore_path, None).unwrap());
+        defs.save(dir.path()).unwrap();
+
+        assert!(!dir.path().join(CONFIG_TEMP_FILENAME).exists());
+        let opened = ValidatorDefinitions::open(dir.path()).unwrap();
+        assert!(opened.as_slice() == defs.as_slice());
+    }
+
+    #[test]
+    fn unknown_fields() {
+        let dir = tempdir().unwrap();
+        let yaml = r#"---
+- enabled: true
+  voting_public_key: "0x87a580d31d7bc69069b55f5a01995a610dd391a26dc9e36e81057a17211983a79266800ab8531f21f1083d7d84085007"
+  operator: alice
+  type: local_keystore
+  voting_keystore_path: /path/to/voting-keystore.json
+"#;
+        fs::write(dir.path().join(CONFIG_FILENAME), yaml).unwrap();
+
+        let mut defs = ValidatorDefinitions::open(dir.path()).unwrap();
+        let def = &defs.as_slice()[0];
+        assert_eq!(def.unknown_fields.len(), 1);
+        assert_eq!(
+            def.unknown_fields["operator"],
+            serde_yaml::Value::from("alice")
+        );
+
+        // The field is kept when the definition is modified and the file is re-encoded.
+        defs.as_mut_slice()[0].enabled = false;
+        defs.save(dir.path()).unwrap();
+        let opened = ValidatorDefinitions::open(dir.path()).unwrap();
+        assert!(opened.as_slice() == defs.as_slice());
+    }
+
+    #[test]
+    fn signing_definition_fields_are_known() {
+        let dir = tempdir().unwrap();
+        let path = PathBuf::from("/path/to/voting-keystore.json");
+        let password_path = PathBuf::from("/path/to/password");
+        let signing_definitions = vec![
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path: path.clone(),
+                voting_keystore_password_path: None,
+                voting_keystore_password: None,
+            },
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path: path,
+                voting_keystore_password_path: Some(password_path),
+                voting_keystore_password: Some("cats".to_string().into()),
+            },
+        ];
+
+        let mut defs = ValidatorDefinitions::default();
+        for signing_definition in signing_definitions {
+            defs.push(ValidatorDefinition {
+                enabled: true,
+                voting_public_key: types::Keypair::random().pk,
+                graffiti: None,
+                suggested_fee_recipient: None,
+                imported_keystore_sha256: None,
+                relative_paths: false,
+                signing_definition,
+                unknown_fields: BTreeMap::new(),
+            });
+        }
+        defs.save(dir.path()).unwrap();
+
+        let opened = ValidatorDefinitions::open(dir.path()).unwrap();
+        for def in opened.as_slice() {
+            assert!(
+                def.unknown_fields.is_empty(),
+                "signing definition fields should not be unknown: {:?}",
+                def.unknown_fields.keys().collect::<Vec<_>>()
+            );
+        }
+        assert!(opened.as_slice() == defs.as_slice());
+    }
+
+    #[test]
+    fn select_and_password() {
+        let dir = tempdir().unwrap();
+        let password_path = dir.path().join("password");
+        fs::write(&password_path, "dogs\n").unwrap();
+        let pubkeys = (0..2)
+            .map(|_| types::Keypair::random().pk.to_hex_string())
+            .collect::<Vec<_>>();
+        let yaml = format!(
+            r#"---
+- enabled: true
+  voting_public_key: "{}"
+  type: local_keystore
+  voting_keystore_path: /path/to/voting-keystore.json
+  voting_keystore_password_path: {:?}
+  voting_keystore_password: cats
+- enabled: true
+  voting_public_key: "{}"
+  type: local_keystore
+  voting_keystore_path: /path/to/voting-keystore.json
+  voting_keystore_password_path: {:?}
+"#,
+            pubkeys[0], password_path, pubkeys[1], password_path
+        );
+        fs::write(dir.path().join(CONFIG_FILENAME), yaml).unwrap();
+        let mut defs = ValidatorDefinitions::open(dir.path()).unwrap();
+
+        assert_eq!(defs.select(None).unwrap().len(), 2);
+        let uppercase = pubkeys[1].trim_start_matches("0x").to_uppercase();
+        let selected = defs.select(Some(&uppercase)).unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].voting_public_key.to_hex_string(), pubkeys[1]);
+        let unknown = types::Keypair::random().pk.to_hex_string();
+        assert!(matches!(
+            defs.select_mut(Some(&unknown)),
+            Err(Error::UnknownPubkey(pubkey)) if pubkey == unknown
+        ));
+
+        // A stored password takes precedence over the password path.
+        let passwords = defs
+            .select_mut(None)
+            .unwrap()
+            .iter()
+            .map(|def| {
+                def.signing_definition
+                    .voting_keystore_password()
+                    .unwrap()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(passwords[0].as_bytes(), b"cats");
+        assert_eq!(passwords[1].as_bytes(), b"dogs");
+    }
+
+    #[test]
+    fn relative_paths() {
+        let dir = tempdir().unwrap();
+        let keystore_dir = dir.path().join("0x00");
+        fs::create_dir(&keystore_dir).unwrap();
+
+        let keystore_path = keystore_dir.join(VOTING_KEYSTORE_FILE);
+        let keypair = types::Keypair::random();
+        let keystore = eth2_keystore::KeystoreBuilder::new(&keypair, b"cats", "".into())
+            .unwrap()
+            .build()
+            .unwrap();
+        File::create(&keystore_path)
+            .map(|mut file| keystore.to_json_writer(&mut file).unwrap())
+            .unwrap();
+
+        let mut def =
+            ValidatorDefinition::new_keystore_with_password(&keystore_path, None).unwrap();
+        def.relative_paths = true;
+        let mut defs = ValidatorDefinitions::default();
+        defs.push(def);
+        defs.save(dir.path()).unwrap();
+
+        let yaml = fs::read_to_string(dir.path().join(CONFIG_FILENAME)).unwrap();
+        assert!(!yaml.contains(dir.path().to_str().unwrap()));
+
+        // The definitions are opened from the new location of the moved directory.
+        let moved_dir = tempdir().unwrap();
+        let moved = moved_dir.path().join("validators");
+        fs::rename(dir.path(), &moved).unwrap();
+
+        let opened = ValidatorDefinitions::open(&moved).unwrap();
+        assert!(opened.as_slice()[0].relative_paths);
+        match &opened.as_slice()[0].signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                ..
+            } => assert_eq!(
+                voting_keystore_path,
+                &moved.join("0x00").join(VOTING_KEYSTORE_FILE)
+            ),
+        }
+
+        opened.save(&moved).unwrap();
+        assert_eq!(
+            fs::read_to_string(moved.join(CONFIG_FILENAME)).unwrap(),
+            yaml
+        );
+    }
+
+    #[test]
+    fn voting_keystores_matching() {
+        let dir = tempdir().unwrap();
+        let sub_dir = dir.path().join("sub");
+        fs::create_dir(&sub_dir).unwrap();
+
+        for path in &[
+            dir.path().join("validator-0.json"),
+            sub_dir.join("validator-1.json"),
+            dir.path().join("validator-2.json.bak"),
+            dir.path().join(VOTING_KEYSTORE_FILE),
+        ] {
+            File::create(path).unwrap();
+        }
+
+        let pattern = Regex::new(r"^validator-[0-9]+\.json$").unwrap();
+        let mut matches = vec![];
+        recursively_find_voting_keystores_matching(dir.path(), &pattern, false, &mut matches)
+            .unwrap();
+        matches.sort();
+
+        assert_eq!(
+            matches,
+            vec![
+                sub_dir.join("validator-1.json"),
+                dir.path().join("validator-0.json"),
+            ]
+        );
+
+        let mut matches = vec![];
+        recursively_find_voting_keystores(dir.path(), false, &mut matches).unwrap();
+        assert_eq!(matches, vec![dir.path().join(VOTING_KEYSTORE_FILE)]);
+    }
+
+    #[test]
+    fn voting_keystores_skip_staging_dir() {
+        let dir = tempdir().unwrap();
+        let staging_dir = dir.path().join(STAGING_DIR).join("uuid");
+        fs::create_dir_all(&staging_dir).unwrap();
+        File::create(staging_dir.join(VOTING_KEYSTORE_FILE)).unwrap();
+
+        let mut matches = vec![];
+        recursively_find_voting_keystores(dir.path(), false, &mut matches).unwrap();
+        assert!(matches.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn voting_keystores_symlinks() {
+        let dir = tempdir().unwrap();
+        let target_dir = tempdir().unwrap();
+        let target = target_dir.path().join(VOTING_KEYSTORE_FILE);
+        File::create(&target).unwrap();
+
+        let sub_dir = dir.path().join("sub");
+        fs::create_dir(&sub_dir).unwrap();
+        std::os::unix::fs::symlink(&target, sub_dir.join(VOTING_KEYSTORE_FILE)).unwrap();
+        // A link to an ancestor must not be searched again.
+        std::os::unix::fs::symlink(dir.path(), sub_dir.join("loop")).unwrap();
+
+        let mut matches = vec![];
+        recursively_find_voting_keystores(dir.path(), false, &mut matches).unwrap();
+        assert!(matches.is_empty());
+
+        recursively_find_voting_keystores(dir.path(), true, &mut matches).unwrap();
+        assert_eq!(matches, vec![sub_dir.join(VOTING_KEYSTORE_FILE)]);
+    }
 }
@@ -5,7 +5,8 @@ use criterion::{black_box, criterion_group, criterion_main, Benchmark};
 use ssz::Encode;
 use state_processing::{test_utils::BlockBuilder, BlockSignatureStrategy, VerifySignatures};
 use types::{
-    BeaconState, ChainSpec, EthSpec, MainnetEthSpec, MinimalEthSpec, SignedBeaconBlock, Slot,
+    BeaconState, ChainSpec, EthSpec, MainnetEthSpec, MinimalEthSpec, RelativeEpoch,
+    SignedBeaconBlock, Slot, Unsigned,
 };
 
 pub const VALIDATORS_LOW: usize = 32_768;
@@ -21,6 +22,12 @@ fn all_benches(c: &mut Criterion) {
     worst_bench::<MinimalEthSpec>(c, "minimal", VALIDATORS_LOW);
     worst_bench::<MainnetEthSpec>(c, "mainnet", VALIDATORS_LOW);
     worst_bench::<MainnetEthSpec>(c, "mainnet", VALIDATORS_HIGH);
+
+    attestations_bench::<MinimalEthSpec>(c, "minimal", VALIDATORS_LOW);
+    attestations_bench::<MainnetEthSpec>(c, "mainnet", VALIDATORS_LOW);
+
+    block_header_bench::<MainnetEthSpec>(c, "mainnet", VALIDATORS_LOW);
+    block_header_bench::<MainnetEthSpec>(c, "mainnet", VALIDATORS_HIGH);
 }
 
 /// Run a bench with a average complexity block.
@@ -42,6 +49,35 @@ fn worst_bench<T: EthSpec>(c: &mut Criterion, spec_desc: &str, validator_count:
     bench_block::<T>(c, block, state, spec, spec_desc, "high_complexity_block");
 }
 
+/// Run a bench of attestation processing, with and without the committee caches built.
+fn attestations_bench<T: EthSpec>(c: &mut Criterion, spec_desc: &str, validator_count: usize) {
+    let spec = &T::default_spec();
+
+    let (block, state) = get_attestations_block(validator_count, spec);
+    bench_attestations::<T>(c, block, state, spec, spec_desc);
+}
+
+/// Run a bench of block header processing, with and without the current committee cache built.
+fn block_header_bench<T: EthSpec>(c: &mut Criterion, spec_desc: &str, validator_count: usize) {
+    let spec = &T::default_spec();
+
+    let (block, state) = get_average_block(validator_count, spec);
+    bench_block_header::<T>(c, block, state, spec, spec_desc);
+}
+
+/// Return a block and state where the block has the maximum number of attestations, and no other
+/// operations.
+fn get_attestations_block<T: EthSpec>(
+    validator_count: usize,
+    spec: &ChainSpec,
+) -> (SignedBeaconBlock<T>, BeaconState<T>) {
+    let mut builder: BlockBuilder<T> = BlockBuilder::new(validator_count, &spec);
+    builder.num_attestations = T::MaxAttestations::to_usize();
+    builder.set_slot(Slot::from(T::slots_per_epoch() * 3 - 2));
+    builder.build_caches(&spec);
+    builder.build(&spec)
+}
+
 /// Return a block and state where the block has "average" complexity. I.e., the number of
 /// operations we'd generally expect to see.
 fn get_average_block<T: EthSpec>(
@@ -65,9 +101,6 @@ fn get_worst_block<T: EthSpec>(
     let mut builder: BlockBuilder<T> = BlockBuilder::new(validator_count, &spec);
     builder.maximize_block_operations();
 
-    // FIXME: enable deposits once we can generate them with valid proofs.
-    builder.num_deposits = 0;
-
     builder.set_slot(Slot::from(T::slots_per_epoch() * 3 - 2));
     builder.build_caches(&spec);
     builder.build(&spec)
@@ -147,6 +180,35 @@ fn bench_block<T: EthSpec>(
         .sample_size(10),
     );
 
+    let local_block = block.clone();
+    let local_state = state.clone();
+    let local_spec = spec.clone();
+    c.bench(
+        &title,
+        Benchmark::new(
+            "per_block_processing/bulk_attestation_signature_verification",
+            move |b| {
+                b.iter_batched_ref(
+                    || (local_spec.clone(), local_state.clone(), local_block.clone()),
+                    |(spec, ref mut state, block)| {
+                        black_box(
+                            state_processing::per_block_processing::<T>(
+                                state,
+                                &block,
+                                None,
+                                BlockSignatureStrategy::VerifyAttestationsBulk,
+                                &spec,
+                            )
+                            .expect("block processing should succeed"),
+                        )
+                    },
+                    criterion::BatchSize::SmallInput,
+                )
+            },
+        )
+        .sample_size(10),
+    );
+
     let local_block = block.clone();
     let local_state = state.clone();
     let local_spec = spec.clone();
@@ -425,5 +487,149 @@ fn bench_block<T: EthSpec>(
     );
 }
 
+#[allow(clippy::unit_arg)]
+fn bench_attestations<T: EthSpec>(
+    c: &mut Criterion,
+    block: SignedBeaconBlock<T>,
+    state: BeaconState<T>,
+    spec: &ChainSpec,
+    spec_desc: &str,
+) {
+    let validator_count = state.validators.len();
+
+    let title = &format!(
+        "{}/{}_validators/max_attestations_block",
+        spec_desc, validator_count
+    );
+
+    // Signatures are not verified, since they would dominate the time spent using the committees.
+    let local_block = block.clone();
+    let local_state = state.clone();
+    let local_spec = spec.clone();
+    c.bench(
+        &title,
+        Benchmark::new("process_attestations/warm_committee_cache", move |b| {
+            b.iter_batched_ref(
+                || (local_spec.clone(), local_state.clone(), local_block.clone()),
+                |(spec, ref mut state, block)| {
+                    black_box(
+                        state_processing::per_block_processing::process_attestations::<T>(
+                            state,
+                            &block.message.body.attestations,
+                            VerifySignatures::False,
+                            &spec,
+                        )
+                        .expect("attestation processing should succeed"),
+                    )
+                },
+                criterion::BatchSize::SmallInput,
+            )
+        })
+        .sample_size(10),
+    );
+
+    // The committee caches are built as part of processing, as when a state is loaded from the
+    // database.
+    let local_block = block;
+    let local_state = state;
+    let local_spec = spec.clone();
+    c.bench(
+        &title,
+        Benchmark::new("process_attestations/cold_committee_cache", move |b| {
+            b.iter_batched_ref(
+                || {
+                    let mut state = local_state.clone();
+                    state.drop_committee_cache(RelativeEpoch::Previous);
+                    state.drop_committee_cache(RelativeEpoch::Current);
+                    (local_spec.clone(), state, local_block.clone())
+                },
+                |(spec, ref mut state, block)| {
+                    state
+                        .build_committee_cache(RelativeEpoch::Current, &spec)
+                        .expect("should build committee cache");
+                    black_box(
+                        state_processing::per_block_processing::process_attestations::<T>(
+                            state,
+                            &block.message.body.attestations,
+                            VerifySignatures::False,
+                            &spec,
+                        )
+                        .expect("attestation processing should succeed"),
+                    )
+                },
+                criterion::BatchSize::SmallInput,
+            )
+        })
+        .sample_size(10),
+    );
+}
+
+#[allow(clippy::unit_arg)]
+fn bench_block_header<T: EthSpec>(
+    c: &mut Criterion,
+    block: SignedBeaconBlock<T>,
+    state: BeaconState<T>,
+    spec: &ChainSpec,
+    spec_desc: &str,
+) {
+    let validator_count = state.validators.len();
+
+    let title = &format!(
+        "{}/{}_validators/average_complexity_block",
+        spec_desc, validator_count
+    );
+
+    // The proposer is found from the active validators in the committee cache.
+    let local_block = block.clone();
+    let local_state = state.clone();
+    let local_spec = spec.clone();
+    c.bench(
+        &title,
+        Benchmark::new("process_block_header/warm_committee_cache", move |b| {
+            b.iter_batched_ref(
+                || (local_spec.clone(), local_state.clone(), local_block.clone()),
+                |(spec, ref mut state, block)| {
+                    black_box(
+                        state_processing::per_block_processing::process_block_header::<T>(
+                            state,
+                            &block.message,
+                            &spec,
+                        )
+                        .expect("block header processing should succeed"),
+                    )
+                },
+                criterion::BatchSize::SmallInput,
+            )
+        })
+        .sample_size(10),
+    );
+
+    // Without the committee cache, the proposer is found from the validator registry.
+    let local_block = block;
+    let mut local_state = state;
+    local_state.drop_committee_cache(RelativeEpoch::Current);
+    let local_spec = spec.clone();
+    c.bench(
+        &title,
+        Benchmark::new("process_block_header/cold_committee_cache", move |b| {
+            b.iter_batched_ref(
+                || (local_spec.clone(), local_state.clone(), local_block.clone()),
+                |(spec, ref mut state, block)| {
+                    black_box(
+                        state_processing::per_block_processing::process_block_header::<T>(
+                            state,
+                            &block.message,
+                            &spec,
+                        )
+                        .expect("block header processing should succeed"),
+                    )
+                },
+                criterion::BatchSize::SmallInput,
+            )
+        })
+        .sample_size(10),
+    );
+}
+
 criterion_group!(benches, all_benches,);
 criterion_main!(benches);
@@ -16,7 +16,9 @@ pub use genesis::{
     process_activations,
 };
 pub use per_block_processing::{
-    block_signature_verifier, errors::BlockProcessingError, per_block_processing, signature_sets,
+    block_signature_verifier,
+    errors::{BlockProcessingError, HeaderInvalid},
+    per_block_processing, per_block_processing_with_timer, signature_sets, BlockProcessingTimings,
     BlockSignatureStrategy, BlockSignatureVerifier, VerifySignatures,
 };
 pub use per_epoch_processing::{errors::EpochProcessingError, per_epoch_processing};
@@ -12,6 +12,7 @@ pub use self::verify_attester_slashing::{
 pub use self::verify_proposer_slashing::verify_proposer_slashing;
 pub use block_signature_verifier::BlockSignatureVerifier;
 pub use is_valid_indexed_attestation::is_valid_indexed_attestation;
+pub use timings::{BlockProcessingStage, BlockProcessingTimer, BlockProcessingTimings, NoTimer};
 pub use verify_attestation::{
     verify_attestation_for_block_inclusion, verify_attestation_for_state,
 };
@@ -26,6 +27,7 @@ pub mod errors;
 mod is_valid_indexed_attestation;
 pub mod signature_sets;
 pub mod tests;
+mod timings;
 mod verify_attestation;
 mod verify_attester_slashing;
 mod verify_deposit;
@@ -45,6 +47,10 @@ pub enum BlockSignatureStrategy {
     VerifyIndividual,
     /// Verify all signatures in bulk at the beginning of block processing.
     VerifyBulk,
+    /// Validate each signature individually, except for the attestation signatures which are
+    /// verified together in a single batch. If the batch fails, the attestation signatures are
+    /// verified individually to identify the invalid attestation.
+    VerifyAttestationsBulk,
 }
 
 /// The strategy to be used when validating the block's signatures.
@@ -77,11 +83,32 @@ impl VerifySignatures {
 ///
 /// Spec v0.12.1
 pub fn per_block_processing<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    signed_block: &SignedBeaconBlock<T>,
+    block_root: Option<Hash256>,
+    block_signature_strategy: BlockSignatureStrategy,
+    spec: &ChainSpec,
+) -> Result<(), BlockProcessingError> {
+    per_block_processing_with_timer(
+        state,
+        signed_block,
+        block_root,
+        block_signature_strategy,
+        spec,
+        &mut NoTimer,
+    )
+}
+
+/// As `per_block_processing`, but passes each stage of processing to `timer`.
+///
+/// Use `BlockProcessingTimings` as the `timer` to find the duration of each stage.
+pub fn per_block_processing_with_timer<T: EthSpec, U: BlockProcessingTimer>(
     mut state: &mut BeaconState<T>,
     signed_block: &SignedBeaconBlock<T>,
     block_root: Option<Hash256>,
     block_signature_strategy: BlockSignatureStrategy,
     spec: &ChainSpec,
+    timer: &mut U,
 ) -> Result<(), BlockProcessingError> {
     let block = &signed_block.message;
     let verify_signatures = match block_signature_strategy {
@@ -100,47 +127,75 @@ pub fn per_block_processing<T: EthSpec>(
             );
             VerifySignatures::False
         }
-        BlockSignatureStrategy::VerifyIndividual => VerifySignatures::True,
+        BlockSignatureStrategy::VerifyIndividual
+        | BlockSignatureStrategy::VerifyAttestationsBulk => VerifySignatures::True,
         BlockSignatureStrategy::NoVerification => VerifySignatures::False,
     };
 
-    process_block_header(state, block, spec)?;
+    // Ensure the current and previous epoch caches are built. The current epoch cache is also used
+    // to find the proposer, so it is built before the header is processed.
+    state.build_committee_cache(RelativeEpoch::Previous, spec)?;
+    state.build_committee_cache(RelativeEpoch::Current, spec)?;
+
+    timer.time(BlockProcessingStage::Header, || {
+        process_block_header(state, block, spec)
+    })?;
 
     if verify_signatures.is_true() {
         verify_block_signature(&state, signed_block, block_root, &spec)?;
     }
 
-    // Ensure the current and previous epoch caches are built.
-    state.build_committee_cache(RelativeEpoch::Previous, spec)?;
-    state.build_committee_cache(RelativeEpoch::Current, spec)?;
-
-    process_randao(&mut state, &block, verify_signatures, &spec)?;
-    process_eth1_data(&mut state, &block.body.eth1_data)?;
-    process_proposer_slashings(
-        &mut state,
-        &block.body.proposer_slashings,
-        verify_signatures,
-        spec,
-    )?;
-    process_attester_slashings(
-        &mut state,
-        &block.body.attester_slashings,
-        verify_signatures,
-        spec,
-    )?;
-    process_attestations(
-        &mut state,
-        &block.body.attestations,
-        verify_signatures,
-        spec,
-    )?;
-    process_deposits(&mut state, &block.body.deposits, spec)?;
-    process_exits(
-        &mut state,
-        &block.body.voluntary_exits,
-        verify_signatures,
-        spec,
-    )?;
+    timer.time(BlockProcessingStage::Randao, || {
+        process_randao(&mut state, &block, verify_signatures, &spec)
+    })?;
+    timer.time(BlockProcessingStage::Eth1Data, || {
+        process_eth1_data(&mut state, &block.body.eth1_data)
+    })?;
+    timer.time(BlockProcessingStage::ProposerSlashings, || {
+        process_proposer_slashings(
+            &mut state,
+            &block.body.proposer_slashings,
+            verify_signatures,
+            spec,
+        )
+    })?;
+    timer.time(BlockProcessingStage::AttesterSlashings, || {
+        process_attester_slashings(
+            &mut state,
+            &block.body.attester_slashings,
+            verify_signatures,
+            spec,
+        )
+    })?;
+    timer.time(BlockProcessingStage::Attestations, || {
+        // If the batch of attestation signatures is valid there is no need to verify them again,
+        // otherwise fall back to verifying each signature so the invalid attestation is reported.
+        let verify_attestation_signatures = match block_signature_strategy {
+            BlockSignatureStrategy::VerifyAttestationsBulk
+                if verify_attestation_signatures_in_bulk(state, signed_block, spec) =>
+            {
+                VerifySignatures::False
+            }
+            _ => verify_signatures,
+        };
+        process_attestations(
+            &mut state,
+            &block.body.attestations,
+            verify_attestation_signatures,
+            spec,
+        )
+    })?;
+    timer.time(BlockProcessingStage::Deposits, || {
+        process_deposits(&mut state, &block.body.deposits, spec)
+    })?;
+    timer.time(BlockProcessingStage::Exits, || {
+        process_exits(
+            &mut state,
+            &block.body.voluntary_exits,
+            verify_signatures,
+            spec,
+        )
+    })?;
 
     Ok(())
 }
@@ -221,6 +276,21 @@ pub fn verify_block_signature<T: EthSpec>(
     Ok(())
 }
 
+/// Verifies the signatures of all attestations in the block as a single batch.
+///
+/// Returns `false` if any signature is invalid or a signature set could not be built. It is not
+/// possible to know which attestation was invalid, see `BlockSignatureVerifier::verify`.
+fn verify_attestation_signatures_in_bulk<T: EthSpec>(
+    state: &BeaconState<T>,
+    signed_block: &SignedBeaconBlock<T>,
+    spec: &ChainSpec,
+) -> bool {
+    let mut verifier =
+        BlockSignatureVerifier::new(state, |i| get_pubkey_from_state(state, i), spec);
+
+    verifier.include_attestations(signed_block).is_ok() && verifier.verify().is_ok()
+}
+
 /// Verifies the `randao_reveal` against the block's proposer pubkey and updates
 /// `state.latest_randao_mixes`.
 ///
@@ -368,7 +438,7 @@ pub fn process_attestations<T: EthSpec>(
         let pending_attestation = PendingAttestation {
             aggregation_bits: attestation.aggregation_bits.clone(),
             data: attestation.data.clone(),
-            inclusion_delay: (state.slot - attestation.data.slot).as_u64(),
+            inclusion_delay: state.slot.safe_sub(attestation.data.slot)?.as_u64(),
             proposer_index,
         };
 
@@ -172,22 +172,30 @@ impl<T> From<ArithError> for BlockOperationError<T> {
     }
 }
 
+/// Describes why a block header is invalid.
+///
+/// Returned by `process_block_header` and `verify_block_signature`. New reasons may be added, so
+/// matches outside of this crate must include a wildcard arm.
 #[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
 pub enum HeaderInvalid {
+    /// The block proposal signature was invalid.
     ProposalSignatureInvalid,
+    /// The block slot does not match the state slot.
     StateSlotMismatch,
+    /// The block is not newer than the latest block header in the state.
     OlderThanLatestBlockHeader {
         latest_block_header_slot: Slot,
         block_slot: Slot,
     },
+    /// The block was not proposed by the proposer for the slot.
     ProposerIndexMismatch {
         block_proposer_index: usize,
         state_proposer_index: usize,
     },
-    ParentBlockRootMismatch {
-        state: Hash256,
-        block: Hash256,
-    },
+    /// The block parent root does not match the root of the latest block header in the state.
+    ParentBlockRootMismatch { state: Hash256, block: Hash256 },
+    /// The proposer at the given index has been slashed.
     ProposerSlashed(usize),
 }
 
@@ -316,6 +324,9 @@ pub enum DepositInvalid {
     /// The specified `branch` and `index` did not form a valid proof that the deposit is included
     /// in the eth1 deposit root.
     BadMerkleProof,
+    /// The `branch` does not have the `deposit_contract_tree_depth + 1` nodes required to prove
+    /// inclusion in the eth1 deposit root.
+    BadMerkleProofLength { expected: usize, found: usize },
 }
 
 #[derive(Debug, PartialEq, Clone)]
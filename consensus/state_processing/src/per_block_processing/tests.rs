@@ -2,9 +2,20 @@
 
 use super::block_processing_builder::BlockProcessingBuilder;
 use super::errors::*;
-use crate::{per_block_processing, BlockSignatureStrategy};
+use super::{
+    process_block_header, process_deposits, process_eth1_data, BlockProcessingStage,
+    BlockProcessingTimer,
+};
+use crate::{
+    per_block_processing, per_block_processing_with_timer, BlockProcessingTimings,
+    BlockSignatureStrategy,
+};
+use safe_arith::ArithError;
+use std::time::Instant;
 use types::test_utils::{
-    AttestationTestTask, AttesterSlashingTestTask, DepositTestTask, ProposerSlashingTestTask,
+    generate_deterministic_keypair, AttestationTestTask, AttesterSlashingTestTask, DepositTestTask,
+    ProposerSlashingTestTask, TestingBeaconBlockBuilder, TestingBeaconStateBuilder,
+    TestingDepositBuilder,
 };
 use types::*;
 
@@ -32,6 +43,55 @@ fn valid_block_ok() {
     assert_eq!(result, Ok(()));
 }
 
+#[test]
+fn valid_block_timings() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec, EPOCH_OFFSET, VALIDATOR_COUNT);
+    let (block, mut state) = builder.build(None, None);
+
+    let mut timer = StageRecorder::default();
+    let start = Instant::now();
+    let result = per_block_processing_with_timer(
+        &mut state,
+        &block,
+        None,
+        BlockSignatureStrategy::VerifyIndividual,
+        &spec,
+        &mut timer,
+    );
+    let elapsed = start.elapsed();
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(
+        timer.stages,
+        vec![
+            BlockProcessingStage::Header,
+            BlockProcessingStage::Randao,
+            BlockProcessingStage::Eth1Data,
+            BlockProcessingStage::ProposerSlashings,
+            BlockProcessingStage::AttesterSlashings,
+            BlockProcessingStage::Attestations,
+            BlockProcessingStage::Deposits,
+            BlockProcessingStage::Exits,
+        ]
+    );
+    assert!(timer.timings.total() <= elapsed);
+}
+
+/// Records the order of the stages timed, as well as their durations.
+#[derive(Default)]
+struct StageRecorder {
+    stages: Vec<BlockProcessingStage>,
+    timings: BlockProcessingTimings,
+}
+
+impl BlockProcessingTimer for StageRecorder {
+    fn time<R, F: FnOnce() -> R>(&mut self, stage: BlockProcessingStage, f: F) -> R {
+        self.stages.push(stage);
+        self.timings.time(stage, f)
+    }
+}
+
 #[test]
 fn invalid_block_header_state_slot() {
     let spec = MainnetEthSpec::default_spec();
@@ -83,6 +143,23 @@ fn invalid_parent_block_root() {
     );
 }
 
+#[test]
+fn valid_block_header_max_slot() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec, EPOCH_OFFSET, VALIDATOR_COUNT);
+    let (mut block, mut state) = builder.build(None, None);
+
+    state.slot = Slot::max_value();
+    block.message.slot = Slot::max_value();
+    block.message.proposer_index =
+        state.get_beacon_proposer_index(state.slot, &spec).unwrap() as u64;
+
+    let result = process_block_header(&mut state, &block.message, &spec);
+
+    // Expecting Ok because the seed and proposer for the last slot can be computed
+    assert_eq!(result, Ok(()));
+}
+
 #[test]
 fn invalid_block_signature() {
     let spec = MainnetEthSpec::default_spec();
@@ -116,6 +193,86 @@ fn invalid_block_signature() {
     );
 }
 
+#[test]
+fn invalid_block_header_proposer_index() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec, EPOCH_OFFSET, VALIDATOR_COUNT);
+    let (mut block, mut state) = builder.build(None, None);
+
+    let state_proposer_index = block.message.proposer_index as usize;
+    let block_proposer_index = (state_proposer_index + 1) % VALIDATOR_COUNT;
+    block.message.proposer_index = block_proposer_index as u64;
+
+    let result = process_block_header(&mut state, &block.message, &spec);
+
+    assert_eq!(
+        result,
+        Err(BlockOperationError::invalid(
+            HeaderInvalid::ProposerIndexMismatch {
+                block_proposer_index,
+                state_proposer_index,
+            }
+        ))
+    );
+}
+
+#[test]
+fn invalid_block_header_proposer_index_correctly_signed() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec, EPOCH_OFFSET, VALIDATOR_COUNT);
+    let (block, mut state) = builder.build(None, None);
+
+    // The block is signed by the validator it claims as the proposer, so only the proposer index
+    // check can reject it.
+    let state_proposer_index = block.message.proposer_index as usize;
+    let block_proposer_index = (state_proposer_index + 1) % VALIDATOR_COUNT;
+    let mut message = block.message;
+    message.proposer_index = block_proposer_index as u64;
+    let block = message.sign(
+        &generate_deterministic_keypair(block_proposer_index).sk,
+        &state.fork,
+        state.genesis_validators_root,
+        &spec,
+    );
+
+    let result = per_block_processing(
+        &mut state,
+        &block,
+        None,
+        BlockSignatureStrategy::VerifyIndividual,
+        &spec,
+    );
+
+    assert_eq!(
+        result,
+        Err(BlockProcessingError::HeaderInvalid {
+            reason: HeaderInvalid::ProposerIndexMismatch {
+                block_proposer_index,
+                state_proposer_index,
+            }
+        })
+    );
+}
+
+#[test]
+fn invalid_block_header_proposer_slashed() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec, EPOCH_OFFSET, VALIDATOR_COUNT);
+    let (block, mut state) = builder.build(None, None);
+
+    let proposer_index = block.message.proposer_index as usize;
+    state.validators[proposer_index].slashed = true;
+
+    let result = process_block_header(&mut state, &block.message, &spec);
+
+    assert_eq!(
+        result,
+        Err(BlockOperationError::invalid(
+            HeaderInvalid::ProposerSlashed(proposer_index)
+        ))
+    );
+}
+
 #[test]
 fn invalid_randao_reveal_signature() {
     let spec = MainnetEthSpec::default_spec();
@@ -217,6 +374,32 @@ fn invalid_deposit_count_too_small() {
     );
 }
 
+#[test]
+fn valid_inserted_deposits() {
+    let spec = MinimalEthSpec::default_spec();
+    let (mut state, _) = TestingBeaconStateBuilder::<MinimalEthSpec>::from_deterministic_keypairs(
+        VALIDATOR_COUNT,
+        &spec,
+    )
+    .build();
+
+    let mut builder = TestingBeaconBlockBuilder::new_with_seed(&spec, 42);
+    for i in 0..2 {
+        let keypair = generate_deterministic_keypair(VALIDATOR_COUNT + i);
+        let mut deposit =
+            TestingDepositBuilder::new(keypair.pk.clone(), spec.max_effective_balance);
+        deposit.sign(DepositTestTask::Valid, &keypair, &spec);
+        builder.insert_deposit(deposit.build().data, &mut state, &spec);
+    }
+    let block = builder.build_without_signing();
+
+    let result = process_deposits(&mut state, &block.message.body.deposits, &spec);
+
+    // Expecting Ok because the proofs were generated against the state's deposit root
+    assert_eq!(result, Ok(()));
+    assert_eq!(state.validators.len(), VALIDATOR_COUNT + 2);
+}
+
 #[test]
 fn invalid_deposit_bad_merkle_proof() {
     let spec = MainnetEthSpec::default_spec();
@@ -249,6 +432,40 @@ fn invalid_deposit_bad_merkle_proof() {
     );
 }
 
+#[test]
+fn invalid_deposit_bad_merkle_proof_length() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec, EPOCH_OFFSET, VALIDATOR_COUNT);
+    let test_task = DepositTestTask::Valid;
+
+    let (block, state) = builder.build_with_n_deposits(NUM_DEPOSITS, test_task, None, None, &spec);
+    let proof_len = spec.deposit_contract_tree_depth as usize + 1;
+
+    // Truncate the proof, then extend it with a zero node.
+    for found in vec![proof_len - 1, proof_len + 1] {
+        let mut deposits = block.message.body.deposits.to_vec();
+        let mut proof = deposits[0].proof.to_vec();
+        proof.resize(found, Hash256::zero());
+        // `FixedVector` does not check its length when deserialized with serde.
+        deposits[0].proof = serde_yaml::from_str(&serde_yaml::to_string(&proof).unwrap()).unwrap();
+        let mut state = state.clone();
+
+        let result = process_deposits(&mut state, &deposits, &spec);
+
+        // Expecting BadMerkleProofLength because the proof no longer has a node per tree level
+        assert_eq!(
+            result,
+            Err(BlockProcessingError::DepositInvalid {
+                index: 0,
+                reason: DepositInvalid::BadMerkleProofLength {
+                    expected: proof_len,
+                    found,
+                }
+            })
+        );
+    }
+}
+
 #[test]
 fn invalid_deposit_wrong_pubkey() {
     let spec = MainnetEthSpec::default_spec();
@@ -480,6 +697,64 @@ fn invalid_attestation_bad_signature() {
     );
 }
 
+#[test]
+fn valid_attestations_bulk() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec, EPOCH_OFFSET, VALIDATOR_COUNT);
+    let test_task = AttestationTestTask::Valid;
+    let (block, mut state) = builder.build_with_n_attestations(test_task, 2, None, None, &spec);
+
+    let result = per_block_processing(
+        &mut state,
+        &block,
+        None,
+        BlockSignatureStrategy::VerifyAttestationsBulk,
+        &spec,
+    );
+
+    // Expecting Ok(()) because these are valid attestations
+    assert_eq!(result, Ok(()));
+}
+
+#[test]
+fn invalid_attestation_bad_signature_bulk() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec, EPOCH_OFFSET, VALIDATOR_COUNT);
+    let test_task = AttestationTestTask::Valid;
+    let (block, mut state) = builder.build_with_n_attestations(test_task, 2, None, None, &spec);
+
+    // Give the second attestation the signature of the first and re-sign the block.
+    let mut block = block.message;
+    block.body.attestations[1].signature = block.body.attestations[0].signature.clone();
+    let proposer_sk = generate_deterministic_keypair(block.proposer_index as usize).sk;
+    let block = block.sign(
+        &proposer_sk,
+        &state.fork,
+        state.genesis_validators_root,
+        &spec,
+    );
+
+    let result = per_block_processing(
+        &mut state,
+        &block,
+        None,
+        BlockSignatureStrategy::VerifyAttestationsBulk,
+        &spec,
+    );
+
+    // Expecting BadSignature for the second attestation because the failed batch falls back to
+    // verifying each signature
+    assert_eq!(
+        result,
+        Err(BlockProcessingError::AttestationInvalid {
+            index: 1,
+            reason: AttestationInvalid::BadIndexedAttestation(
+                IndexedAttestationInvalid::BadSignature
+            )
+        })
+    );
+}
+
 #[test]
 fn invalid_attestation_included_too_early() {
     let spec = MainnetEthSpec::default_spec();
@@ -539,6 +814,33 @@ fn invalid_attestation_included_too_late() {
     );
 }
 
+#[test]
+fn invalid_attestation_slot_overflow() {
+    let spec = MainnetEthSpec::default_spec();
+    let slot = Epoch::new(EPOCH_OFFSET).start_slot(E::slots_per_epoch());
+    let builder =
+        get_builder(&spec, EPOCH_OFFSET, VALIDATOR_COUNT).insert_attestation(slot, 0, |_, _| true);
+    let (block, mut state) = builder
+        .modify(|block| {
+            block.body.attestations[0].data.slot = Slot::max_value();
+        })
+        .build(None, None);
+
+    let result = per_block_processing(
+        &mut state,
+        &block,
+        None,
+        BlockSignatureStrategy::VerifyIndividual,
+        &spec,
+    );
+
+    // Expecting an overflow rather than a saturated inclusion delay check
+    assert_eq!(
+        result,
+        Err(BlockProcessingError::ArithError(ArithError::Overflow))
+    );
+}
+
 #[test]
 fn invalid_attestation_target_epoch_slot_mismatch() {
     let spec = MainnetEthSpec::default_spec();
@@ -884,6 +1186,54 @@ fn invalid_proposer_slashing_proposal_epoch_mismatch() {
     );
 }
 
+#[test]
+fn eth1_data_majority_vote() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec, EPOCH_OFFSET, VALIDATOR_COUNT);
+    let (_, mut state) = builder.build(None, None);
+
+    let eth1_data = Eth1Data {
+        deposit_root: Hash256::repeat_byte(42),
+        deposit_count: state.eth1_data.deposit_count,
+        block_hash: Hash256::repeat_byte(42),
+    };
+
+    // The `eth1_data` is only adopted once more than half of the voting period has voted for it.
+    for _ in 0..E::slots_per_eth1_voting_period() / 2 {
+        process_eth1_data(&mut state, &eth1_data).unwrap();
+        assert_ne!(state.eth1_data, eth1_data);
+    }
+    process_eth1_data(&mut state, &eth1_data).unwrap();
+    assert_eq!(state.eth1_data, eth1_data);
+}
+
+#[test]
+fn invalid_eth1_data_votes_full() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec, EPOCH_OFFSET, VALIDATOR_COUNT);
+    let (_, mut state) = builder.build(None, None);
+
+    let eth1_data = state.eth1_data.clone();
+    while state.eth1_data_votes.len() < E::slots_per_eth1_voting_period() {
+        process_eth1_data(&mut state, &eth1_data).unwrap();
+    }
+
+    // A vote beyond the end of the voting period is an error, rather than a panic.
+    assert_eq!(
+        process_eth1_data(&mut state, &eth1_data),
+        Err(BeaconStateError::SszTypesError(
+            ssz_types::Error::OutOfBounds {
+                i: E::slots_per_eth1_voting_period() + 1,
+                len: E::slots_per_eth1_voting_period(),
+            }
+        ))
+    );
+    assert_eq!(
+        state.eth1_data_votes.len(),
+        E::slots_per_eth1_voting_period()
+    );
+}
+
 fn get_builder(
     spec: &ChainSpec,
     epoch_offset: u64,
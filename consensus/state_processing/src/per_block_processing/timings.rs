@@ -0,0 +1,90 @@
+use std::time::{Duration, Instant};
+
+/// A stage of `per_block_processing` which is timed by a `BlockProcessingTimer`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BlockProcessingStage {
+    Header,
+    Randao,
+    Eth1Data,
+    ProposerSlashings,
+    AttesterSlashings,
+    Attestations,
+    Deposits,
+    Exits,
+}
+
+/// Collects the duration of each stage of `per_block_processing_with_timer`.
+pub trait BlockProcessingTimer {
+    /// Runs `f`, which performs `stage`, and returns its result.
+    fn time<R, F: FnOnce() -> R>(&mut self, stage: BlockProcessingStage, f: F) -> R;
+}
+
+/// A timer which records nothing, used by `per_block_processing` so that block processing has no
+/// timing overhead unless it is requested.
+pub struct NoTimer;
+
+impl BlockProcessingTimer for NoTimer {
+    #[inline(always)]
+    fn time<R, F: FnOnce() -> R>(&mut self, _stage: BlockProcessingStage, f: F) -> R {
+        f()
+    }
+}
+
+/// The time spent in each stage of `per_block_processing_with_timer`.
+///
+/// Signature verification which happens before the header is processed (i.e., the bulk
+/// verification of `BlockSignatureStrategy::VerifyBulk` and the proposer signature) is not
+/// attributed to any stage.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct BlockProcessingTimings {
+    pub header: Duration,
+    pub randao: Duration,
+    pub eth1_data: Duration,
+    pub proposer_slashings: Duration,
+    pub attester_slashings: Duration,
+    /// Includes the bulk verification of attestation signatures with
+    /// `BlockSignatureStrategy::VerifyAttestationsBulk`.
+    pub attestations: Duration,
+    pub deposits: Duration,
+    pub exits: Duration,
+}
+
+impl BlockProcessingTimings {
+    /// Returns the sum of the durations of all stages.
+    pub fn total(&self) -> Duration {
+        [
+            self.header,
+            self.randao,
+            self.eth1_data,
+            self.proposer_slashings,
+            self.attester_slashings,
+            self.attestations,
+            self.deposits,
+            self.exits,
+        ]
+        .iter()
+        .sum()
+    }
+
+    fn stage_mut(&mut self, stage: BlockProcessingStage) -> &mut Duration {
+        match stage {
+            BlockProcessingStage::Header => &mut self.header,
+            BlockProcessingStage::Randao => &mut self.randao,
+            BlockProcessingStage::Eth1Data => &mut self.eth1_data,
+            BlockProcessingStage::ProposerSlashings => &mut self.proposer_slashings,
+            BlockProcessingStage::AttesterSlashings => &mut self.attester_slashings,
+            BlockProcessingStage::Attestations => &mut self.attestations,
+            BlockProcessingStage::Deposits => &mut self.deposits,
+            BlockProcessingStage::Exits => &mut self.exits,
+        }
+    }
+}
+
+impl BlockProcessingTimer for BlockProcessingTimings {
+    fn time<R, F: FnOnce() -> R>(&mut self, stage: BlockProcessingStage, f: F) -> R {
+        let start = Instant::now();
+        let result = f();
+        *self.stage_mut(stage) += start.elapsed();
+        result
+    }
+}
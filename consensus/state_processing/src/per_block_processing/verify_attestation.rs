@@ -25,7 +25,7 @@ pub fn verify_attestation_for_block_inclusion<T: EthSpec>(
     let data = &attestation.data;
 
     verify!(
-        data.slot + spec.min_attestation_inclusion_delay <= state.slot,
+        data.slot.safe_add(spec.min_attestation_inclusion_delay)? <= state.slot,
         Invalid::IncludedTooEarly {
             state: state.slot,
             delay: spec.min_attestation_inclusion_delay,
@@ -33,7 +33,7 @@ pub fn verify_attestation_for_block_inclusion<T: EthSpec>(
         }
     );
     verify!(
-        state.slot <= data.slot + T::slots_per_epoch(),
+        state.slot <= data.slot.safe_add(T::slots_per_epoch())?,
         Invalid::IncludedTooLate {
             state: state.slot,
             attestation: data.slot,
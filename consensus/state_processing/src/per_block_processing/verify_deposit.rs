@@ -55,12 +55,22 @@ pub fn verify_deposit_merkle_proof<T: EthSpec>(
     spec: &ChainSpec,
 ) -> Result<()> {
     let leaf = deposit.data.tree_hash_root();
+    // The branch includes the deposit count mixed into the root.
+    let proof_len = spec.deposit_contract_tree_depth.safe_add(1)? as usize;
+
+    verify!(
+        deposit.proof.len() == proof_len,
+        DepositInvalid::BadMerkleProofLength {
+            expected: proof_len,
+            found: deposit.proof.len(),
+        }
+    );
 
     verify!(
         verify_merkle_proof(
             leaf,
             &deposit.proof[..],
-            spec.deposit_contract_tree_depth.safe_add(1)? as usize,
+            proof_len,
             deposit_index as usize,
             state.eth1_data.deposit_root,
         ),
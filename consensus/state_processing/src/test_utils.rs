@@ -142,14 +142,19 @@ impl<T: EthSpec> BlockBuilder<T> {
         );
 
         // Insert `Deposit` objects.
-        builder.insert_deposits(
-            32_000_000_000,
-            DepositTestTask::NoReset,
-            state.eth1_data.deposit_count,
-            self.num_deposits as u64,
-            &mut state,
-            spec,
-        );
+        //
+        // The `Valid` task resets the deposit index of the state so that the generated merkle
+        // proofs verify against the new deposit root.
+        if self.num_deposits > 0 {
+            builder.insert_deposits(
+                32_000_000_000,
+                DepositTestTask::Valid,
+                state.eth1_data.deposit_count,
+                self.num_deposits as u64,
+                &mut state,
+                spec,
+            );
+        }
         info!("Inserted {} deposits.", builder.block.body.deposits.len());
 
         // Insert the maximum possible number of `Exit` objects.
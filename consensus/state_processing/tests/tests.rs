@@ -3,9 +3,10 @@
 use state_processing::{
     per_block_processing, test_utils::BlockBuilder, BlockProcessingError, BlockSignatureStrategy,
 };
+use types::test_utils::{TestingBeaconBlockBuilder, TestingBeaconStateBuilder};
 use types::{
     AggregateSignature, BeaconState, ChainSpec, EthSpec, Hash256, Keypair, MinimalEthSpec,
-    Signature, SignedBeaconBlock, Slot,
+    SecretKey, Signature, SignedBeaconBlock, Slot, Unsigned,
 };
 
 const VALIDATOR_COUNT: usize = 64;
@@ -59,6 +60,18 @@ where
         "valid block should pass with verify bulk"
     );
 
+    assert_eq!(
+        per_block_processing(
+            &mut state.clone(),
+            &block,
+            None,
+            BlockSignatureStrategy::VerifyAttestationsBulk,
+            spec
+        ),
+        Ok(()),
+        "valid block should pass with verify attestations bulk"
+    );
+
     invalidate_block(&mut block);
 
     /*
@@ -77,6 +90,18 @@ where
         "invalid block should fail with verify individual"
     );
 
+    assert!(
+        per_block_processing(
+            &mut state.clone(),
+            &block,
+            None,
+            BlockSignatureStrategy::VerifyAttestationsBulk,
+            spec
+        )
+        .is_err(),
+        "invalid block should fail with verify attestations bulk"
+    );
+
     assert_eq!(
         per_block_processing(
             &mut state,
@@ -196,8 +221,6 @@ mod signatures_minimal {
     }
 
     #[test]
-    // TODO: fix fail by making valid merkle proofs.
-    #[should_panic]
     fn deposit() {
         let spec = &TestEthSpec::default_spec();
 
@@ -226,3 +249,83 @@ mod signatures_minimal {
         );
     }
 }
+
+#[test]
+fn all_block_operations() {
+    let mut spec = TestEthSpec::default_spec();
+
+    // Allows the exits to be processed successfully.
+    spec.shard_committee_period = 0;
+
+    let (block, state) = get_block::<TestEthSpec, _>(|builder| builder.maximize_block_operations());
+
+    assert_eq!(
+        block.message.body.deposits.len(),
+        <TestEthSpec as EthSpec>::MaxDeposits::to_usize()
+    );
+
+    for strategy in &[
+        BlockSignatureStrategy::VerifyIndividual,
+        BlockSignatureStrategy::VerifyBulk,
+        BlockSignatureStrategy::VerifyAttestationsBulk,
+    ] {
+        assert_eq!(
+            per_block_processing(&mut state.clone(), &block, None, *strategy, &spec),
+            Ok(()),
+            "block with all operations should pass"
+        );
+    }
+}
+
+#[test]
+fn block_with_attestations() {
+    let spec = TestEthSpec::default_spec();
+
+    let mut state_builder = TestingBeaconStateBuilder::<TestEthSpec>::from_deterministic_keypairs(
+        VALIDATOR_COUNT,
+        &spec,
+    );
+    state_builder.teleport_to_slot(Slot::from(TestEthSpec::slots_per_epoch() * 3 - 2));
+    state_builder.build_caches(&spec).unwrap();
+    let (mut state, keypairs) = state_builder.build();
+
+    let proposer_index = state.get_beacon_proposer_index(state.slot, &spec).unwrap();
+    let proposer_sk = &keypairs[proposer_index].sk;
+    let secret_keys: Vec<&SecretKey> = keypairs.iter().map(|keypair| &keypair.sk).collect();
+
+    let mut builder = TestingBeaconBlockBuilder::new(&spec);
+    builder.set_slot(state.slot);
+    builder.set_proposer_index(proposer_index as u64);
+    builder.set_parent_root(state.latest_block_header.canonical_root());
+    builder.set_randao_reveal(
+        proposer_sk,
+        &state.fork,
+        state.genesis_validators_root,
+        &spec,
+    );
+    let block = builder
+        .with_attestations(&state, &secret_keys, usize::max_value(), &spec)
+        .unwrap()
+        .build(
+            proposer_sk,
+            &state.fork,
+            state.genesis_validators_root,
+            &spec,
+        );
+
+    let num_attestations = block.message.body.attestations.len();
+    assert!(num_attestations > 0);
+    assert!(num_attestations <= <TestEthSpec as EthSpec>::MaxAttestations::to_usize());
+
+    assert_eq!(
+        per_block_processing(
+            &mut state,
+            &block,
+            None,
+            BlockSignatureStrategy::VerifyIndividual,
+            &spec
+        ),
+        Ok(()),
+        "block with attestations should pass"
+    );
+}
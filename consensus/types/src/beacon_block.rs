@@ -213,4 +213,31 @@ mod tests {
     use super::*;
 
     ssz_and_tree_hash_tests!(BeaconBlock<MainnetEthSpec>);
+
+    #[test]
+    fn ssz_trailing_bytes() {
+        use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+        use ssz::{Decode, Encode};
+
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+        let block = BeaconBlock::<MainnetEthSpec>::random_for_test(&mut rng);
+        let bytes = block.as_ssz_bytes();
+
+        // Trailing bytes are read as part of the last variable-length field, the voluntary exits.
+        // Bytes which do not form a whole exit must be rejected.
+        let mut garbage = bytes.clone();
+        garbage.push(42);
+        assert!(BeaconBlock::<MainnetEthSpec>::from_ssz_bytes(&garbage).is_err());
+
+        // Bytes which do form a whole exit decode as a different block.
+        let exit = SignedVoluntaryExit::random_for_test(&mut rng);
+        let mut extended = bytes;
+        extended.append(&mut exit.as_ssz_bytes());
+        let decoded = BeaconBlock::<MainnetEthSpec>::from_ssz_bytes(&extended).unwrap();
+        assert_eq!(
+            decoded.body.voluntary_exits.len(),
+            block.body.voluntary_exits.len() + 1
+        );
+        assert_ne!(decoded.canonical_root(), block.canonical_root());
+    }
 }
@@ -523,7 +523,13 @@ impl<T: EthSpec> BeaconState<T> {
         }
 
         let seed = self.get_beacon_proposer_seed(slot, spec)?;
-        let indices = self.get_active_validator_indices(epoch, spec)?;
+        // Reading the active validators from the committee cache is faster than iterating the
+        // validator registry (see the `process_block_header` benches in `state_processing`), so
+        // the cache is used whenever it has been built.
+        let indices = match self.committee_cache(RelativeEpoch::Current) {
+            Ok(cache) => cache.sorted_active_validator_indices(),
+            Err(_) => self.get_active_validator_indices(epoch, spec)?,
+        };
 
         self.compute_proposer_index(&indices, &seed, spec)
     }
@@ -782,7 +788,10 @@ impl<T: EthSpec> BeaconState<T> {
         // Bypass the safe getter for RANDAO so we can gracefully handle the scenario where `epoch
         // == 0`.
         let mix = {
-            let i = epoch + T::EpochsPerHistoricalVector::to_u64() - spec.min_seed_lookahead - 1;
+            let i = epoch
+                .safe_add(T::EpochsPerHistoricalVector::to_u64())?
+                .safe_sub(spec.min_seed_lookahead)?
+                .safe_sub(1_u64)?;
             self.randao_mixes[i.as_usize().safe_rem(self.randao_mixes.len())?]
         };
         let domain_bytes = int_to_bytes4(spec.get_domain_constant(domain_type));
@@ -980,7 +989,7 @@ impl<T: EthSpec> BeaconState<T> {
     }
 
     /// Drops the cache, leaving it in an uninitialized state.
-    fn drop_committee_cache(&mut self, relative_epoch: RelativeEpoch) {
+    pub fn drop_committee_cache(&mut self, relative_epoch: RelativeEpoch) {
         self.committee_caches[Self::committee_cache_index(relative_epoch)] =
             CommitteeCache::default();
     }
@@ -94,6 +94,23 @@ impl CommitteeCache {
         &self.shuffling
     }
 
+    /// Returns the list of active validator indices for the initialized epoch, in ascending order.
+    ///
+    /// Equivalent to `get_active_validator_indices`, without reading the validator registry.
+    ///
+    /// Always returns an empty list for a non-initialized epoch.
+    pub fn sorted_active_validator_indices(&self) -> Vec<usize> {
+        let mut active = Vec::with_capacity(self.shuffling.len());
+
+        for (index, position) in self.shuffling_positions.iter().enumerate() {
+            if position.is_some() {
+                active.push(index)
+            }
+        }
+
+        active
+    }
+
     /// Returns the shuffled list of active validator indices for the initialized epoch.
     ///
     /// Always returns `&[]` for a non-initialized epoch.
@@ -118,3 +118,23 @@ fn shuffles_for_the_right_epoch() {
     assert_eq!(cache.shuffling, shuffling_with_seed(next_seed));
     assert_shuffling_positions_accurate(&cache);
 }
+
+#[test]
+fn sorted_active_validator_indices() {
+    let mut state = new_state::<MinimalEthSpec>(16, Slot::new(0));
+    let spec = &MinimalEthSpec::default_spec();
+
+    // Validators which are not active are excluded.
+    state.validators[3].activation_epoch = state.next_epoch();
+    state.validators[7].exit_epoch = state.current_epoch();
+
+    let cache = CommitteeCache::default();
+    assert!(cache.sorted_active_validator_indices().is_empty());
+
+    let cache = CommitteeCache::initialized(&state, state.current_epoch(), &spec).unwrap();
+    assert_eq!(
+        cache.sorted_active_validator_indices(),
+        get_active_validator_indices(&state.validators, state.current_epoch())
+    );
+    assert_eq!(cache.sorted_active_validator_indices().len(), 14);
+}
@@ -32,11 +32,16 @@ fn test_beacon_proposer_index<T: EthSpec>() {
         .unwrap()]
     };
 
-    // Run a test on the state.
+    // Run a test on the state, with and without the committee cache.
     let test = |state: &BeaconState<T>, slot: Slot, candidate_index: usize| {
+        let expected = ith_candidate(state, slot, candidate_index, &spec);
+        assert_eq!(state.get_beacon_proposer_index(slot, &spec), Ok(expected));
+
+        let mut uncached_state = state.clone();
+        uncached_state.drop_all_caches();
         assert_eq!(
-            state.get_beacon_proposer_index(slot, &spec),
-            Ok(ith_candidate(state, slot, candidate_index, &spec))
+            uncached_state.get_beacon_proposer_index(slot, &spec),
+            Ok(expected)
         );
     };
 
@@ -4,7 +4,8 @@
 //! `Slot` and `Epoch` have implementations which permit conversion, comparison and math operations
 //! between each and `u64`, however specifically not between each other.
 //!
-//! All math operations on `Slot` and `Epoch` are saturating, they never wrap.
+//! All math operations on `Slot` and `Epoch` are saturating, they never wrap. Use `safe_add` and
+//! `safe_sub` where an overflow must be detected.
 //!
 //! It would be easy to define `PartialOrd` and other traits generically across all types which
 //! implement `Into<u64>`, however this would allow operations between `Slots` and `Epochs` which
@@ -14,6 +15,7 @@ use crate::test_utils::TestRandom;
 use crate::SignedRoot;
 
 use rand::RngCore;
+use safe_arith::{ArithError, SafeArith};
 use serde_derive::{Deserialize, Serialize};
 use ssz::{ssz_encode, Decode, DecodeError, Encode};
 use std::cmp::{Ord, Ordering};
@@ -151,6 +151,18 @@ macro_rules! impl_math {
                 *self + other.into()
             }
 
+            /// Adds `other`, erroring on overflow instead of saturating.
+            pub fn safe_add<T: Into<$type>>(&self, other: T) -> Result<$type, ArithError> {
+                let other: $type = other.into();
+                self.0.safe_add(other.0).map($type)
+            }
+
+            /// Subtracts `other`, erroring on underflow instead of saturating.
+            pub fn safe_sub<T: Into<$type>>(&self, other: T) -> Result<$type, ArithError> {
+                let other: $type = other.into();
+                self.0.safe_sub(other.0).map($type)
+            }
+
             pub fn checked_div<T: Into<$type>>(&self, rhs: T) -> Option<$type> {
                 let rhs: $type = rhs.into();
                 if rhs == 0 {
@@ -515,6 +527,23 @@ macro_rules! math_tests {
             assert_saturating_add(u64::max_value(), u64::max_value(), u64::max_value());
         }
 
+        #[test]
+        fn safe_add_and_safe_sub() {
+            assert_eq!($type(1).safe_add($type(2)), Ok($type(3)));
+            assert_eq!($type(3).safe_sub($type(2)), Ok($type(1)));
+            assert_eq!(
+                $type(u64::max_value() - 1).safe_add($type(1)),
+                Ok($type(u64::max_value()))
+            );
+
+            // Unlike `+` and `-`, overflow is an error.
+            assert_eq!(
+                $type(u64::max_value()).safe_add($type(1)),
+                Err(ArithError::Overflow)
+            );
+            assert_eq!($type(0).safe_sub($type(1)), Err(ArithError::Overflow));
+        }
+
         #[test]
         fn checked_div() {
             let assert_checked_div = |a: u64, b: u64, result: Option<u64>| {
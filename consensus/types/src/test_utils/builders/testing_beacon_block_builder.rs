@@ -1,7 +1,8 @@
 use crate::{
     test_utils::{
-        TestingAttestationBuilder, TestingAttesterSlashingBuilder, TestingDepositBuilder,
-        TestingProposerSlashingBuilder, TestingVoluntaryExitBuilder,
+        generate_deterministic_keypair, RngCore, SeedableRng, TestingAttestationBuilder,
+        TestingAttesterSlashingBuilder, TestingDepositBuilder, TestingProposerSlashingBuilder,
+        TestingVoluntaryExitBuilder, XorShiftRng,
     },
     typenum::U4294967296,
     *,
@@ -16,6 +17,10 @@ use tree_hash::TreeHash;
 /// This struct should **never be used for production purposes.**
 pub struct TestingBeaconBlockBuilder<T: EthSpec> {
     pub block: BeaconBlock<T>,
+    /// If `Some`, used to generate the keys which would otherwise be random.
+    rng: Option<XorShiftRng>,
+    /// The leaves of the deposit tree built by `insert_deposit`, from the first deposit.
+    deposit_leaves: Vec<Hash256>,
 }
 
 /// Enum used for passing test options to builder
@@ -70,6 +75,27 @@ impl<T: EthSpec> TestingBeaconBlockBuilder<T> {
     pub fn new(spec: &ChainSpec) -> Self {
         Self {
             block: BeaconBlock::empty(spec),
+            rng: None,
+            deposit_leaves: vec![],
+        }
+    }
+
+    /// Create a new builder from genesis which generates keys from `seed` instead of at random.
+    ///
+    /// Builders with the same `seed` produce identical blocks for identical inputs.
+    pub fn new_with_seed(spec: &ChainSpec, seed: u64) -> Self {
+        Self {
+            block: BeaconBlock::empty(spec),
+            rng: Some(XorShiftRng::seed_from_u64(seed)),
+            deposit_leaves: vec![],
+        }
+    }
+
+    /// Generates a keypair from the seeded RNG, if any, otherwise generates a random keypair.
+    fn new_keypair(&mut self) -> Keypair {
+        match &mut self.rng {
+            Some(rng) => generate_deterministic_keypair(rng.next_u64() as usize),
+            None => Keypair::random(),
         }
     }
 
@@ -160,7 +186,8 @@ impl<T: EthSpec> TestingBeaconBlockBuilder<T> {
     /// It will first go and get each committee that is able to include an attestation in this
     /// block. If there _are_ enough committees, it will produce an attestation for each. If there
     /// _are not_ enough committees, it will start splitting the committees in half until it
-    /// achieves the target. It will then produce separate attestations for each split committee.
+    /// achieves the target or every committee has a single signer. It will then produce separate
+    /// attestations for each split committee.
     ///
     /// Note: the signed messages of the split committees will be identical -- it would be possible
     /// to aggregate these split attestations.
@@ -215,12 +242,14 @@ impl<T: EthSpec> TestingBeaconBlockBuilder<T> {
         }
 
         // Loop through all the committees, splitting each one in half until we have
-        // `MAX_ATTESTATIONS` committees.
+        // `MAX_ATTESTATIONS` committees or there are no committees left to split.
         loop {
             if committees.len() >= num_attestations as usize {
                 break;
             }
 
+            let mut split = false;
+
             for i in 0..committees.len() {
                 if committees.len() >= num_attestations as usize {
                     break;
@@ -228,11 +257,21 @@ impl<T: EthSpec> TestingBeaconBlockBuilder<T> {
 
                 let (slot, committee, mut signing_validators, index) = committees[i].clone();
 
+                // Splitting a single signer would produce an attestation without any signers.
+                if signing_validators.len() < 2 {
+                    continue;
+                }
+
                 let new_signing_validators =
                     signing_validators.split_off(signing_validators.len() / 2);
 
                 committees[i] = (slot, committee.clone(), signing_validators, index);
                 committees.push((slot, committee, new_signing_validators, index));
+                split = true;
+            }
+
+            if !split {
+                break;
             }
         }
 
@@ -267,6 +306,31 @@ impl<T: EthSpec> TestingBeaconBlockBuilder<T> {
         Ok(())
     }
 
+    /// Inserts up to `num_attestations` valid attestations for the committees of `state`.
+    ///
+    /// The number of attestations is limited by `T::MaxAttestations` and by the number of
+    /// committees that are able to include an attestation in this block. See
+    /// `Self::insert_attestations`.
+    pub fn with_attestations(
+        mut self,
+        state: &BeaconState<T>,
+        secret_keys: &[&SecretKey],
+        num_attestations: usize,
+        spec: &ChainSpec,
+    ) -> Result<Self, BeaconStateError> {
+        let num_attestations = std::cmp::min(num_attestations, T::MaxAttestations::to_usize());
+
+        self.insert_attestations(
+            AttestationTestTask::Valid,
+            state,
+            secret_keys,
+            num_attestations,
+            spec,
+        )?;
+
+        Ok(self)
+    }
+
     /// Insert a `Valid` deposit into the state.
     pub fn insert_deposits(
         &mut self,
@@ -281,10 +345,11 @@ impl<T: EthSpec> TestingBeaconBlockBuilder<T> {
         // Vector containing deposits' data
         let mut datas = vec![];
         for _ in 0..num_deposits {
-            let keypair = Keypair::random();
+            let keypair = self.new_keypair();
+            let wrong_keypair = self.new_keypair();
 
             let mut builder = TestingDepositBuilder::new(keypair.pk.clone(), amount);
-            builder.sign(test_task, &keypair, spec);
+            builder.sign_with_wrong_key(test_task, &keypair, wrong_keypair, spec);
             datas.push(builder.build().data);
         }
 
@@ -334,6 +399,50 @@ impl<T: EthSpec> TestingBeaconBlockBuilder<T> {
         }
     }
 
+    /// Insert a deposit of `data` into the block, with a valid proof against the deposit tree
+    /// tracked by the builder.
+    ///
+    /// The deposits already processed by `state` are represented by zero leaves in the tree. The
+    /// `eth1_data` of `state` is set to the root and count of the tree, and the proofs of the
+    /// deposits previously inserted are regenerated against the new root.
+    ///
+    /// Should not be combined with `insert_deposits`.
+    pub fn insert_deposit(
+        &mut self,
+        data: DepositData,
+        state: &mut BeaconState<T>,
+        spec: &ChainSpec,
+    ) {
+        let first_index = state.eth1_deposit_index as usize;
+        if self.deposit_leaves.is_empty() {
+            self.deposit_leaves = vec![Hash256::zero(); first_index];
+        }
+        self.deposit_leaves.push(data.tree_hash_root());
+
+        self.block
+            .body
+            .deposits
+            .push(Deposit {
+                proof: vec![].into(),
+                data,
+            })
+            .unwrap();
+
+        // Every proof changes with the root, which commits to the number of leaves.
+        let depth = spec.deposit_contract_tree_depth as usize;
+        let tree = MerkleTree::create(&self.deposit_leaves, depth);
+        let deposit_count = self.deposit_leaves.len() as u64;
+        for (i, deposit) in self.block.body.deposits.iter_mut().enumerate() {
+            let (_, mut proof) = tree.generate_proof(first_index + i, depth);
+            proof.push(Hash256::from_slice(&int_to_bytes32(deposit_count)));
+            deposit.proof = proof.into();
+        }
+
+        state.eth1_data.deposit_root =
+            VariableList::<_, U4294967296>::from(self.deposit_leaves.clone()).tree_hash_root();
+        state.eth1_data.deposit_count = deposit_count;
+    }
+
     /// Insert an exit for the given validator at the given epoch into the block.
     pub fn insert_exit(
         &mut self,
@@ -422,3 +531,61 @@ pub fn build_double_vote_attester_slashing<T: EthSpec>(
         spec,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestingBeaconStateBuilder;
+
+    fn block_with_deposits(seed: u64) -> SignedBeaconBlock<MinimalEthSpec> {
+        let spec = MinimalEthSpec::default_spec();
+        let (mut state, _keypairs) =
+            TestingBeaconStateBuilder::from_deterministic_keypairs(8, &spec).build();
+
+        let mut builder = TestingBeaconBlockBuilder::new_with_seed(&spec, seed);
+        builder.insert_deposits(
+            spec.max_effective_balance,
+            DepositTestTask::BadSig,
+            0,
+            2,
+            &mut state,
+            &spec,
+        );
+        builder.build_without_signing()
+    }
+
+    #[test]
+    fn inserted_deposit_proofs_verify() {
+        let spec = MinimalEthSpec::default_spec();
+        let (mut state, _keypairs) =
+            TestingBeaconStateBuilder::<MinimalEthSpec>::from_deterministic_keypairs(8, &spec)
+                .build();
+
+        let mut builder = TestingBeaconBlockBuilder::new_with_seed(&spec, 42);
+        for i in 0..3 {
+            let keypair = generate_deterministic_keypair(8 + i);
+            let mut deposit =
+                TestingDepositBuilder::new(keypair.pk.clone(), spec.max_effective_balance);
+            deposit.sign(DepositTestTask::Valid, &keypair, &spec);
+            builder.insert_deposit(deposit.build().data, &mut state, &spec);
+        }
+        let block = builder.build_without_signing();
+
+        assert_eq!(state.eth1_data.deposit_count, state.eth1_deposit_index + 3);
+        for (i, deposit) in block.message.body.deposits.iter().enumerate() {
+            assert!(merkle_proof::verify_merkle_proof(
+                deposit.data.tree_hash_root(),
+                &deposit.proof[..],
+                spec.deposit_contract_tree_depth as usize + 1,
+                state.eth1_deposit_index as usize + i,
+                state.eth1_data.deposit_root,
+            ));
+        }
+    }
+
+    #[test]
+    fn seeded_blocks_are_deterministic() {
+        assert_eq!(block_with_deposits(42), block_with_deposits(42));
+        assert_ne!(block_with_deposits(42), block_with_deposits(43));
+    }
+}
@@ -31,7 +31,18 @@ impl TestingDepositBuilder {
     /// - `withdrawal_credentials` to the signing pubkey.
     /// - `proof_of_possession`
     pub fn sign(&mut self, test_task: DepositTestTask, keypair: &Keypair, spec: &ChainSpec) {
-        let new_key = Keypair::random();
+        self.sign_with_wrong_key(test_task, keypair, Keypair::random(), spec)
+    }
+
+    /// Signs the deposit as per `Self::sign`, using `new_key` as the wrong key for the
+    /// `BadPubKey` and `BadSig` test tasks.
+    pub fn sign_with_wrong_key(
+        &mut self,
+        test_task: DepositTestTask,
+        keypair: &Keypair,
+        new_key: Keypair,
+        spec: &ChainSpec,
+    ) {
         let mut pubkeybytes = PublicKeyBytes::from(keypair.pk.clone());
         let mut secret_key = keypair.sk.clone();
 
@@ -24,6 +24,7 @@ use sha2::{Digest, Sha256};
 use std::fs::OpenOptions;
 use std::io::{Read, Write};
 use std::path::Path;
+use zeroize::Zeroizing;
 
 /// The byte-length of a BLS secret key.
 const SECRET_KEY_LEN: usize = 32;
@@ -81,6 +82,7 @@ pub struct KeystoreBuilder<'a> {
     cipher: Cipher,
     uuid: Uuid,
     path: String,
+    description: Option<String>,
 }
 
 impl<'a> KeystoreBuilder<'a> {
@@ -105,6 +107,7 @@ impl<'a> KeystoreBuilder<'a> {
                 cipher: Cipher::Aes128Ctr(Aes128Ctr { iv }),
                 uuid: Uuid::new_v4(),
                 path,
+                description: None,
             })
         }
     }
@@ -115,8 +118,21 @@ impl<'a> KeystoreBuilder<'a> {
         self
     }
 
+    /// Build the keystore using the supplied `uuid` instead of a random one.
+    pub fn uuid(mut self, uuid: Uuid) -> Self {
+        self.uuid = uuid;
+        self
+    }
+
+    /// Build the keystore with the supplied `description`, instead of none.
+    pub fn description(mut self, description: String) -> Self {
+        self.description = Some(description);
+        self
+    }
+
     /// Consumes `self`, returning a `Keystore`.
     pub fn build(self) -> Result<Keystore, Error> {
+        let description = self.description;
         Keystore::encrypt(
             self.keypair,
             self.password,
@@ -125,6 +141,10 @@ impl<'a> KeystoreBuilder<'a> {
             self.uuid,
             self.path,
         )
+        .map(|mut keystore| {
+            keystore.json.description = description;
+            keystore
+        })
     }
 }
 
@@ -232,6 +252,11 @@ impl Keystore {
         serde_json::from_str(&format!("\"0x{}\"", &self.json.pubkey)).ok()
     }
 
+    /// Returns the description for the keystore, if any.
+    pub fn description(&self) -> Option<&str> {
+        self.json.description.as_deref()
+    }
+
     /// Returns the key derivation function for the keystore.
     pub fn kdf(&self) -> &Kdf {
         &self.json.crypto.kdf.params
@@ -253,19 +278,44 @@ impl Keystore {
     }
 
     /// Instantiates `self` from a JSON `reader`.
+    ///
+    /// The JSON is read into a buffer which is zeroized once it has been parsed.
     pub fn from_json_reader<R: Read>(reader: R) -> Result<Self, Error> {
-        serde_json::from_reader(reader).map_err(|e| Error::ReadError(format!("{}", e)))
+        Self::from_json_reader_with_capacity(reader, 0)
     }
 
     /// Instantiates `self` by reading a JSON file at `path`.
+    ///
+    /// The JSON is read into a buffer which is zeroized once it has been parsed.
     pub fn from_json_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
-        OpenOptions::new()
+        let file = OpenOptions::new()
             .read(true)
             .write(false)
             .create(false)
             .open(path)
-            .map_err(|e| Error::ReadError(format!("{}", e)))
-            .and_then(Self::from_json_reader)
+            .map_err(|e| Error::ReadError(format!("{}", e)))?;
+
+        // Allocating the whole file up-front avoids reallocations, which would leave copies of
+        // the JSON on the heap that are never zeroized.
+        let capacity = file
+            .metadata()
+            .map(|metadata| metadata.len() as usize + 1)
+            .unwrap_or(0);
+
+        Self::from_json_reader_with_capacity(file, capacity)
+    }
+
+    /// Instantiates `self` from a JSON `reader`, reading it into a zeroizing buffer of (at least)
+    /// `capacity` bytes.
+    fn from_json_reader_with_capacity<R: Read>(
+        mut reader: R,
+        capacity: usize,
+    ) -> Result<Self, Error> {
+        let mut json = Zeroizing::new(Vec::with_capacity(capacity));
+        reader
+            .read_to_end(&mut json)
+            .map_err(|e| Error::ReadError(format!("{}", e)))?;
+        serde_json::from_slice(&json).map_err(|e| Error::ReadError(format!("{}", e)))
     }
 }
 
@@ -373,10 +423,9 @@ fn generate_checksum(derived_key: &DerivedKey, cipher_message: &[u8]) -> [u8; HA
     digest
 }
 
-/// Derive a private key from the given `password` using the given `kdf` (key derivation function).
-fn derive_key(password: &[u8], kdf: &Kdf) -> Result<DerivedKey, Error> {
-    let mut dk = DerivedKey::zero();
-
+/// Returns an error if the parameters of `kdf` are not supported (e.g., some values are zero or
+/// the derived key would not be `DKLEN` bytes).
+pub fn validate_kdf(kdf: &Kdf) -> Result<(), Error> {
     match &kdf {
         Kdf::Pbkdf2(params) => {
             // RFC2898 declares that `c` must be a "positive integer" and the `crypto` crate panics
@@ -393,13 +442,6 @@ fn derive_key(password: &[u8], kdf: &Kdf) -> Result<DerivedKey, Error> {
             if params.c == 0 || params.dklen != DKLEN {
                 return Err(Error::InvalidPbkdf2Param);
             }
-
-            pbkdf2::<Hmac<Sha256>>(
-                password,
-                params.salt.as_bytes(),
-                params.c,
-                dk.as_mut_bytes(),
-            );
         }
         Kdf::Scrypt(params) => {
             // RFC7914 declares that all these parameters must be greater than 1:
@@ -422,7 +464,28 @@ fn derive_key(password: &[u8], kdf: &Kdf) -> Result<DerivedKey, Error> {
             if params.n != 2u32.pow(log2_int(params.n)) {
                 return Err(Error::InvalidScryptParam);
             }
+        }
+    }
 
+    Ok(())
+}
+
+/// Derive a private key from the given `password` using the given `kdf` (key derivation function).
+fn derive_key(password: &[u8], kdf: &Kdf) -> Result<DerivedKey, Error> {
+    validate_kdf(kdf)?;
+
+    let mut dk = DerivedKey::zero();
+
+    match &kdf {
+        Kdf::Pbkdf2(params) => {
+            pbkdf2::<Hmac<Sha256>>(
+                password,
+                params.salt.as_bytes(),
+                params.c,
+                dk.as_mut_bytes(),
+            );
+        }
+        Kdf::Scrypt(params) => {
             scrypt(
                 password,
                 params.salt.as_bytes(),
@@ -423,7 +423,11 @@ fn kdf_function() {
         "#;
 
     match Keystore::from_json_str(&vector) {
-        Err(Error::InvalidJson(_)) => {}
+        Err(Error::InvalidJson(e)) => assert!(
+            e.contains("Unsupported kdf function: not-scrypt"),
+            "error should name the kdf function: {}",
+            e
+        ),
         _ => panic!("expected invalid json error"),
     }
 }
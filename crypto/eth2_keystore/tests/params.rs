@@ -1,6 +1,10 @@
 #![cfg(test)]
 
-use eth2_keystore::{Error, Keystore};
+use eth2_keystore::{
+    default_kdf,
+    json_keystore::{Kdf, Pbkdf2, Prf, Scrypt},
+    validate_kdf, Error, Keystore, DKLEN,
+};
 
 const PASSWORD: &str = "testpassword";
 
@@ -320,3 +324,27 @@ fn pbkdf2_dklen_not_32() {
 
     assert_eq!(decrypt_error(vector), Error::InvalidPbkdf2Param);
 }
+
+#[test]
+fn validate_kdf_params() {
+    assert_eq!(validate_kdf(&default_kdf(vec![42; 32])), Ok(()));
+    assert_eq!(
+        validate_kdf(&Kdf::Scrypt(Scrypt {
+            dklen: DKLEN,
+            n: 3,
+            r: 8,
+            p: 1,
+            salt: vec![42; 32].into(),
+        })),
+        Err(Error::InvalidScryptParam)
+    );
+    assert_eq!(
+        validate_kdf(&Kdf::Pbkdf2(Pbkdf2 {
+            c: 0,
+            dklen: DKLEN,
+            prf: Prf::HmacSha256,
+            salt: vec![42; 32].into(),
+        })),
+        Err(Error::InvalidPbkdf2Param)
+    );
+}
@@ -5,9 +5,9 @@ use bls::Keypair;
 use eth2_keystore::{
     default_kdf,
     json_keystore::{Kdf, Pbkdf2, Prf, Scrypt},
-    Error, Keystore, KeystoreBuilder, DKLEN,
+    Error, Keystore, KeystoreBuilder, Uuid, DKLEN,
 };
-use std::fs::OpenOptions;
+use std::fs::{self, OpenOptions};
 use tempfile::tempdir;
 
 const GOOD_PASSWORD: &[u8] = &[42, 42, 42];
@@ -87,6 +87,40 @@ fn file() {
     );
 }
 
+#[test]
+fn json_file() {
+    let keypair = Keypair::random();
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("keystore.json");
+
+    let keystore = KeystoreBuilder::new(&keypair, GOOD_PASSWORD, "".into())
+        .unwrap()
+        .build()
+        .unwrap();
+    let json = keystore.to_json_string().unwrap();
+
+    fs::write(&path, &json).unwrap();
+    let decoded = Keystore::from_json_file(&path).expect("should read from file");
+    assert_eq!(
+        decoded.decrypt_keypair(GOOD_PASSWORD).unwrap().pk,
+        keypair.pk
+    );
+
+    fs::write(&path, &json[..json.len() - 1]).unwrap();
+    assert!(
+        matches!(Keystore::from_json_file(&path), Err(Error::ReadError(_))),
+        "should not read a truncated file"
+    );
+
+    assert!(
+        matches!(
+            Keystore::from_json_file(dir.path().join("missing.json")),
+            Err(Error::ReadError(_))
+        ),
+        "should not read a missing file"
+    );
+}
+
 #[test]
 fn scrypt_params() {
     let keypair = Keypair::random();
@@ -137,6 +171,21 @@ fn custom_scrypt_kdf() {
     assert_eq!(keystore.kdf(), &my_kdf);
 }
 
+#[test]
+fn custom_uuid() {
+    let keypair = Keypair::random();
+
+    let my_uuid = Uuid::new_v4();
+
+    let keystore = KeystoreBuilder::new(&keypair, GOOD_PASSWORD, "".into())
+        .unwrap()
+        .uuid(my_uuid)
+        .build()
+        .unwrap();
+
+    assert_eq!(keystore.uuid(), &my_uuid);
+}
+
 #[test]
 fn custom_pbkdf2_kdf() {
     let keypair = Keypair::random();
@@ -1,3 +1,4 @@
+use account_manager::ExitError;
 use beacon_node::ProductionBeaconNode;
 use clap::{App, Arg, ArgMatches};
 use env_logger::{Builder, Env};
@@ -164,21 +165,24 @@ fn main() {
         Ok(()) => exit(0),
         Err(e) => {
             eprintln!("{}", e);
+            let code = e.code;
             drop(e);
-            exit(1)
+            exit(code)
         }
     }
 }
 
+/// Runs the subcommand in `matches`, returning the code to exit with on error.
 fn run<E: EthSpec>(
     environment_builder: EnvironmentBuilder<E>,
     matches: &ArgMatches,
-) -> Result<(), String> {
+) -> Result<(), ExitError> {
     if std::mem::size_of::<usize>() != 8 {
         return Err(format!(
             "{}bit architecture is not supported (64bit only).",
             std::mem::size_of::<usize>() * 8
-        ));
+        )
+        .into());
     }
 
     let debug_level = matches
@@ -234,6 +238,7 @@ fn run<E: EthSpec>(
     if let Some(sub_matches) = matches.subcommand_matches("account_manager") {
         eprintln!("Running account manager for {} testnet", testnet_name);
         // Pass the entire `environment` to the account manager so it can run blocking operations.
+        // It is dropped, flushing the logs, before any error is returned.
         account_manager::run(sub_matches, environment)?;
 
         // Exit as soon as account manager returns control.
@@ -295,7 +300,7 @@ fn run<E: EthSpec>(
 
     if beacon_node.is_none() && validator_client.is_none() {
         crit!(log, "No subcommand supplied. See --help .");
-        return Err("No subcommand supplied.".into());
+        return Err("No subcommand supplied.".to_string().into());
     }
 
     // Block this thread until Crtl+C is pressed.
@@ -3,7 +3,18 @@
 use account_manager::{
     validator::{
         create::*,
+        enable::{self, DISABLE_CMD, ENABLE_CMD},
+        export::{self, CMD as EXPORT_CMD},
         import::{self, CMD as IMPORT_CMD},
+        list::{self as validator_list, CMD as VALIDATOR_LIST_CMD},
+        modify::{self, CMD as MODIFY_CMD},
+        recover::{self, CMD as RECOVER_CMD},
+        reharden::{self, CMD as REHARDEN_CMD},
+        slashing_protection::{
+            export::{self as slashing_protection_export, CMD as SLASHING_PROTECTION_EXPORT_CMD},
+            CMD as SLASHING_PROTECTION_CMD,
+        },
+        verify::{self, CMD as VERIFY_CMD},
         CMD as VALIDATOR_CMD,
     },
     wallet::{
@@ -14,19 +25,38 @@ use account_manager::{
     BASE_DIR_FLAG, CMD as ACCOUNT_CMD, *,
 };
 use account_utils::{
-    eth2_keystore::KeystoreBuilder,
-    validator_definitions::{SigningDefinition, ValidatorDefinition, ValidatorDefinitions},
+    eth2_keystore::{
+        json_keystore::{Kdf, Pbkdf2, Prf, Scrypt},
+        Error as KeystoreError, Keystore, KeystoreBuilder, DKLEN,
+    },
+    validator_definitions::{
+        SigningDefinition, ValidatorDefinition, ValidatorDefinitions, CONFIG_FILENAME,
+    },
     ZeroizeString,
 };
+use eth2_testnet_config::Eth2TestnetConfig;
+use slashing_protection::{
+    interchange::{
+        Interchange, InterchangeBlock, InterchangeData, InterchangeMetadata,
+        INTERCHANGE_FORMAT_VERSION,
+    },
+    InvalidBlock, NotSafe, Safe, SlashingDatabase, SLASHING_PROTECTION_FILENAME,
+};
+use std::collections::HashMap;
 use std::env;
-use std::fs::{self, File};
-use std::io::{BufRead, BufReader, Write};
+use std::ffi::OsStr;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output, Stdio};
 use std::str::from_utf8;
+use std::time::{Duration, Instant};
 use tempfile::{tempdir, TempDir};
-use types::Keypair;
-use validator_dir::ValidatorDir;
+use types::{
+    BeaconBlockHeader, BeaconState, ChainSpec, Eth1Data, Hash256, Keypair, MainnetEthSpec, Slot,
+    YamlConfig,
+};
+use validator_dir::{ValidatorDir, VOTING_KEYSTORE_FILE};
 
 // TODO: create tests for the `lighthouse account validator deposit` command. This involves getting
 // access to an IPC endpoint during testing or adding support for deposit submission via HTTP and
@@ -64,6 +94,33 @@ fn output_result(cmd: &mut Command) -> Result<Output, String> {
     }
 }
 
+/// Returns the file name of the keystore at `index`, as written by the `eth2.0-deposit-cli`.
+fn keystore_name(index: usize) -> String {
+    format!("keystore-m_12381_3600_{}_0_0-1595406747.json", index)
+}
+
+/// Writes `keystore` to a new file at `path`.
+fn write_keystore_file<P: AsRef<Path>>(keystore: &Keystore, path: P) {
+    File::create(path)
+        .map(|mut file| keystore.to_json_writer(&mut file).unwrap())
+        .unwrap();
+}
+
+/// Writes a keystore of a random keypair, encrypted with `password`, to a new file at `path`.
+fn write_keystore<P: AsRef<Path>>(path: P, password: &str) -> Keystore {
+    let keystore = KeystoreBuilder::new(&Keypair::random(), password.as_bytes(), "".into())
+        .unwrap()
+        .build()
+        .unwrap();
+    write_keystore_file(&keystore, path);
+    keystore
+}
+
+/// Returns the hex-encoded SHA-256 of the file at `path`.
+fn file_sha256(path: &Path) -> String {
+    hex::encode(eth2_hashing::hash(&fs::read(path).unwrap()))
+}
+
 /// Returns the number of nodes in a directory.
 fn dir_child_count<P: AsRef<Path>>(dir: P) -> usize {
     fs::read_dir(dir).expect("should read dir").count()
@@ -378,7 +435,10 @@ fn validator_create() {
 /// Returns the `lighthouse account validator import` command.
 fn validator_import_cmd() -> Command {
     let mut cmd = validator_cmd();
-    cmd.arg(IMPORT_CMD);
+    // The pause after each password prompt only slows down the tests.
+    cmd.arg(IMPORT_CMD)
+        .arg(format!("--{}", import::PROMPT_DELAY_FLAG))
+        .arg("0");
     cmd
 }
 
@@ -391,19 +451,11 @@ fn validator_import_launchpad() {
     let src_dir = tempdir().unwrap();
     let dst_dir = tempdir().unwrap();
 
-    let keypair = Keypair::random();
-    let keystore = KeystoreBuilder::new(&keypair, PASSWORD.as_bytes(), "".into())
-        .unwrap()
-        .build()
-        .unwrap();
+    // Create a keystore in the src dir.
+    let keystore = write_keystore(src_dir.path().join(KEYSTORE_NAME), PASSWORD);
 
     let dst_keystore_dir = dst_dir.path().join(format!("0x{}", keystore.pubkey()));
 
-    // Create a keystore in the src dir.
-    File::create(src_dir.path().join(KEYSTORE_NAME))
-        .map(|mut file| keystore.to_json_writer(&mut file).unwrap())
-        .unwrap();
-
     // Create a not-keystore file in the src dir.
     File::create(src_dir.path().join(NOT_KEYSTORE_NAME)).unwrap();
 
@@ -456,6 +508,11 @@ fn validator_import_launchpad() {
     let expected_def = ValidatorDefinition {
         enabled: true,
         voting_public_key: keystore.public_key().unwrap(),
+        graffiti: None,
+        suggested_fee_recipient: None,
+        imported_keystore_sha256: Some(file_sha256(&src_dir.path().join(KEYSTORE_NAME))),
+        relative_paths: false,
+        unknown_fields: Default::default(),
         signing_definition: SigningDefinition::LocalKeystore {
             voting_keystore_path,
             voting_keystore_password_path: None,
@@ -468,3 +525,3764 @@ fn validator_import_launchpad() {
         "validator defs file should be accurate"
     );
 }
+
+#[test]
+fn validator_import_password_file() {
+    const PASSWORD_A: &str = "cats";
+    const PASSWORD_B: &str = "dogs:and:cats";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let password_dir = tempdir().unwrap();
+
+    let keystore_a = write_keystore(src_dir.path().join(keystore_name(0)), PASSWORD_A);
+    let keystore_b = write_keystore(src_dir.path().join(keystore_name(1)), PASSWORD_B);
+
+    // Identify one keystore by pubkey and the other by UUID.
+    let password_file = password_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        format!(
+            "0x{}:{}\n{}:{}\n",
+            keystore_a.pubkey(),
+            PASSWORD_A,
+            keystore_b.uuid(),
+            PASSWORD_B
+        ),
+    )
+    .unwrap();
+
+    let output = output_result(
+        validator_import_cmd()
+            .arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+            .arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str())
+            .arg(format!("--{}", import::JSON_FLAG)),
+    )
+    .unwrap();
+
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        summary["count"], 2,
+        "json summary should count both keystores"
+    );
+    assert!(
+        summary["validators"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|validator| validator["password_saved"] == true),
+        "json summary should record saved passwords"
+    );
+    let decrypt_times = summary["validators"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|validator| validator["decrypt_time_ms"].as_u64().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary["total_decrypt_time_ms"].as_u64().unwrap(),
+        decrypt_times.iter().sum::<u64>(),
+        "json summary should total the decrypt times"
+    );
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(
+        defs.as_slice().len(),
+        2,
+        "both keystores should be imported"
+    );
+    assert!(
+        !dst_dir.path().join(import::STAGING_DIR).exists(),
+        "staging directory should be removed"
+    );
+
+    for (keystore, password) in &[(keystore_a, PASSWORD_A), (keystore_b, PASSWORD_B)] {
+        let def = defs
+            .as_slice()
+            .iter()
+            .find(|def| def.voting_public_key == keystore.public_key().unwrap())
+            .expect("keystore should have definition");
+
+        match &def.signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_password,
+                ..
+            } => assert!(
+                *voting_keystore_password == Some(ZeroizeString::from(password.to_string())),
+                "password from file should be stored"
+            ),
+        }
+    }
+}
+
+/// Returns the `lighthouse account validator export` command.
+fn validator_export_cmd() -> Command {
+    let mut cmd = validator_cmd();
+    cmd.arg(EXPORT_CMD);
+    cmd
+}
+
+#[test]
+fn validator_export() {
+    const PASSWORD: &str = "cats";
+    const KEYSTORE_NAME: &str = "keystore-m_12381_3600_0_0_0-1595406747.json";
+
+    let src_dir = tempdir().unwrap();
+    let validator_dir = tempdir().unwrap();
+    let export_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(src_dir.path().join(KEYSTORE_NAME), PASSWORD);
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        format!("{}:{}", keystore.pubkey(), PASSWORD),
+    )
+    .unwrap();
+
+    output_result(
+        validator_import_cmd()
+            .arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+            .arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(validator_dir.path().as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str()),
+    )
+    .unwrap();
+
+    let export = || {
+        output_result(
+            validator_export_cmd()
+                .arg(format!("--{}", export::DIR_FLAG))
+                .arg(export_dir.path().as_os_str())
+                .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+                .arg(validator_dir.path().as_os_str()),
+        )
+    };
+
+    export().unwrap();
+
+    let exported_keystore = export_dir
+        .path()
+        .join(format!("0x{}", keystore.pubkey()))
+        .join(KEYSTORE_NAME);
+    assert!(exported_keystore.exists(), "keystore should be exported");
+
+    let defs = ValidatorDefinitions::open(&export_dir).unwrap();
+    let expected_def = ValidatorDefinition {
+        enabled: true,
+        voting_public_key: keystore.public_key().unwrap(),
+        graffiti: None,
+        suggested_fee_recipient: None,
+        imported_keystore_sha256: Some(file_sha256(&src_dir.path().join(KEYSTORE_NAME))),
+        relative_paths: true,
+        unknown_fields: Default::default(),
+        signing_definition: SigningDefinition::LocalKeystore {
+            voting_keystore_path: exported_keystore,
+            voting_keystore_password_path: None,
+            voting_keystore_password: None,
+        },
+    };
+    assert!(
+        defs.as_slice() == [expected_def],
+        "exported validator defs file should be accurate, without the password"
+    );
+
+    let yaml = fs::read_to_string(export_dir.path().join(CONFIG_FILENAME)).unwrap();
+    assert!(!yaml.contains(PASSWORD), "password should not be exported");
+    assert!(
+        !yaml.contains(export_dir.path().to_str().unwrap()),
+        "keystore paths should be relative to the export directory"
+    );
+
+    assert!(
+        export().is_err(),
+        "should not export into a non-empty directory"
+    );
+
+    output_result(
+        validator_export_cmd()
+            .arg(format!("--{}", export::DIR_FLAG))
+            .arg(export_dir.path().as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(validator_dir.path().as_os_str())
+            .arg(format!("--{}", export::FORCE_FLAG)),
+    )
+    .unwrap();
+}
+
+#[test]
+fn validator_import_dry_run() {
+    const KEYSTORE_NAME: &str = "keystore-m_12381_3600_0_0_0-1595406747.json";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(src_dir.path().join(KEYSTORE_NAME), "cats");
+
+    let output = output_result(
+        validator_import_cmd()
+            .arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+            .arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::DRY_RUN_FLAG)),
+    )
+    .unwrap();
+
+    let stderr = from_utf8(&output.stderr).unwrap();
+    assert!(
+        stderr.contains(&format!("0x{}", keystore.pubkey())),
+        "dry run should print the destination"
+    );
+    assert_eq!(
+        dir_child_count(dst_dir.path()),
+        0,
+        "dry run should not modify the validator dir"
+    );
+}
+
+/// Returns the `lighthouse account validator recover` command.
+fn validator_recover_cmd() -> Command {
+    let mut cmd = validator_cmd();
+    cmd.arg(RECOVER_CMD);
+    cmd
+}
+
+#[test]
+fn validator_recover() {
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon \
+                            abandon abandon abandon about";
+    const PASSWORD: &str = "correct horse battery staple";
+
+    let mnemonic_dir = tempdir().unwrap();
+    let validator_dir = tempdir().unwrap();
+
+    let mnemonic_path = mnemonic_dir.path().join("mnemonic.txt");
+    fs::write(&mnemonic_path, format!("{}\n", MNEMONIC)).unwrap();
+
+    let recover = |count: usize, password: &str| {
+        let mut child = validator_recover_cmd()
+            .arg(format!("--{}", recover::STDIN_PASSWORD_FLAG))
+            .arg(format!("--{}", recover::MNEMONIC_FLAG))
+            .arg(mnemonic_path.as_os_str())
+            .arg(format!("--{}", recover::COUNT_FLAG))
+            .arg(count.to_string())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(validator_dir.path().as_os_str())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        child
+            .stdin
+            .as_mut()
+            .unwrap()
+            .write_all(format!("{}\n{}\n", password, password).as_bytes())
+            .unwrap();
+
+        child.wait_with_output().unwrap().status.success()
+    };
+
+    assert!(!recover(2, "cats"), "weak password should be rejected");
+    assert!(recover(2, PASSWORD), "recover should succeed");
+
+    let defs = ValidatorDefinitions::open(&validator_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), 2, "should recover two validators");
+
+    // Recovering an overlapping range should skip the existing validators.
+    assert!(recover(3, PASSWORD), "recover should succeed");
+
+    let defs = ValidatorDefinitions::open(&validator_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), 3, "should only add new validators");
+    for def in defs.as_slice() {
+        match &def.signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                voting_keystore_password,
+                ..
+            } => {
+                assert!(voting_keystore_path.exists(), "keystore should exist");
+                assert!(
+                    *voting_keystore_password == Some(ZeroizeString::from(PASSWORD.to_string())),
+                    "password should be stored"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn validator_list() {
+    let validator_dir = tempdir().unwrap();
+
+    let keystore_path = validator_dir.path().join("voting-keystore.json");
+    let keystore = write_keystore(&keystore_path, "cats");
+
+    // Add one valid definition and one which points to a missing keystore.
+    let mut defs = ValidatorDefinitions::default();
+    let def = ValidatorDefinition::new_keystore_with_password(&keystore_path, None).unwrap();
+    let mut missing_def = def.clone();
+    missing_def.signing_definition = SigningDefinition::LocalKeystore {
+        voting_keystore_path: validator_dir.path().join("missing.json"),
+        voting_keystore_password_path: None,
+        voting_keystore_password: Some(ZeroizeString::from("cats".to_string())),
+    };
+    defs.push(def);
+    defs.push(missing_def);
+
+    // A validator directory, as made by `validator create`, which is not defined.
+    let undefined_dir = validator_dir.path().join("0xundefined");
+    fs::create_dir(&undefined_dir).unwrap();
+    File::create(undefined_dir.join(VOTING_KEYSTORE_FILE)).unwrap();
+
+    let list = || {
+        let output = output_result(
+            validator_cmd()
+                .arg(VALIDATOR_LIST_CMD)
+                .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+                .arg(validator_dir.path().as_os_str())
+                .arg(format!("--{}", validator_list::JSON_FLAG)),
+        )
+        .unwrap();
+        let validators: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        validators.as_array().unwrap().clone()
+    };
+
+    // Without a definitions file, only the directory is listed and no file is created.
+    let validators = list();
+    assert_eq!(validators.len(), 1);
+    assert_eq!(validators[0]["pubkey"], "0xundefined");
+    assert_eq!(validators[0]["defined"], false);
+    assert!(!validator_dir.path().join(CONFIG_FILENAME).exists());
+
+    defs.save(&validator_dir).unwrap();
+
+    let validators = list();
+    assert_eq!(validators.len(), 3);
+    assert_eq!(validators[0]["pubkey"], format!("0x{}", keystore.pubkey()));
+    assert_eq!(validators[0]["defined"], true);
+    assert_eq!(validators[0]["keystore_exists"], true);
+    assert_eq!(validators[0]["password_stored"], false);
+    assert_eq!(validators[1]["keystore_exists"], false);
+    assert_eq!(validators[1]["password_stored"], true);
+    assert_eq!(validators[2]["pubkey"], "0xundefined");
+    assert_eq!(validators[2]["defined"], false);
+    assert_eq!(validators[2]["keystore_exists"], true);
+}
+
+#[test]
+fn validator_import_duplicate_pubkeys() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    // The same keystore exported twice, e.g., into two sub-directories.
+    let keystore_name = "keystore-m_12381_3600_0_0_0-1595406747.json";
+    for sub_dir in &["a", "b"] {
+        fs::create_dir(src_dir.path().join(sub_dir)).unwrap();
+    }
+    let keystore = write_keystore(src_dir.path().join("a").join(keystore_name), "cats");
+    write_keystore_file(&keystore, src_dir.path().join("b").join(keystore_name));
+
+    let err = output_result(
+        validator_import_cmd()
+            .arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+            .arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str()),
+    )
+    .unwrap_err();
+
+    assert!(
+        err.contains(&format!("0x{}", keystore.pubkey())),
+        "error should list the duplicate pubkey"
+    );
+    assert!(
+        !dst_dir
+            .path()
+            .join(format!("0x{}", keystore.pubkey()))
+            .exists(),
+        "no keystore should be imported"
+    );
+    assert!(
+        ValidatorDefinitions::open(&dst_dir)
+            .unwrap()
+            .as_slice()
+            .is_empty(),
+        "no definitions should be added"
+    );
+}
+
+#[test]
+fn validator_import_reencrypt() {
+    const PASSWORD: &str = "cats";
+    const NEW_PASSWORD: &str = "dogs";
+    const KEYSTORE_NAME: &str = "keystore-m_12381_3600_0_0_0-1595406747.json";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keypair = Keypair::random();
+    let keystore = KeystoreBuilder::new(&keypair, PASSWORD.as_bytes(), "".into())
+        .unwrap()
+        .build()
+        .unwrap();
+    write_keystore_file(&keystore, src_dir.path().join(KEYSTORE_NAME));
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        format!("{}:{}", keystore.pubkey(), PASSWORD),
+    )
+    .unwrap();
+
+    let mut child = validator_import_cmd()
+        .arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+        .arg(format!("--{}", import::DIR_FLAG))
+        .arg(src_dir.path().as_os_str())
+        .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(dst_dir.path().as_os_str())
+        .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+        .arg(password_file.as_os_str())
+        .arg(format!("--{}", import::REENCRYPT_FLAG))
+        .arg(format!("--{}", ALLOW_WEAK_PASSWORD_FLAG))
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(format!("{}\n{}\n", NEW_PASSWORD, NEW_PASSWORD).as_bytes())
+        .unwrap();
+
+    assert!(
+        child.wait_with_output().unwrap().status.success(),
+        "import should succeed"
+    );
+
+    let dst_keystore = Keystore::from_json_file(
+        dst_dir
+            .path()
+            .join(format!("0x{}", keystore.pubkey()))
+            .join(KEYSTORE_NAME),
+    )
+    .unwrap();
+
+    assert_eq!(
+        dst_keystore.decrypt_keypair(PASSWORD.as_bytes()).err(),
+        Some(KeystoreError::InvalidPassword),
+        "original password should not decrypt the imported keystore"
+    );
+    assert_eq!(
+        dst_keystore
+            .decrypt_keypair(NEW_PASSWORD.as_bytes())
+            .unwrap()
+            .pk,
+        keypair.pk,
+        "new password should decrypt the imported keystore"
+    );
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    match &defs.as_slice()[0].signing_definition {
+        SigningDefinition::LocalKeystore {
+            voting_keystore_password,
+            ..
+        } => assert!(
+            *voting_keystore_password == Some(ZeroizeString::from(NEW_PASSWORD.to_string())),
+            "new password should be stored"
+        ),
+    }
+}
+
+#[test]
+fn validator_import_stdin_paths() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let path_a = src_dir.path().join(keystore_name(0));
+    let path_b = src_dir.path().join(keystore_name(1));
+    let keystore_a = write_keystore(&path_a, PASSWORD);
+    let keystore_b = write_keystore(&path_b, PASSWORD);
+
+    let run = |password_file: &Path| {
+        let mut child = validator_import_cmd()
+            .arg(format!("--{}", import::STDIN_PATHS_FLAG))
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str())
+            .stdin(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        child
+            .stdin
+            .as_mut()
+            .unwrap()
+            .write_all(format!("{}\n\n{}\n", path_a.display(), path_b.display()).as_bytes())
+            .unwrap();
+
+        child.wait_with_output().unwrap()
+    };
+
+    // A missing password must abort the import before anything is written.
+    let partial_password_file = src_dir.path().join("partial.txt");
+    fs::write(
+        &partial_password_file,
+        format!("0x{}:{}\n", keystore_a.pubkey(), PASSWORD),
+    )
+    .unwrap();
+
+    assert!(
+        !run(&partial_password_file).status.success(),
+        "import should fail without all passwords"
+    );
+    assert!(
+        !dst_dir
+            .path()
+            .join(format!("0x{}", keystore_a.pubkey()))
+            .exists(),
+        "no keystores should be imported"
+    );
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        format!(
+            "0x{}:{}\n0x{}:{}\n",
+            keystore_a.pubkey(),
+            PASSWORD,
+            keystore_b.pubkey(),
+            PASSWORD
+        ),
+    )
+    .unwrap();
+
+    assert!(
+        run(&password_file).status.success(),
+        "import should succeed"
+    );
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(
+        defs.as_slice().len(),
+        2,
+        "both keystores should be imported"
+    );
+}
+
+#[test]
+fn validator_verify() {
+    let validator_dir = tempdir().unwrap();
+
+    let keystore_path = validator_dir.path().join("voting-keystore.json");
+    let keystore = write_keystore(&keystore_path, "cats");
+
+    let verify = || {
+        validator_cmd()
+            .arg(VERIFY_CMD)
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(validator_dir.path().as_os_str())
+            .output()
+            .unwrap()
+    };
+
+    let mut defs = ValidatorDefinitions::default();
+    defs.push(
+        ValidatorDefinition::new_keystore_with_password(
+            &keystore_path,
+            Some(ZeroizeString::from("cats".to_string())),
+        )
+        .unwrap(),
+    );
+    defs.save(&validator_dir).unwrap();
+
+    assert!(verify().status.success(), "valid keystore should verify");
+
+    // A definition whose public key does not match the keystore should fail.
+    let mut mismatched_def = defs.as_slice()[0].clone();
+    mismatched_def.voting_public_key = Keypair::random().pk;
+    defs.push(mismatched_def.clone());
+    defs.save(&validator_dir).unwrap();
+
+    let output = verify();
+    assert!(!output.status.success(), "mismatched pubkey should fail");
+    assert!(
+        from_utf8(&output.stdout).unwrap().contains(&format!(
+            "FAILED\t{}",
+            mismatched_def.voting_public_key.to_hex_string()
+        )),
+        "mismatched validator should be reported"
+    );
+
+    // Disabled validators are not verified.
+    defs.as_mut_slice()[1].enabled = false;
+    defs.save(&validator_dir).unwrap();
+
+    assert!(
+        verify().status.success(),
+        "disabled validator should be ignored"
+    );
+}
+
+#[test]
+fn validator_import_no_store_password() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(
+        src_dir
+            .path()
+            .join("keystore-m_12381_3600_0_0_0-1595406747.json"),
+        PASSWORD,
+    );
+
+    let mut child = validator_import_cmd()
+        .arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+        .arg(format!("--{}", import::DIR_FLAG))
+        .arg(src_dir.path().as_os_str())
+        .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(dst_dir.path().as_os_str())
+        .arg(format!("--{}", import::NO_STORE_PASSWORD_FLAG))
+        .arg(format!("--{}", import::JSON_FLAG))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // An empty password is rejected, then an incorrect one, before the correct one is accepted.
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(format!("\ndogs\n{}\n", PASSWORD).as_bytes())
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "import should succeed");
+
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(summary["count"], 1);
+    assert_eq!(summary["validators"][0]["password_saved"], false);
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), 1, "keystore should be imported");
+    match &defs.as_slice()[0].signing_definition {
+        SigningDefinition::LocalKeystore {
+            voting_keystore_password,
+            voting_keystore_password_path,
+            ..
+        } => assert!(
+            voting_keystore_password.is_none() && voting_keystore_password_path.is_none(),
+            "password should not be stored"
+        ),
+    }
+}
+
+#[test]
+fn validator_import_json_no_prompt_delay() {
+    const NUM_KEYSTORES: usize = 5;
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    for i in 0..NUM_KEYSTORES {
+        write_keystore(src_dir.path().join(keystore_name(i)), "cats");
+    }
+
+    // Without --prompt-delay-ms, so that the default delay applies.
+    let started = Instant::now();
+    let mut child = validator_cmd()
+        .arg(IMPORT_CMD)
+        .arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+        .arg(format!("--{}", import::DIR_FLAG))
+        .arg(src_dir.path().as_os_str())
+        .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(dst_dir.path().as_os_str())
+        .arg(format!("--{}", import::JSON_FLAG))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Skip every password, so that no keystore is decrypted.
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all("\n".repeat(NUM_KEYSTORES).as_bytes())
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "import should succeed");
+
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(summary["count"], NUM_KEYSTORES);
+    assert!(
+        started.elapsed() < Duration::from_secs(NUM_KEYSTORES as u64),
+        "--json should not pause after each prompt"
+    );
+}
+
+#[test]
+fn validator_import_filename_pattern() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let custom = write_keystore(src_dir.path().join("validator-0.json"), PASSWORD);
+    let default = write_keystore(src_dir.path().join(keystore_name(0)), PASSWORD);
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        format!(
+            "0x{}:{}\n0x{}:{}\n",
+            custom.pubkey(),
+            PASSWORD,
+            default.pubkey(),
+            PASSWORD
+        ),
+    )
+    .unwrap();
+
+    output_result(
+        validator_import_cmd()
+            .arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+            .arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str())
+            .arg(format!("--{}", import::FILENAME_PATTERN_FLAG))
+            .arg(r"validator-[0-9]+\.json"),
+    )
+    .unwrap();
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(
+        defs.as_slice().len(),
+        1,
+        "only the matching keystore should be imported"
+    );
+    assert_eq!(
+        Some(defs.as_slice()[0].voting_public_key.clone()),
+        custom.public_key()
+    );
+}
+
+#[test]
+fn validator_import_log_level() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(
+        src_dir
+            .path()
+            .join("keystore-m_12381_3600_0_0_0-1595406747.json"),
+        "cats",
+    );
+
+    let import = |log_level: &str| {
+        let output = output_result(
+            validator_import_cmd()
+                .arg(format!("--{}", import::DIR_FLAG))
+                .arg(src_dir.path().as_os_str())
+                .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+                .arg(dst_dir.path().as_os_str())
+                .arg(format!("--{}", import::DRY_RUN_FLAG))
+                .arg(format!("--{}", import::LOG_LEVEL_FLAG))
+                .arg(log_level),
+        )
+        .unwrap();
+        String::from_utf8(output.stderr).unwrap()
+    };
+
+    assert!(
+        import("info").contains(&format!("0x{}", keystore.pubkey())),
+        "info logs should include the keystore"
+    );
+    assert!(
+        !import("error").contains(&format!("0x{}", keystore.pubkey())),
+        "info logs should be filtered"
+    );
+}
+
+#[test]
+fn validator_import_skip_existing() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let path_a = src_dir.path().join(keystore_name(0));
+    let keystore_a = write_keystore(&path_a, PASSWORD);
+    let keystore_b = write_keystore(src_dir.path().join(keystore_name(1)), PASSWORD);
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        format!(
+            "0x{}:{}\n0x{}:{}\n",
+            keystore_a.pubkey(),
+            PASSWORD,
+            keystore_b.pubkey(),
+            PASSWORD
+        ),
+    )
+    .unwrap();
+
+    let import = |source_flag: &str, source: &Path, skip_existing: bool| {
+        let mut cmd = validator_import_cmd();
+        cmd.arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+            .arg(format!("--{}", source_flag))
+            .arg(source.as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str());
+        if skip_existing {
+            cmd.arg(format!("--{}", import::SKIP_EXISTING_FLAG));
+        }
+        output_result(&mut cmd)
+    };
+
+    // Import the first keystore, as if a previous batch import was interrupted. The trailing
+    // newline changes the content hash, so only the public key identifies it as existing.
+    let copy_dir = tempdir().unwrap();
+    let copy_a = copy_dir.path().join(path_a.file_name().unwrap());
+    fs::write(
+        &copy_a,
+        [fs::read(&path_a).unwrap(), b"\n".to_vec()].concat(),
+    )
+    .unwrap();
+    import(import::KEYSTORE_FLAG, &copy_a, false).unwrap();
+
+    assert!(
+        import(import::DIR_FLAG, src_dir.path(), false).is_err(),
+        "import should abort without --skip-existing"
+    );
+    assert_eq!(
+        ValidatorDefinitions::open(&dst_dir)
+            .unwrap()
+            .as_slice()
+            .len(),
+        1
+    );
+
+    import(import::DIR_FLAG, src_dir.path(), true).unwrap();
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(
+        defs.as_slice().len(),
+        2,
+        "remaining keystore should be imported"
+    );
+    assert!(defs
+        .as_slice()
+        .iter()
+        .any(|def| Some(&def.voting_public_key) == keystore_b.public_key().as_ref()));
+}
+
+#[test]
+fn validator_import_min_kdf_cost() {
+    const PASSWORD: &str = "cats";
+    const KDF_COST: u32 = 1024;
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystore = KeystoreBuilder::new(&Keypair::random(), PASSWORD.as_bytes(), "".into())
+        .unwrap()
+        .kdf(Kdf::Pbkdf2(Pbkdf2 {
+            c: KDF_COST,
+            dklen: DKLEN,
+            prf: Prf::HmacSha256,
+            salt: vec![42; 32].into(),
+        }))
+        .build()
+        .unwrap();
+    let keystore_path = src_dir
+        .path()
+        .join("keystore-m_12381_3600_0_0_0-1595406747.json");
+    write_keystore_file(&keystore, &keystore_path);
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        format!("0x{}:{}\n", keystore.pubkey(), PASSWORD),
+    )
+    .unwrap();
+
+    let import = |min_kdf_cost: Option<(&str, u32)>| {
+        let mut cmd = validator_import_cmd();
+        cmd.arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+            .arg(format!("--{}", import::KEYSTORE_FLAG))
+            .arg(keystore_path.as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str())
+            .arg(format!("--{}", import::JSON_FLAG));
+        if let Some((flag, min_kdf_cost)) = min_kdf_cost {
+            cmd.arg(format!("--{}", flag)).arg(min_kdf_cost.to_string());
+        }
+        output_result(&mut cmd)
+    };
+
+    assert!(
+        import(Some((import::MIN_PBKDF2_C_FLAG, KDF_COST + 1))).is_err(),
+        "keystore below the minimum cost should be refused"
+    );
+    assert!(
+        !dst_dir
+            .path()
+            .join(format!("0x{}", keystore.pubkey()))
+            .exists(),
+        "no keystore should be imported"
+    );
+
+    // The scrypt minimum does not apply to a pbkdf2 keystore.
+    let output = import(Some((import::MIN_SCRYPT_N_FLAG, KDF_COST + 1))).unwrap();
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(summary["validators"][0]["kdf"], "pbkdf2");
+    assert_eq!(summary["validators"][0]["kdf_cost"], KDF_COST);
+    assert_eq!(
+        summary["validators"][0]["kdf_params"],
+        format!("pbkdf2 (c: {})", KDF_COST)
+    );
+}
+
+/// The pbkdf2 test vector from EIP-2335, the password is `testpassword`.
+const PBKDF2_KEYSTORE: &str = r#"
+    {
+        "crypto": {
+            "kdf": {
+                "function": "pbkdf2",
+                "params": {
+                    "dklen": 32,
+                    "c": 262144,
+                    "prf": "hmac-sha256",
+                    "salt": "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+                },
+                "message": ""
+            },
+            "checksum": {
+                "function": "sha256",
+                "params": {},
+                "message": "18b148af8e52920318084560fd766f9d09587b4915258dec0676cba5b0da09d8"
+            },
+            "cipher": {
+                "function": "aes-128-ctr",
+                "params": {
+                    "iv": "264daa3f303d7259501c93d997d84fe6"
+                },
+                "message": "a9249e0ca7315836356e4c7440361ff22b9fe71e2e2ed34fc1eb03976924ed48"
+            }
+        },
+        "pubkey": "9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07",
+        "path": "m/12381/60/0/0",
+        "uuid": "64625def-3331-4eea-ab6f-782f3ed16a83",
+        "version": 4
+    }
+"#;
+
+#[test]
+fn validator_import_pbkdf2() {
+    const PASSWORD: &str = "testpassword";
+    const KEYSTORE_NAME: &str = "keystore-m_12381_3600_0_0_0-1595406747.json";
+
+    let keystore = Keystore::from_json_str(PBKDF2_KEYSTORE).unwrap();
+
+    let import = |keystore_json: &str| {
+        let src_dir = tempdir().unwrap();
+        let dst_dir = tempdir().unwrap();
+
+        let keystore_path = src_dir.path().join(KEYSTORE_NAME);
+        fs::write(&keystore_path, keystore_json).unwrap();
+
+        let password_file = src_dir.path().join("passwords.txt");
+        fs::write(
+            &password_file,
+            format!("0x{}:{}\n", keystore.pubkey(), PASSWORD),
+        )
+        .unwrap();
+
+        let result = output_result(
+            validator_import_cmd()
+                .arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+                .arg(format!("--{}", import::KEYSTORE_FLAG))
+                .arg(keystore_path.as_os_str())
+                .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+                .arg(dst_dir.path().as_os_str())
+                .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+                .arg(password_file.as_os_str())
+                .arg(format!("--{}", import::JSON_FLAG)),
+        );
+
+        (result, dst_dir)
+    };
+
+    let (result, dst_dir) = import(PBKDF2_KEYSTORE);
+    let summary: serde_json::Value = serde_json::from_slice(&result.unwrap().stdout).unwrap();
+    assert_eq!(summary["validators"][0]["kdf"], "pbkdf2");
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), 1);
+    assert_eq!(
+        defs.as_slice()[0].voting_public_key,
+        keystore.public_key().unwrap()
+    );
+
+    let (result, dst_dir) = import(&PBKDF2_KEYSTORE.replace("pbkdf2", "argon2id"));
+    let stderr = result.expect_err("unsupported kdf should be refused");
+    assert!(
+        stderr.contains("Unsupported kdf function: argon2id"),
+        "error should name the kdf: {}",
+        stderr
+    );
+    assert!(
+        !dst_dir
+            .path()
+            .join(format!("0x{}", keystore.pubkey()))
+            .exists(),
+        "no keystore should be imported"
+    );
+}
+
+#[test]
+fn validator_import_count() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    // Created out of order, so the import order must come from the file names.
+    let keystores = [2, 0, 1]
+        .iter()
+        .map(|i| {
+            let keystore = write_keystore(src_dir.path().join(keystore_name(*i)), PASSWORD);
+            (*i, keystore)
+        })
+        .collect::<HashMap<_, _>>();
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        keystores
+            .values()
+            .map(|keystore| format!("0x{}:{}\n", keystore.pubkey(), PASSWORD))
+            .collect::<String>(),
+    )
+    .unwrap();
+
+    let import = || {
+        output_result(
+            validator_import_cmd()
+                .arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+                .arg(format!("--{}", import::DIR_FLAG))
+                .arg(src_dir.path().as_os_str())
+                .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+                .arg(dst_dir.path().as_os_str())
+                .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+                .arg(password_file.as_os_str())
+                .arg(format!("--{}", import::COUNT_FLAG))
+                .arg("2")
+                .arg(format!("--{}", import::SKIP_EXISTING_FLAG)),
+        )
+        .unwrap();
+
+        ValidatorDefinitions::open(&dst_dir)
+            .unwrap()
+            .as_slice()
+            .iter()
+            .map(|def| def.voting_public_key.clone())
+            .collect::<Vec<_>>()
+    };
+
+    let pubkey = |i: usize| keystores[&i].public_key().unwrap();
+
+    assert_eq!(import(), vec![pubkey(0), pubkey(1)], "first batch");
+    assert_eq!(
+        import(),
+        vec![pubkey(0), pubkey(1), pubkey(2)],
+        "second batch"
+    );
+    assert_eq!(
+        import(),
+        vec![pubkey(0), pubkey(1), pubkey(2)],
+        "nothing left to import"
+    );
+}
+
+#[test]
+fn validator_import_archive() {
+    const PASSWORD: &str = "cats";
+    const KEYSTORE_NAME: &str = "keystore-m_12381_3600_0_0_0-1595406747.json";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystore = KeystoreBuilder::new(&Keypair::random(), PASSWORD.as_bytes(), "".into())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    // Create an archive with a nested keystore and a not-keystore file.
+    let archive_path = src_dir.path().join("keystores.zip");
+    let mut archive = zip::ZipWriter::new(File::create(&archive_path).unwrap());
+    let options = zip::write::FileOptions::default();
+    archive
+        .start_file(format!("validator_keys/nested/{}", KEYSTORE_NAME), options)
+        .unwrap();
+    keystore.to_json_writer(&mut archive).unwrap();
+    archive
+        .start_file("validator_keys/deposit_data.json", options)
+        .unwrap();
+    archive.finish().unwrap();
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        format!("0x{}:{}\n", keystore.pubkey(), PASSWORD),
+    )
+    .unwrap();
+
+    output_result(
+        validator_import_cmd()
+            .arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+            .arg(format!("--{}", import::ARCHIVE_FLAG))
+            .arg(archive_path.as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str()),
+    )
+    .unwrap();
+
+    let voting_keystore_path = dst_dir
+        .path()
+        .join(format!("0x{}", keystore.pubkey()))
+        .join(KEYSTORE_NAME);
+    assert!(
+        voting_keystore_path.exists(),
+        "keystore should be present in dst dir"
+    );
+    assert!(
+        !dst_dir.path().join("deposit_data.json").exists(),
+        "not-keystore should not be present in dst dir"
+    );
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), 1);
+    assert_eq!(
+        defs.as_slice()[0].voting_public_key,
+        keystore.public_key().unwrap()
+    );
+}
+
+#[test]
+fn validator_import_stale_definitions() {
+    const PASSWORD: &str = "cats";
+    const KEYSTORE_NAME: &str = "keystore-m_12381_3600_0_0_0-1595406747.json";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(src_dir.path().join(KEYSTORE_NAME), PASSWORD);
+
+    let dst_keystore_dir = dst_dir.path().join(format!("0x{}", keystore.pubkey()));
+
+    // Rewrites the definitions file whilst the import waits for a password, as a running VC might.
+    let import = |force: bool| {
+        let mut cmd = validator_import_cmd();
+        cmd.arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+            .arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str());
+        if force {
+            cmd.arg(format!("--{}", import::FORCE_FLAG));
+        }
+        let mut child = cmd
+            .stderr(Stdio::piped())
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let mut stderr = child.stderr.as_mut().map(BufReader::new).unwrap().lines();
+        let stdin = child.stdin.as_mut().unwrap();
+
+        loop {
+            if stderr.next().unwrap().unwrap() == import::PASSWORD_PROMPT {
+                break;
+            }
+        }
+
+        let defs_path = dst_dir.path().join(CONFIG_FILENAME);
+        fs::write(&defs_path, fs::read(&defs_path).unwrap()).unwrap();
+
+        stdin
+            .write_all(format!("{}\n", PASSWORD).as_bytes())
+            .unwrap();
+
+        if !force {
+            loop {
+                if stderr.next().unwrap().unwrap() == import::OVERWRITE_PROMPT {
+                    break;
+                }
+            }
+            stdin.write_all(b"no\n").unwrap();
+        }
+
+        child.wait().unwrap().success()
+    };
+
+    assert!(!import(false), "import should be aborted");
+    assert!(
+        !dst_keystore_dir.exists(),
+        "keystore should not be imported"
+    );
+    assert!(ValidatorDefinitions::open(&dst_dir)
+        .unwrap()
+        .as_slice()
+        .is_empty());
+
+    assert!(import(true), "import should overwrite the definitions");
+    assert!(
+        dst_keystore_dir.join(KEYSTORE_NAME).exists(),
+        "keystore should be imported"
+    );
+    assert_eq!(
+        ValidatorDefinitions::open(&dst_dir)
+            .unwrap()
+            .as_slice()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn validator_import_lockfile() {
+    const PASSWORD: &str = "cats";
+    const KEYSTORE_NAME: &str = "keystore-m_12381_3600_0_0_0-1595406747.json";
+
+    let dst_dir = tempdir().unwrap();
+
+    let keystores = (0..2)
+        .map(|_| {
+            let src_dir = tempdir().unwrap();
+            let keystore = write_keystore(src_dir.path().join(KEYSTORE_NAME), PASSWORD);
+            (src_dir, keystore)
+        })
+        .collect::<Vec<_>>();
+
+    let import_cmd = |src_dir: &Path| {
+        let mut cmd = validator_import_cmd();
+        cmd.arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+            .arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str());
+        cmd
+    };
+
+    // Hold the first import at its password prompt.
+    let mut child = import_cmd(keystores[0].0.path())
+        .stderr(Stdio::piped())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stderr = child.stderr.as_mut().map(BufReader::new).unwrap().lines();
+    let stdin = child.stdin.as_mut().unwrap();
+
+    loop {
+        if stderr.next().unwrap().unwrap() == import::PASSWORD_PROMPT {
+            break;
+        }
+    }
+
+    let lockfile = dst_dir.path().join(import::LOCKFILE_NAME);
+    assert!(lockfile.exists(), "lockfile should be held during import");
+
+    let output = import_cmd(keystores[1].0.path()).output().unwrap();
+    assert!(!output.status.success(), "second import should fail");
+    let stderr_str = from_utf8(&output.stderr).unwrap();
+    assert!(
+        stderr_str.contains("The validator directory is locked"),
+        "error should mention the lock: {}",
+        stderr_str
+    );
+    assert!(
+        lockfile.exists(),
+        "lockfile should not be removed by another import"
+    );
+
+    stdin
+        .write_all(format!("{}\n", PASSWORD).as_bytes())
+        .unwrap();
+    assert!(
+        child.wait().unwrap().success(),
+        "first import should succeed"
+    );
+
+    let mut child = import_cmd(keystores[1].0.path())
+        .stdin(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(format!("{}\n", PASSWORD).as_bytes())
+        .unwrap();
+    assert!(
+        child.wait().unwrap().success(),
+        "second import should succeed"
+    );
+
+    let pubkeys = ValidatorDefinitions::open(&dst_dir)
+        .unwrap()
+        .as_slice()
+        .iter()
+        .map(|def| def.voting_public_key.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        pubkeys,
+        keystores
+            .iter()
+            .map(|(_, keystore)| keystore.public_key().unwrap())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn validator_import_graffiti_and_fee_recipient() {
+    const PASSWORD: &str = "cats";
+    const FLAG_GRAFFITI: &str = "flag graffiti";
+    const FLAG_ADDRESS: &str = "0x1111111111111111111111111111111111111111";
+    const OVERRIDE_ADDRESS: &str = "0x2222222222222222222222222222222222222222";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystores = (0..2)
+        .map(|i| write_keystore(src_dir.path().join(keystore_name(i)), PASSWORD))
+        .collect::<Vec<_>>();
+
+    // The second keystore overrides the graffiti and fee recipient.
+    let password_file = src_dir.path().join("passwords.json");
+    fs::write(
+        &password_file,
+        format!(
+            r#"{{
+                "0x{}": "{}",
+                "0x{}": {{
+                    "password": "{}",
+                    "graffiti": "override graffiti",
+                    "suggested_fee_recipient": "{}"
+                }}
+            }}"#,
+            keystores[0].pubkey(),
+            PASSWORD,
+            keystores[1].pubkey(),
+            PASSWORD,
+            OVERRIDE_ADDRESS
+        ),
+    )
+    .unwrap();
+
+    let import = |graffiti: &str, address: &str| {
+        output_result(
+            validator_import_cmd()
+                .arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+                .arg(format!("--{}", import::DIR_FLAG))
+                .arg(src_dir.path().as_os_str())
+                .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+                .arg(dst_dir.path().as_os_str())
+                .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+                .arg(password_file.as_os_str())
+                .arg(format!("--{}", import::GRAFFITI_FLAG))
+                .arg(graffiti)
+                .arg(format!("--{}", import::FEE_RECIPIENT_FLAG))
+                .arg(address),
+        )
+    };
+
+    let error = import(&"a".repeat(33), FLAG_ADDRESS).unwrap_err();
+    assert!(error.contains("Invalid --graffiti"), "{}", error);
+    let error = import(FLAG_GRAFFITI, &FLAG_ADDRESS[..40]).unwrap_err();
+    assert!(
+        error.contains("Invalid --suggested-fee-recipient"),
+        "{}",
+        error
+    );
+    let error = import(FLAG_GRAFFITI, &FLAG_ADDRESS[2..]).unwrap_err();
+    assert!(
+        error.contains("Invalid --suggested-fee-recipient"),
+        "{}",
+        error
+    );
+    assert!(
+        !dst_dir.path().join(CONFIG_FILENAME).exists(),
+        "invalid flags should be rejected before any files are modified"
+    );
+
+    import(FLAG_GRAFFITI, FLAG_ADDRESS).unwrap();
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    let defs = defs.as_slice();
+    assert_eq!(defs.len(), 2);
+
+    assert_eq!(
+        defs[0].voting_public_key,
+        keystores[0].public_key().unwrap()
+    );
+    assert_eq!(defs[0].graffiti.as_deref(), Some(FLAG_GRAFFITI));
+    assert_eq!(
+        defs[0].suggested_fee_recipient,
+        Some(FLAG_ADDRESS[2..].parse().unwrap())
+    );
+
+    assert_eq!(
+        defs[1].voting_public_key,
+        keystores[1].public_key().unwrap()
+    );
+    assert_eq!(defs[1].graffiti.as_deref(), Some("override graffiti"));
+    assert_eq!(
+        defs[1].suggested_fee_recipient,
+        Some(OVERRIDE_ADDRESS[2..].parse().unwrap())
+    );
+}
+
+#[test]
+fn validator_import_twice() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystores = (0..2)
+        .map(|i| write_keystore(src_dir.path().join(keystore_name(i)), PASSWORD))
+        .collect::<Vec<_>>();
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        keystores
+            .iter()
+            .map(|keystore| format!("0x{}:{}\n", keystore.pubkey(), PASSWORD))
+            .collect::<String>(),
+    )
+    .unwrap();
+
+    let import = || {
+        output_result(
+            validator_import_cmd()
+                .arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+                .arg(format!("--{}", import::DIR_FLAG))
+                .arg(src_dir.path().as_os_str())
+                .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+                .arg(dst_dir.path().as_os_str())
+                .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+                .arg(password_file.as_os_str()),
+        )
+        .unwrap();
+
+        ValidatorDefinitions::open(&dst_dir).unwrap()
+    };
+
+    let first = import();
+    assert_eq!(first.as_slice().len(), 2);
+
+    // Renaming the validator directories does not affect the content hash.
+    for keystore in &keystores {
+        let pubkey_dir = dst_dir.path().join(format!("0x{}", keystore.pubkey()));
+        fs::rename(&pubkey_dir, pubkey_dir.with_extension("renamed")).unwrap();
+    }
+
+    let second = import();
+    assert!(
+        first.as_slice() == second.as_slice(),
+        "second import should not add definitions"
+    );
+}
+
+#[test]
+fn validator_import_password_env() {
+    const PASSWORD: &str = "cats";
+    const PASSWORD_VAR: &str = "LIGHTHOUSE_TEST_KEYSTORE_PASSWORD";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystores = (0..2)
+        .map(|i| {
+            let path = src_dir.path().join(keystore_name(i));
+            let keystore = write_keystore(&path, PASSWORD);
+            (keystore, path)
+        })
+        .collect::<Vec<_>>();
+
+    let import = |password: &str| {
+        let mut child = validator_import_cmd()
+            .arg(format!("--{}", import::STDIN_PATHS_FLAG))
+            .arg(format!("--{}", import::PASSWORD_ENV_FLAG))
+            .arg(PASSWORD_VAR)
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .env(PASSWORD_VAR, password)
+            .stdin(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let paths = keystores
+            .iter()
+            .map(|(_, path)| format!("{}\n", path.to_str().unwrap()))
+            .collect::<String>();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(paths.as_bytes())
+            .unwrap();
+
+        let output = child.wait_with_output().unwrap();
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8(output.stderr).unwrap())
+        }
+    };
+
+    let error = import("dogs").unwrap_err();
+    assert!(
+        error.contains("do not provide a correct password"),
+        "incorrect password should be rejected: {}",
+        error
+    );
+
+    import(PASSWORD).unwrap();
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), 2);
+    for (def, (keystore, _)) in defs.as_slice().iter().zip(keystores.iter()) {
+        assert_eq!(def.voting_public_key, keystore.public_key().unwrap());
+        match &def.signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_password,
+                ..
+            } => assert!(
+                voting_keystore_password == &Some(ZeroizeString::from(PASSWORD.to_string())),
+                "password should be stored"
+            ),
+        }
+    }
+
+    let output = validator_import_cmd()
+        .arg(format!("--{}", import::STDIN_PATHS_FLAG))
+        .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(dst_dir.path().as_os_str())
+        .output()
+        .unwrap();
+    assert!(
+        !output.status.success(),
+        "--stdin-paths should require a password source"
+    );
+}
+
+#[test]
+fn validator_modify() {
+    let validator_dir = tempdir().unwrap();
+
+    // Both keystores have their password changed from "cats" to "dogs" externally.
+    let keystores = (0..2)
+        .map(|i| {
+            let keypair = Keypair::random();
+            let keystore_path = validator_dir
+                .path()
+                .join(format!("voting-keystore-{}.json", i));
+            let build = |password: &[u8]| {
+                let keystore = KeystoreBuilder::new(&keypair, password, "".into())
+                    .unwrap()
+                    .build()
+                    .unwrap();
+                write_keystore_file(&keystore, &keystore_path);
+                keystore
+            };
+            build(b"cats");
+            let keystore = build(b"dogs");
+            (keystore, keystore_path)
+        })
+        .collect::<Vec<_>>();
+
+    let mut defs = ValidatorDefinitions::default();
+    for (_, keystore_path) in &keystores {
+        defs.push(
+            ValidatorDefinition::new_keystore_with_password(
+                keystore_path,
+                Some(ZeroizeString::from("cats".to_string())),
+            )
+            .unwrap(),
+        );
+    }
+    defs.save(&validator_dir).unwrap();
+
+    let modify = |target: &[String], input: &str| {
+        let mut child = validator_cmd()
+            .arg(MODIFY_CMD)
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(validator_dir.path().as_os_str())
+            .arg(format!("--{}", modify::STDIN_PASSWORD_FLAG))
+            .args(target)
+            .stdin(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        child.wait().unwrap().success()
+    };
+
+    let stored_passwords = || {
+        ValidatorDefinitions::open(&validator_dir)
+            .unwrap()
+            .as_slice()
+            .iter()
+            .map(|def| match &def.signing_definition {
+                SigningDefinition::LocalKeystore {
+                    voting_keystore_password,
+                    ..
+                } => String::from_utf8(voting_keystore_password.clone().unwrap().as_ref().to_vec())
+                    .unwrap(),
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let pubkey_target = vec![
+        format!("--{}", modify::PUBKEY_FLAG),
+        format!("0x{}", keystores[0].0.pubkey()),
+    ];
+
+    assert!(
+        !modify(&pubkey_target, "cats\n"),
+        "an incorrect password should not be stored"
+    );
+    assert_eq!(stored_passwords(), vec!["cats", "cats"]);
+
+    assert!(
+        !modify(
+            &[format!("--{}", modify::PUBKEY_FLAG), "0x1234".to_string()],
+            "dogs\n"
+        ),
+        "an unknown public key should be rejected"
+    );
+
+    // The incorrect password is prompted for again.
+    assert!(modify(&pubkey_target, "cats\ndogs\n"));
+    assert_eq!(stored_passwords(), vec!["dogs", "cats"]);
+
+    assert!(modify(&[format!("--{}", modify::ALL_FLAG)], "dogs\ndogs\n"));
+    assert_eq!(stored_passwords(), vec!["dogs", "dogs"]);
+}
+
+#[test]
+fn validator_import_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    const PASSWORD: &str = "cats";
+    const KEYSTORE_NAME: &str = "keystore-m_12381_3600_0_0_0-1595406747.json";
+
+    let src_dir = tempdir().unwrap();
+
+    let src_keystore = src_dir.path().join(KEYSTORE_NAME);
+    let keystore = write_keystore(&src_keystore, PASSWORD);
+    fs::set_permissions(&src_keystore, fs::Permissions::from_mode(0o644)).unwrap();
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        format!("0x{}:{}\n", keystore.pubkey(), PASSWORD),
+    )
+    .unwrap();
+
+    let import = |dst_dir: &Path, strict_perms: bool| {
+        let mut cmd = validator_import_cmd();
+        cmd.arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+            .arg(format!("--{}", import::KEYSTORE_FLAG))
+            .arg(src_keystore.as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str());
+        if strict_perms {
+            cmd.arg(format!("--{}", import::STRICT_PERMS_FLAG));
+        }
+        output_result(&mut cmd)
+    };
+    let dst_keystore = |dst_dir: &Path| {
+        dst_dir
+            .join(format!("0x{}", keystore.pubkey()))
+            .join(KEYSTORE_NAME)
+    };
+
+    let dst_dir = tempdir().unwrap();
+    let output = import(dst_dir.path(), false).unwrap();
+    assert!(
+        from_utf8(&output.stderr)
+            .unwrap()
+            .contains("Keystore is readable by other users"),
+        "a warning should be logged"
+    );
+    let mode = fs::metadata(dst_keystore(dst_dir.path()))
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o600, "imported keystore should be 0600");
+
+    let dst_dir = tempdir().unwrap();
+    let error = import(dst_dir.path(), true).unwrap_err();
+    assert!(error.contains("readable by other users"), "{}", error);
+    assert!(
+        !dst_keystore(dst_dir.path()).exists(),
+        "keystore should not be imported"
+    );
+
+    fs::set_permissions(&src_keystore, fs::Permissions::from_mode(0o600)).unwrap();
+    import(dst_dir.path(), true).unwrap();
+    assert!(dst_keystore(dst_dir.path()).exists());
+}
+
+/// Writes a testnet directory for a network with `genesis_validators_root`.
+fn write_testnet_dir(dir: &Path, genesis_validators_root: Hash256) {
+    let spec = ChainSpec::mainnet();
+    let mut genesis_state = BeaconState::<MainnetEthSpec>::new(0, Eth1Data::default(), &spec);
+    genesis_state.genesis_validators_root = genesis_validators_root;
+
+    Eth2TestnetConfig {
+        deposit_contract_address: "0x0000000000000000000000000000000000000000".into(),
+        deposit_contract_deploy_block: 0,
+        boot_enr: None,
+        genesis_state: Some(genesis_state),
+        yaml_config: Some(YamlConfig::from_spec::<MainnetEthSpec>(&spec)),
+    }
+    .write_to_file(dir.into(), true)
+    .unwrap();
+}
+
+#[test]
+fn validator_import_slashing_protection() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let testnet_dir = tempdir().unwrap();
+    let genesis_validators_root = Hash256::repeat_byte(1);
+    write_testnet_dir(testnet_dir.path(), genesis_validators_root);
+
+    let keystores = (0..2)
+        .map(|i| write_keystore(src_dir.path().join(keystore_name(i)), PASSWORD))
+        .collect::<Vec<_>>();
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        keystores
+            .iter()
+            .map(|keystore| format!("0x{}:{}\n", keystore.pubkey(), PASSWORD))
+            .collect::<String>(),
+    )
+    .unwrap();
+
+    // Only the first validator has signed before.
+    let interchange_file = src_dir.path().join("interchange.json");
+    let write_interchange = |genesis_validators_root: Hash256| {
+        let interchange = Interchange {
+            metadata: InterchangeMetadata {
+                interchange_format_version: INTERCHANGE_FORMAT_VERSION.to_string(),
+                genesis_validators_root,
+            },
+            data: vec![InterchangeData {
+                pubkey: keystores[0].public_key().unwrap(),
+                signed_blocks: vec![InterchangeBlock {
+                    slot: Slot::new(10),
+                    signing_root: None,
+                }],
+                signed_attestations: vec![],
+            }],
+        };
+        interchange
+            .to_json_writer(File::create(&interchange_file).unwrap())
+            .unwrap();
+    };
+
+    let import = || {
+        output_result(
+            validator_import_cmd()
+                .arg("--testnet-dir")
+                .arg(testnet_dir.path().as_os_str())
+                .arg(format!("--{}", import::DIR_FLAG))
+                .arg(src_dir.path().as_os_str())
+                .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+                .arg(dst_dir.path().as_os_str())
+                .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+                .arg(password_file.as_os_str())
+                .arg(format!("--{}", import::SLASHING_PROTECTION_FLAG))
+                .arg(interchange_file.as_os_str()),
+        )
+    };
+
+    write_interchange(Hash256::repeat_byte(2));
+    let error = import().unwrap_err();
+    assert!(error.contains("different network"), "{}", error);
+    assert!(
+        error.contains(&format!("{:?}", Hash256::repeat_byte(2)))
+            && error.contains(&format!("{:?}", genesis_validators_root)),
+        "both genesis validators roots should be printed: {}",
+        error
+    );
+    assert!(
+        !dst_dir.path().join(CONFIG_FILENAME).exists(),
+        "no validators should be imported"
+    );
+    assert!(!dst_dir.path().join(SLASHING_PROTECTION_FILENAME).exists());
+
+    write_interchange(genesis_validators_root);
+    import().unwrap();
+    assert_eq!(
+        ValidatorDefinitions::open(&dst_dir)
+            .unwrap()
+            .as_slice()
+            .len(),
+        2
+    );
+
+    let db = SlashingDatabase::open(&dst_dir.path().join(SLASHING_PROTECTION_FILENAME)).unwrap();
+    let block = BeaconBlockHeader {
+        slot: Slot::new(10),
+        proposer_index: 0,
+        parent_root: Hash256::zero(),
+        state_root: Hash256::zero(),
+        body_root: Hash256::zero(),
+    };
+    assert!(
+        matches!(
+            db.check_and_insert_block_proposal(
+                &keystores[0].public_key().unwrap(),
+                &block,
+                Hash256::zero()
+            ),
+            Err(NotSafe::InvalidBlock(InvalidBlock::DoubleBlockProposal(_)))
+        ),
+        "the imported history should prevent a double proposal"
+    );
+    assert_eq!(
+        db.check_and_insert_block_proposal(
+            &keystores[1].public_key().unwrap(),
+            &block,
+            Hash256::zero()
+        ),
+        Ok(Safe::Valid),
+        "the validator without history should be registered"
+    );
+}
+
+#[test]
+fn validator_slashing_protection_export() {
+    let validator_dir = tempdir().unwrap();
+    let testnet_dir = tempdir().unwrap();
+    let genesis_validators_root = Hash256::repeat_byte(1);
+    write_testnet_dir(testnet_dir.path(), genesis_validators_root);
+
+    let pubkeys = (0..2).map(|_| Keypair::random().pk).collect::<Vec<_>>();
+    let interchange = Interchange {
+        metadata: InterchangeMetadata {
+            interchange_format_version: INTERCHANGE_FORMAT_VERSION.to_string(),
+            genesis_validators_root,
+        },
+        data: vec![
+            InterchangeData {
+                pubkey: pubkeys[0].clone(),
+                signed_blocks: vec![InterchangeBlock {
+                    slot: Slot::new(10),
+                    signing_root: Some(Hash256::repeat_byte(3)),
+                }],
+                signed_attestations: vec![],
+            },
+            InterchangeData {
+                pubkey: pubkeys[1].clone(),
+                signed_blocks: vec![],
+                signed_attestations: vec![],
+            },
+        ],
+    };
+    SlashingDatabase::create(&validator_dir.path().join(SLASHING_PROTECTION_FILENAME))
+        .unwrap()
+        .import_interchange_info(&interchange, genesis_validators_root)
+        .unwrap();
+
+    let export_file = validator_dir.path().join("interchange.json");
+    let export = |pubkeys: Option<String>| {
+        let mut cmd = validator_cmd();
+        cmd.arg("--testnet-dir")
+            .arg(testnet_dir.path().as_os_str())
+            .arg(SLASHING_PROTECTION_CMD)
+            .arg(SLASHING_PROTECTION_EXPORT_CMD)
+            .arg(export_file.as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(validator_dir.path().as_os_str());
+        if let Some(pubkeys) = pubkeys {
+            cmd.arg(format!("--{}", slashing_protection_export::PUBKEYS_FLAG))
+                .arg(pubkeys);
+        }
+        output_result(&mut cmd)
+            .map(|_| Interchange::from_json_reader(File::open(&export_file).unwrap()).unwrap())
+    };
+
+    assert_eq!(export(None).unwrap(), interchange);
+
+    let exported = export(Some(pubkeys[0].to_hex_string())).unwrap();
+    assert_eq!(exported.data, interchange.data[..1].to_vec());
+
+    let unknown = Keypair::random().pk.to_hex_string();
+    let error = export(Some(format!("{},{}", pubkeys[1].to_hex_string(), unknown))).unwrap_err();
+    assert!(error.contains(&unknown), "{}", error);
+}
+
+#[test]
+fn validator_import_all() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+
+    for i in 0..2 {
+        write_keystore(src_dir.path().join(keystore_name(i)), PASSWORD);
+    }
+
+    // Neither flag should prompt for a selection, so every keystore is imported.
+    for flag in &[import::ALL_FLAG, import::NO_TTY_FLAG] {
+        let dst_dir = tempdir().unwrap();
+
+        let mut child = validator_import_cmd()
+            .arg(format!("--{}", flag))
+            .arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+            .arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .stdin(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(format!("{}\n{}\n", PASSWORD, PASSWORD).as_bytes())
+            .unwrap();
+
+        let output = child.wait_with_output().unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            from_utf8(&output.stderr).unwrap()
+        );
+        assert_eq!(
+            ValidatorDefinitions::open(&dst_dir)
+                .unwrap()
+                .as_slice()
+                .len(),
+            2
+        );
+    }
+}
+
+#[test]
+fn validator_import_keep_going() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+
+    let keystores = (0..3)
+        .map(|i| write_keystore(src_dir.path().join(keystore_name(i)), PASSWORD))
+        .collect::<Vec<_>>();
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        keystores
+            .iter()
+            .map(|keystore| format!("0x{}:{}\n", keystore.pubkey(), PASSWORD))
+            .collect::<String>(),
+    )
+    .unwrap();
+
+    // A file in the place of a keystore's staging directory cannot be removed, so the import of
+    // that keystore fails.
+    let import = |failing: &[usize], keep_going: bool| {
+        let dst_dir = tempdir().unwrap();
+        let staging_dir = dst_dir.path().join(import::STAGING_DIR);
+        fs::create_dir_all(&staging_dir).unwrap();
+        for i in failing {
+            fs::write(staging_dir.join(keystores[*i].uuid().to_string()), "").unwrap();
+        }
+
+        let mut cmd = validator_import_cmd();
+        cmd.arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str());
+        if keep_going {
+            cmd.arg(format!("--{}", import::KEEP_GOING_FLAG));
+        }
+        let output = cmd.output().unwrap();
+        let num_imported = ValidatorDefinitions::open(&dst_dir)
+            .map(|defs| defs.as_slice().len())
+            .unwrap_or(0);
+
+        (
+            output.status.code(),
+            from_utf8(&output.stderr).unwrap().to_string(),
+            num_imported,
+        )
+    };
+
+    let (code, stderr, num_imported) = import(&[], true);
+    assert_eq!(code, Some(0), "{}", stderr);
+    assert_eq!(num_imported, 3);
+
+    let (code, stderr, num_imported) = import(&[1], false);
+    assert_eq!(code, Some(1), "{}", stderr);
+    assert_eq!(num_imported, 1, "the import should stop at the failure");
+
+    let (code, stderr, num_imported) = import(&[1], true);
+    assert_eq!(code, Some(import::PARTIAL_FAILURE_EXIT_CODE), "{}", stderr);
+    assert_eq!(num_imported, 2, "the other keystores should be imported");
+    assert!(stderr.contains("Import summary"), "{}", stderr);
+    assert!(
+        stderr.contains(&format!("0x{}", keystores[1].pubkey())) && stderr.contains("failed: "),
+        "{}",
+        stderr
+    );
+
+    let (code, stderr, num_imported) = import(&[0, 1, 2], true);
+    assert_eq!(code, Some(1), "{}", stderr);
+    assert_eq!(num_imported, 0);
+}
+
+#[test]
+fn validator_import_backup_definitions() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystore_paths = (0..2)
+        .map(|i| {
+            let path = src_dir.path().join(keystore_name(i));
+            write_keystore(&path, PASSWORD);
+            path
+        })
+        .collect::<Vec<_>>();
+
+    let import = |keystore: &Path| {
+        let mut child = validator_import_cmd()
+            .arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+            .arg(format!("--{}", import::KEYSTORE_FLAG))
+            .arg(keystore.as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::BACKUP_DEFINITIONS_FLAG))
+            .arg(format!("--{}", import::MAX_BACKUPS_FLAG))
+            .arg("2")
+            .stdin(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(format!("{}\n", PASSWORD).as_bytes())
+            .unwrap();
+
+        let output = child.wait_with_output().unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            from_utf8(&output.stderr).unwrap()
+        );
+    };
+    let backups = || {
+        let mut backups = fs::read_dir(dst_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .filter(|name| name.starts_with(import::BACKUP_PREFIX))
+            .collect::<Vec<_>>();
+        backups.sort_by_key(|name| name[import::BACKUP_PREFIX.len()..].parse::<u128>().unwrap());
+        backups
+    };
+
+    // There is nothing to back up before the first import.
+    import(&keystore_paths[0]);
+    assert!(backups().is_empty());
+
+    let old_backups = (1..3)
+        .map(|time| format!("{}{}", import::BACKUP_PREFIX, time))
+        .collect::<Vec<_>>();
+    for backup in &old_backups {
+        fs::write(dst_dir.path().join(backup), "").unwrap();
+    }
+    let defs = fs::read(dst_dir.path().join(CONFIG_FILENAME)).unwrap();
+
+    import(&keystore_paths[1]);
+    let backups = backups();
+    assert_eq!(backups.len(), 2, "the oldest backup should be removed");
+    assert_eq!(backups[0], old_backups[1]);
+    assert_eq!(
+        fs::read(dst_dir.path().join(&backups[1])).unwrap(),
+        defs,
+        "the new backup should contain the definitions from before the import"
+    );
+}
+
+#[test]
+fn validator_import_pubkey_mismatch() {
+    const PASSWORD: &str = "cats";
+    const KEYSTORE_NAME: &str = "keystore-m_12381_3600_0_0_0-1595406747.json";
+
+    let src_dir = tempdir().unwrap();
+
+    // The advertised public key of the keystore is replaced with that of another keypair.
+    let keystore = KeystoreBuilder::new(&Keypair::random(), PASSWORD.as_bytes(), "".into())
+        .unwrap()
+        .build()
+        .unwrap();
+    let other_pubkey = Keypair::random().pk.to_hex_string();
+    let mut json = serde_json::to_value(&keystore).unwrap();
+    json["pubkey"] = serde_json::Value::String(other_pubkey[2..].to_string());
+    let src_keystore = src_dir.path().join(KEYSTORE_NAME);
+    fs::write(&src_keystore, serde_json::to_string(&json).unwrap()).unwrap();
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(&password_file, format!("{}:{}\n", other_pubkey, PASSWORD)).unwrap();
+
+    let import = |use_password_file: bool| {
+        let dst_dir = tempdir().unwrap();
+
+        let mut cmd = validator_import_cmd();
+        cmd.arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+            .arg(format!("--{}", import::KEYSTORE_FLAG))
+            .arg(src_keystore.as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .stdin(Stdio::piped())
+            .stderr(Stdio::piped());
+        if use_password_file {
+            cmd.arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+                .arg(password_file.as_os_str());
+        }
+        let mut child = cmd.spawn().unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(format!("{}\n", PASSWORD).as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+
+        assert!(!output.status.success(), "the import should fail");
+        let stderr = from_utf8(&output.stderr).unwrap();
+        assert!(
+            stderr.contains("does not match its public key"),
+            "{}",
+            stderr
+        );
+        assert!(
+            !dst_dir.path().join(&other_pubkey).exists(),
+            "the keystore should not be moved"
+        );
+    };
+
+    import(true);
+    import(false);
+}
+
+#[test]
+fn validator_import_wallet_name() {
+    const PASSWORD: &str = "cats";
+    const WALLET_NAME: &str = "provider-a";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystores = (0..2)
+        .map(|i| write_keystore(src_dir.path().join(keystore_name(i)), PASSWORD))
+        .collect::<Vec<_>>();
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        keystores
+            .iter()
+            .map(|keystore| format!("0x{}:{}\n", keystore.pubkey(), PASSWORD))
+            .collect::<String>(),
+    )
+    .unwrap();
+
+    let import = |keystores_dir: &Path, validator_dir: &Path, wallet_name: Option<&str>| {
+        let mut cmd = validator_import_cmd();
+        cmd.arg(format!("--{}", import::DIR_FLAG))
+            .arg(keystores_dir.as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(validator_dir.as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str());
+        if let Some(wallet_name) = wallet_name {
+            cmd.arg(format!("--{}", import::WALLET_NAME_FLAG))
+                .arg(wallet_name);
+        }
+        cmd.output().unwrap()
+    };
+
+    // Names which are not a single directory are rejected before any files are modified.
+    for name in &["../escape", "a/b", ".staging", ""] {
+        let output = import(src_dir.path(), dst_dir.path(), Some(name));
+        assert!(!output.status.success(), "{:?} should be rejected", name);
+        assert_eq!(dir_child_count(dst_dir.path()), 0);
+    }
+
+    let output = import(src_dir.path(), dst_dir.path(), Some(WALLET_NAME));
+    assert!(
+        output.status.success(),
+        "{}",
+        from_utf8(&output.stderr).unwrap()
+    );
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), 2);
+    for (i, (keystore, def)) in keystores.iter().zip(defs.as_slice()).enumerate() {
+        let expected_path = dst_dir
+            .path()
+            .join(WALLET_NAME)
+            .join(format!("0x{}", keystore.pubkey()))
+            .join(keystore_name(i));
+        assert!(expected_path.exists());
+        match &def.signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                ..
+            } => assert_eq!(voting_keystore_path, &expected_path),
+        }
+    }
+
+    // The nested keystores are still found when the validator directory is imported elsewhere.
+    let other_dir = tempdir().unwrap();
+    let output = import(dst_dir.path(), other_dir.path(), None);
+    assert!(
+        output.status.success(),
+        "{}",
+        from_utf8(&output.stderr).unwrap()
+    );
+    assert_eq!(
+        ValidatorDefinitions::open(&other_dir)
+            .unwrap()
+            .as_slice()
+            .len(),
+        2
+    );
+    for keystore in &keystores {
+        assert!(other_dir
+            .path()
+            .join(format!("0x{}", keystore.pubkey()))
+            .exists());
+    }
+}
+
+#[test]
+fn validator_import_progress() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+
+    let keystores = (0..3)
+        .map(|i| write_keystore(src_dir.path().join(keystore_name(i)), PASSWORD))
+        .collect::<Vec<_>>();
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        keystores
+            .iter()
+            .map(|keystore| format!("0x{}:{}\n", keystore.pubkey(), PASSWORD))
+            .collect::<String>(),
+    )
+    .unwrap();
+
+    let import = |flag: Option<&str>| {
+        let dst_dir = tempdir().unwrap();
+        let mut cmd = validator_import_cmd();
+        cmd.arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str());
+        if let Some(flag) = flag {
+            cmd.arg(format!("--{}", flag));
+        }
+        let output = cmd.output().unwrap();
+        let stderr = from_utf8(&output.stderr).unwrap().to_string();
+        assert!(output.status.success(), "{}", stderr);
+        stderr
+    };
+
+    let stderr = import(None);
+    for (i, keystore) in keystores.iter().enumerate() {
+        assert!(
+            stderr.contains(&format!("[{}/3] importing 0x{}", i + 1, keystore.pubkey())),
+            "{}",
+            stderr
+        );
+    }
+    assert!(
+        stderr.contains("[3/3] checking the password for 0x"),
+        "{}",
+        stderr
+    );
+
+    for flag in &[import::NO_PROGRESS_FLAG, import::JSON_FLAG] {
+        let stderr = import(Some(flag));
+        assert!(!stderr.contains("/3]"), "{}", stderr);
+    }
+}
+
+#[test]
+#[cfg(feature = "hardware_bundle")]
+fn validator_import_hardware_bundle() {
+    use account_manager::validator::hardware_bundle::Bundle;
+
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keypairs = vec![Keypair::random(), Keypair::random()];
+    let kdf = Kdf::Pbkdf2(Pbkdf2 {
+        c: 2,
+        dklen: DKLEN,
+        prf: Prf::HmacSha256,
+        salt: vec![42; 32].into(),
+    });
+    let bundle_path = src_dir.path().join("bundle.json");
+    File::create(&bundle_path)
+        .map(|file| {
+            Bundle::encrypt(&keypairs, PASSWORD.as_bytes(), kdf)
+                .unwrap()
+                .to_json_writer(file)
+                .unwrap()
+        })
+        .unwrap();
+
+    let mut child = validator_import_cmd()
+        .arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+        .arg(format!("--{}", import::KEYSTORE_FLAG))
+        .arg(bundle_path.as_os_str())
+        .arg(format!("--{}", import::FORMAT_FLAG))
+        .arg(import::FORMAT_HARDWARE_BUNDLE)
+        .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(dst_dir.path().as_os_str())
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // An incorrect password is rejected before the correct one is accepted.
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(format!("dogs\n{}\n", PASSWORD).as_bytes())
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    let stderr = from_utf8(&output.stderr).unwrap();
+    assert!(output.status.success(), "{}", stderr);
+    assert!(stderr.contains("Invalid password"), "{}", stderr);
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), 2);
+    for (keypair, def) in keypairs.iter().zip(defs.as_slice()) {
+        assert_eq!(def.voting_public_key, keypair.pk);
+        match &def.signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                voting_keystore_password,
+                ..
+            } => {
+                let keystore = Keystore::from_json_file(voting_keystore_path).unwrap();
+                assert_eq!(
+                    keystore.decrypt_keypair(PASSWORD.as_bytes()).unwrap().pk,
+                    keypair.pk
+                );
+                assert!(
+                    *voting_keystore_password == Some(ZeroizeString::from(PASSWORD.to_string()))
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn validator_import_output_path() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+    let work_dir = tempdir().unwrap();
+    let defs_dir = tempdir().unwrap();
+
+    let keystore_path = src_dir.path().join("keystore.json");
+    let keystore = write_keystore(&keystore_path, PASSWORD);
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        format!("0x{}:{}\n", keystore.pubkey(), PASSWORD),
+    )
+    .unwrap();
+
+    // The validator directory is relative to the working directory, and does not exist yet.
+    let output = validator_import_cmd()
+        .current_dir(work_dir.path())
+        .arg(format!("--{}", import::KEYSTORE_FLAG))
+        .arg(keystore_path.as_os_str())
+        .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg("validators")
+        .arg(format!("--{}", import::OUTPUT_PATH_FLAG))
+        .arg(defs_dir.path().join("definitions").as_os_str())
+        .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+        .arg(password_file.as_os_str())
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        from_utf8(&output.stderr).unwrap()
+    );
+
+    let validator_dir = work_dir.path().join("validators");
+    assert!(!validator_dir.join(CONFIG_FILENAME).exists());
+
+    let defs = ValidatorDefinitions::open(defs_dir.path().join("definitions")).unwrap();
+    assert_eq!(defs.as_slice().len(), 1);
+    match &defs.as_slice()[0].signing_definition {
+        SigningDefinition::LocalKeystore {
+            voting_keystore_path,
+            ..
+        } => {
+            assert!(voting_keystore_path.is_absolute());
+            assert_eq!(
+                voting_keystore_path,
+                &validator_dir
+                    .join(format!("0x{}", keystore.pubkey()))
+                    .join("keystore.json")
+            );
+        }
+    }
+}
+
+#[test]
+fn validator_import_prompt_timeout() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystores = (0..2)
+        .map(|i| write_keystore(src_dir.path().join(keystore_name(i)), PASSWORD))
+        .collect::<Vec<_>>();
+
+    let mut child = validator_import_cmd()
+        .arg(format!("--{}", import::STDIN_PASSWORD_FLAG)) // Using tty does not work well with tests.
+        .arg(format!("--{}", import::DIR_FLAG))
+        .arg(src_dir.path().as_os_str())
+        .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(dst_dir.path().as_os_str())
+        .arg(format!("--{}", import::PROMPT_TIMEOUT_FLAG))
+        .arg("1")
+        .stderr(Stdio::piped())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // The first prompt is left to time out, and the password is entered at the second.
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut output = String::new();
+    let mut num_prompts = 0;
+    while num_prompts < 2 {
+        let mut line = String::new();
+        assert_ne!(stderr.read_line(&mut line).unwrap(), 0, "{}", output);
+        if line.contains(import::PASSWORD_PROMPT) {
+            num_prompts += 1;
+        }
+        output.push_str(&line);
+    }
+    stdin
+        .write_all(format!("{}\n", PASSWORD).as_bytes())
+        .unwrap();
+    stderr.read_to_string(&mut output).unwrap();
+    let status = child.wait().unwrap();
+
+    assert_eq!(
+        status.code(),
+        Some(import::PARTIAL_FAILURE_EXIT_CODE),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains("No password was entered within 1 seconds"),
+        "{}",
+        output
+    );
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), 1);
+    assert_eq!(
+        defs.as_slice()[0].voting_public_key,
+        keystores[1].public_key().unwrap()
+    );
+    assert!(!dst_dir
+        .path()
+        .join(format!("0x{}", keystores[0].pubkey()))
+        .exists());
+}
+
+#[test]
+fn validator_import_journal() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+    let journal_dir = tempdir().unwrap();
+    let journal = journal_dir.path().join("import.journal");
+
+    let keystores = (0..3)
+        .map(|i| write_keystore(src_dir.path().join(keystore_name(i)), PASSWORD))
+        .collect::<Vec<_>>();
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        keystores
+            .iter()
+            .map(|keystore| format!("0x{}:{}\n", keystore.pubkey(), PASSWORD))
+            .collect::<String>(),
+    )
+    .unwrap();
+
+    // A file in the place of a keystore's staging directory cannot be removed, so the import of
+    // that keystore fails.
+    let import = |failing: &[usize]| {
+        let dst_dir = tempdir().unwrap();
+        let staging_dir = dst_dir.path().join(import::STAGING_DIR);
+        fs::create_dir_all(&staging_dir).unwrap();
+        for i in failing {
+            fs::write(staging_dir.join(keystores[*i].uuid().to_string()), "").unwrap();
+        }
+
+        let output = validator_import_cmd()
+            .arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str())
+            .arg(format!("--{}", import::JOURNAL_FLAG))
+            .arg(journal.as_os_str())
+            .output()
+            .unwrap();
+        let imported = ValidatorDefinitions::open(&dst_dir)
+            .map(|defs| {
+                defs.as_slice()
+                    .iter()
+                    .map(|def| def.voting_public_key.to_hex_string())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        (
+            output.status.code(),
+            from_utf8(&output.stderr).unwrap().to_string(),
+            imported,
+        )
+    };
+    let journal_lines = || {
+        fs::read_to_string(&journal)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect::<Vec<_>>()
+    };
+
+    // The import is interrupted by the failure, leaving only the first keystore in the journal.
+    let (code, stderr, imported) = import(&[1]);
+    assert_eq!(code, Some(1), "{}", stderr);
+    assert_eq!(imported, vec![format!("0x{}", keystores[0].pubkey())]);
+    let lines = journal_lines();
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].starts_with(&format!("{} ", keystores[0].pubkey())));
+
+    // Resuming the import skips the keystore in the journal, even into another validator
+    // directory.
+    let (code, stderr, imported) = import(&[]);
+    assert_eq!(code, Some(0), "{}", stderr);
+    assert_eq!(
+        imported,
+        vec![
+            format!("0x{}", keystores[1].pubkey()),
+            format!("0x{}", keystores[2].pubkey()),
+        ]
+    );
+    assert!(stderr.contains("already imported"), "{}", stderr);
+    assert_eq!(journal_lines().len(), 3);
+
+    // Every keystore is in the journal, so nothing is imported.
+    let (code, stderr, imported) = import(&[]);
+    assert_eq!(code, Some(0), "{}", stderr);
+    assert!(imported.is_empty());
+    assert_eq!(journal_lines().len(), 3);
+}
+
+#[test]
+fn validator_import_verify_only() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystores = (0..2)
+        .map(|i| write_keystore(src_dir.path().join(keystore_name(i)), PASSWORD))
+        .collect::<Vec<_>>();
+
+    let password_file = src_dir.path().join("passwords.txt");
+    let verify = |passwords: &[&str]| {
+        fs::write(
+            &password_file,
+            keystores
+                .iter()
+                .zip(passwords)
+                .map(|(keystore, password)| format!("0x{}:{}\n", keystore.pubkey(), password))
+                .collect::<String>(),
+        )
+        .unwrap();
+
+        validator_import_cmd()
+            .arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str())
+            .arg(format!("--{}", import::VERIFY_ONLY_FLAG))
+            .output()
+            .unwrap()
+    };
+
+    // Each keystore is reported, and the one with an incorrect password fails.
+    let output = verify(&[PASSWORD, "dogs"]);
+    let stderr = from_utf8(&output.stderr).unwrap();
+    assert!(!output.status.success(), "{}", stderr);
+    assert!(output.stdout.is_empty());
+    let lines = stderr.lines().collect::<Vec<_>>();
+    let line_for = |pubkey: &str| {
+        lines
+            .iter()
+            .find(|line| line.contains(&format!("pubkey: 0x{}", pubkey)))
+            .unwrap_or_else(|| panic!("{} should be reported: {}", pubkey, stderr))
+    };
+    assert!(
+        line_for(keystores[0].pubkey()).contains("Keystore verified"),
+        "{}",
+        stderr
+    );
+    assert!(
+        line_for(keystores[1].pubkey()).contains("Keystore failed verification")
+            && line_for(keystores[1].pubkey()).contains("Incorrect password"),
+        "{}",
+        stderr
+    );
+
+    let output = verify(&[PASSWORD, PASSWORD]);
+    let stderr = from_utf8(&output.stderr).unwrap();
+    assert!(output.status.success(), "{}", stderr);
+    assert_eq!(stderr.matches("Keystore verified").count(), 2, "{}", stderr);
+}
+
+#[test]
+fn validator_reharden() {
+    const PASSWORD: &str = "cats";
+    const KDF_COST: u32 = 1024;
+
+    let validator_dir = tempdir().unwrap();
+
+    let scrypt = |n| {
+        Kdf::Scrypt(Scrypt {
+            dklen: DKLEN,
+            n,
+            p: 1,
+            r: 8,
+            salt: vec![42; 32].into(),
+        })
+    };
+    let pbkdf2 = Kdf::Pbkdf2(Pbkdf2 {
+        dklen: DKLEN,
+        c: 16,
+        prf: Prf::HmacSha256,
+        salt: vec![42; 32].into(),
+    });
+
+    // The last keystore already has a cost above the target, so it is left unchanged.
+    let keystores = vec![scrypt(16), pbkdf2, scrypt(KDF_COST * 2)]
+        .into_iter()
+        .enumerate()
+        .map(|(i, kdf)| {
+            let keypair = Keypair::random();
+            let keystore = KeystoreBuilder::new(&keypair, PASSWORD.as_bytes(), "".into())
+                .unwrap()
+                .kdf(kdf)
+                .build()
+                .unwrap();
+            let keystore_path = validator_dir
+                .path()
+                .join(format!("voting-keystore-{}.json", i));
+            write_keystore_file(&keystore, &keystore_path);
+            (keypair, keystore, keystore_path)
+        })
+        .collect::<Vec<_>>();
+
+    // The second keystore has its password in a separate file.
+    let password_path = validator_dir.path().join("password.txt");
+    fs::write(&password_path, PASSWORD).unwrap();
+    let mut defs = ValidatorDefinitions::default();
+    for (i, (_, _, keystore_path)) in keystores.iter().enumerate() {
+        let mut def = ValidatorDefinition::new_keystore_with_password(
+            keystore_path,
+            Some(ZeroizeString::from(PASSWORD.to_string())).filter(|_| i != 1),
+        )
+        .unwrap();
+        if i == 1 {
+            match &mut def.signing_definition {
+                SigningDefinition::LocalKeystore {
+                    voting_keystore_password_path,
+                    ..
+                } => *voting_keystore_password_path = Some(password_path.clone()),
+            }
+        }
+        defs.push(def);
+    }
+    defs.save(&validator_dir).unwrap();
+    let defs_contents = fs::read(validator_dir.path().join(CONFIG_FILENAME)).unwrap();
+
+    let reharden = |dry_run: bool| {
+        let mut cmd = validator_cmd();
+        cmd.arg(REHARDEN_CMD)
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(validator_dir.path().as_os_str())
+            .arg(format!("--{}", reharden::KDF_COST_FLAG))
+            .arg(KDF_COST.to_string());
+        if dry_run {
+            cmd.arg(format!("--{}", reharden::DRY_RUN_FLAG));
+        }
+        let output = cmd.output().unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            from_utf8(&output.stderr).unwrap()
+        );
+        from_utf8(&output.stdout).unwrap().to_string()
+    };
+    let read_keystore = |i: usize| Keystore::from_json_file(&keystores[i].2).unwrap();
+
+    // A dry run lists the current and target parameters, without modifying the keystores.
+    let stdout = reharden(true);
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        vec![
+            format!(
+                "0x{}\tscrypt (n: 16, r: 8, p: 1)\tscrypt (n: {}, r: 8, p: 1)",
+                keystores[0].1.pubkey(),
+                KDF_COST
+            ),
+            format!(
+                "0x{}\tpbkdf2 (c: 16)\tscrypt (n: {}, r: 8, p: 1)",
+                keystores[1].1.pubkey(),
+                KDF_COST
+            ),
+            format!(
+                "0x{}\tscrypt (n: {}, r: 8, p: 1)\tunchanged",
+                keystores[2].1.pubkey(),
+                KDF_COST * 2
+            ),
+        ]
+    );
+    for (i, (_, keystore, _)) in keystores.iter().enumerate() {
+        assert_eq!(&read_keystore(i), keystore);
+    }
+
+    reharden(false);
+
+    for (i, (keypair, keystore, _)) in keystores.iter().enumerate() {
+        let rehardened = read_keystore(i);
+        assert_eq!(rehardened.uuid(), keystore.uuid());
+        assert_eq!(rehardened.pubkey(), keystore.pubkey());
+        assert_eq!(
+            rehardened.decrypt_keypair(PASSWORD.as_bytes()).unwrap().pk,
+            keypair.pk
+        );
+        if i == 2 {
+            assert_eq!(&rehardened, keystore);
+        } else {
+            match rehardened.kdf() {
+                Kdf::Scrypt(params) => {
+                    assert_eq!((params.n, params.r, params.p), (KDF_COST, 8, 1));
+                    assert_ne!(params.salt, vec![42; 32].into(), "the salt should be new");
+                }
+                kdf => panic!("unexpected kdf {:?}", kdf),
+            }
+        }
+    }
+
+    // The definitions are unchanged, since the keystores are replaced in place.
+    assert_eq!(
+        fs::read(validator_dir.path().join(CONFIG_FILENAME)).unwrap(),
+        defs_contents
+    );
+}
+
+#[test]
+fn validator_import_deposit_data() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystores = (0..3)
+        .map(|i| write_keystore(src_dir.path().join(keystore_name(i)), PASSWORD))
+        .collect::<Vec<_>>();
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        keystores
+            .iter()
+            .map(|keystore| format!("0x{}:{}\n", keystore.pubkey(), PASSWORD))
+            .collect::<String>(),
+    )
+    .unwrap();
+
+    // The deposit data has the first and last keystores, and a validator without a keystore.
+    let missing_pubkey = Keypair::random().pk.to_hex_string();
+    let deposit_data = src_dir.path().join("deposit_data-1595406747.json");
+    fs::write(
+        &deposit_data,
+        serde_json::to_string(
+            &[
+                keystores[0].pubkey(),
+                keystores[2].pubkey(),
+                missing_pubkey.trim_start_matches("0x"),
+            ]
+            .iter()
+            .map(|pubkey| {
+                serde_json::json!({
+                    "pubkey": pubkey,
+                    "withdrawal_credentials": "00".repeat(32),
+                    "amount": 32000000000u64,
+                })
+            })
+            .collect::<Vec<_>>(),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    let output = validator_import_cmd()
+        .arg(format!("--{}", import::DIR_FLAG))
+        .arg(src_dir.path().as_os_str())
+        .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(dst_dir.path().as_os_str())
+        .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+        .arg(password_file.as_os_str())
+        .arg(format!("--{}", import::DEPOSIT_DATA_FLAG))
+        .arg(deposit_data.as_os_str())
+        .output()
+        .unwrap();
+    let stderr = from_utf8(&output.stderr).unwrap();
+    assert!(output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains("No keystore found for the deposit data")
+            && stderr.contains(&missing_pubkey),
+        "{}",
+        stderr
+    );
+
+    let imported = ValidatorDefinitions::open(&dst_dir)
+        .unwrap()
+        .as_slice()
+        .iter()
+        .map(|def| def.voting_public_key.to_hex_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        imported,
+        vec![
+            format!("0x{}", keystores[0].pubkey()),
+            format!("0x{}", keystores[2].pubkey()),
+        ]
+    );
+}
+
+#[test]
+fn validator_enable_disable() {
+    let validator_dir = tempdir().unwrap();
+
+    let mut defs = ValidatorDefinitions::default();
+    let pubkeys = (0..2)
+        .map(|i| {
+            let keystore_path = validator_dir
+                .path()
+                .join(format!("voting-keystore-{}.json", i));
+            let keystore = write_keystore(&keystore_path, "cats");
+            defs.push(
+                ValidatorDefinition::new_keystore_with_password(&keystore_path, None).unwrap(),
+            );
+            format!("0x{}", keystore.pubkey())
+        })
+        .collect::<Vec<_>>();
+    defs.save(&validator_dir).unwrap();
+
+    let run = |cmd: &str, target: &[&str]| {
+        validator_cmd()
+            .arg(cmd)
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(validator_dir.path().as_os_str())
+            .args(target)
+            .output()
+            .unwrap()
+            .status
+            .success()
+    };
+    let enabled = || {
+        ValidatorDefinitions::open(&validator_dir)
+            .unwrap()
+            .as_slice()
+            .iter()
+            .map(|def| def.enabled)
+            .collect::<Vec<_>>()
+    };
+    let pubkey_flag = format!("--{}", enable::PUBKEY_FLAG);
+    let all_flag = format!("--{}", enable::ALL_FLAG);
+
+    assert!(run(DISABLE_CMD, &[&pubkey_flag, &pubkeys[1]]));
+    assert_eq!(enabled(), vec![true, false]);
+
+    assert!(
+        !run(DISABLE_CMD, &[&pubkey_flag, "0x1234"]),
+        "an unknown public key should be rejected"
+    );
+    assert_eq!(enabled(), vec![true, false]);
+
+    // Disabling a disabled validator leaves it disabled.
+    assert!(run(DISABLE_CMD, &[&all_flag]));
+    assert_eq!(enabled(), vec![false, false]);
+
+    // The public key is not case-sensitive, and the `0x` prefix is optional.
+    assert!(run(
+        ENABLE_CMD,
+        &[&pubkey_flag, &pubkeys[0][2..].to_uppercase()]
+    ));
+    assert_eq!(enabled(), vec![true, false]);
+
+    assert!(run(ENABLE_CMD, &[&all_flag]));
+    assert_eq!(enabled(), vec![true, true]);
+}
+
+#[test]
+fn validator_import_gpg_password_file() {
+    const PASSWORD: &str = "cats";
+    const PASSPHRASE: &str = "dogs";
+
+    let src_dir = tempdir().unwrap();
+    let gnupg_home = tempdir().unwrap();
+
+    let keystore_path = src_dir.path().join("keystore.json");
+    let keystore = write_keystore(&keystore_path, PASSWORD);
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        format!("0x{}:{}\n", keystore.pubkey(), PASSWORD),
+    )
+    .unwrap();
+    let encrypted_password_file = src_dir.path().join("passwords.txt.asc");
+    let encrypted = Command::new("gpg")
+        .env("GNUPGHOME", gnupg_home.path())
+        .args(&["--batch", "--pinentry-mode", "loopback", "--passphrase"])
+        .arg(PASSPHRASE)
+        .args(&["--symmetric", "--armor", "--output"])
+        .arg(encrypted_password_file.as_os_str())
+        .arg(password_file.as_os_str())
+        .status()
+        .map_or(false, |status| status.success());
+    if !encrypted {
+        eprintln!("Skipping test, gpg is not available");
+        return;
+    }
+    fs::remove_file(&password_file).unwrap();
+
+    // The only password read from stdin is the passphrase, since the password file is correct.
+    let import = |passphrase: &str| {
+        let dst_dir = tempdir().unwrap();
+        let mut child = validator_import_cmd()
+            .env("GNUPGHOME", gnupg_home.path())
+            .arg(format!("--{}", import::KEYSTORE_FLAG))
+            .arg(keystore_path.as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(encrypted_password_file.as_os_str())
+            .arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+            .stdin(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(format!("{}\n", passphrase).as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        let num_imported = ValidatorDefinitions::open(&dst_dir)
+            .map(|defs| defs.as_slice().len())
+            .unwrap_or(0);
+
+        (
+            output.status.success(),
+            from_utf8(&output.stderr).unwrap().to_string(),
+            num_imported,
+        )
+    };
+
+    let (success, stderr, num_imported) = import("hamsters");
+    assert!(!success, "{}", stderr);
+    assert!(stderr.contains("Unable to decrypt"), "{}", stderr);
+    assert_eq!(num_imported, 0);
+
+    let (success, stderr, num_imported) = import(PASSPHRASE);
+    assert!(success, "{}", stderr);
+    assert!(
+        stderr.contains(import::PASSWORD_FILE_PASSPHRASE_PROMPT),
+        "{}",
+        stderr
+    );
+    assert_eq!(num_imported, 1);
+}
+
+#[test]
+fn validator_import_dest_naming() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystores = (0..2)
+        .map(|i| write_keystore(src_dir.path().join(keystore_name(i)), PASSWORD))
+        .collect::<Vec<_>>();
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        keystores
+            .iter()
+            .map(|keystore| format!("0x{}:{}\n", keystore.pubkey(), PASSWORD))
+            .collect::<String>(),
+    )
+    .unwrap();
+
+    let import = || {
+        validator_import_cmd()
+            .arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str())
+            .arg(format!("--{}", import::DEST_NAMING_FLAG))
+            .arg(import::DEST_NAMING_UUID)
+            .output()
+            .unwrap()
+    };
+
+    // An existing directory with the name of a keystore UUID aborts the import before any files
+    // are modified.
+    let existing_dir = dst_dir.path().join(keystores[1].uuid().to_string());
+    fs::create_dir(&existing_dir).unwrap();
+    let output = import();
+    assert!(!output.status.success());
+    assert_eq!(
+        ValidatorDefinitions::open(&dst_dir)
+            .unwrap()
+            .as_slice()
+            .len(),
+        0
+    );
+    fs::remove_dir(&existing_dir).unwrap();
+
+    let output = import();
+    assert!(
+        output.status.success(),
+        "{}",
+        from_utf8(&output.stderr).unwrap()
+    );
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), 2);
+    for (i, (keystore, def)) in keystores.iter().zip(defs.as_slice()).enumerate() {
+        let expected_path = dst_dir
+            .path()
+            .join(keystore.uuid().to_string())
+            .join(keystore_name(i));
+        assert!(expected_path.exists());
+        assert!(!dst_dir
+            .path()
+            .join(format!("0x{}", keystore.pubkey()))
+            .exists());
+        match &def.signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                ..
+            } => assert_eq!(voting_keystore_path, &expected_path),
+        }
+    }
+}
+
+#[test]
+fn validator_import_manifest() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let manifest_dir = tempdir().unwrap();
+    let manifest = manifest_dir.path().join("manifest.json");
+
+    let keystores = (0..2)
+        .map(|i| write_keystore(src_dir.path().join(keystore_name(i)), PASSWORD))
+        .collect::<Vec<_>>();
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        keystores
+            .iter()
+            .map(|keystore| format!("0x{}:{}\n", keystore.pubkey(), PASSWORD))
+            .collect::<String>(),
+    )
+    .unwrap();
+
+    let output = validator_import_cmd()
+        .arg(format!("--{}", import::DIR_FLAG))
+        .arg(src_dir.path().as_os_str())
+        .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(dst_dir.path().as_os_str())
+        .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+        .arg(password_file.as_os_str())
+        .arg(format!("--{}", import::MANIFEST_FLAG))
+        .arg(manifest.as_os_str())
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        from_utf8(&output.stderr).unwrap()
+    );
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&manifest).unwrap()).unwrap();
+    assert_eq!(json["keystores"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        PathBuf::from(json["definitions"]["path"].as_str().unwrap()),
+        dst_dir.path().join(CONFIG_FILENAME)
+    );
+
+    let verify = || {
+        validator_cmd()
+            .arg(VERIFY_CMD)
+            .arg(format!("--{}", verify::MANIFEST_FLAG))
+            .arg(manifest.as_os_str())
+            .output()
+            .unwrap()
+    };
+
+    let output = verify();
+    assert!(
+        output.status.success(),
+        "{}",
+        from_utf8(&output.stderr).unwrap()
+    );
+    assert_eq!(from_utf8(&output.stdout).unwrap().lines().count(), 3);
+
+    // A modified keystore is reported, and the other files still verify.
+    let keystore_path = PathBuf::from(json["keystores"][0]["path"].as_str().unwrap());
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(&keystore_path)
+        .unwrap();
+    writeln!(file).unwrap();
+    drop(file);
+
+    let output = verify();
+    assert!(!output.status.success());
+    let stdout = from_utf8(&output.stdout).unwrap();
+    assert!(stdout.contains(&format!("FAILED\t{:?}", keystore_path)));
+    assert_eq!(stdout.matches("OK\t").count(), 2);
+}
+
+#[cfg(unix)]
+#[test]
+fn validator_import_symlinks() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+    let target_dir = tempdir().unwrap();
+
+    let target = target_dir
+        .path()
+        .join("keystore-m_12381_3600_0_0_0-1595406747.json");
+    let keystore = write_keystore(&target, PASSWORD);
+    let target_contents = fs::read(&target).unwrap();
+
+    let link = src_dir
+        .path()
+        .join("keystore-m_12381_3600_0_0_0-1595406747.json");
+    std::os::unix::fs::symlink(&target, &link).unwrap();
+
+    let password_file = target_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        format!("0x{}:{}\n", keystore.pubkey(), PASSWORD),
+    )
+    .unwrap();
+
+    let import = |validator_dir: &Path, args: &[&OsStr]| {
+        validator_import_cmd()
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(validator_dir.as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str())
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let imported_keystore = |validator_dir: &Path| {
+        validator_dir
+            .join(format!("0x{}", keystore.pubkey()))
+            .join("keystore-m_12381_3600_0_0_0-1595406747.json")
+    };
+    let dir_flag = format!("--{}", import::DIR_FLAG);
+    let follow_flag = format!("--{}", import::FOLLOW_SYMLINKS_FLAG);
+    let keystore_flag = format!("--{}", import::KEYSTORE_FLAG);
+
+    // Links are skipped whilst searching a directory by default.
+    let dst_dir = tempdir().unwrap();
+    let output = import(
+        dst_dir.path(),
+        &[dir_flag.as_ref(), src_dir.path().as_os_str()],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        from_utf8(&output.stderr).unwrap()
+    );
+    assert!(!imported_keystore(dst_dir.path()).exists());
+
+    // With `--follow-symlinks`, or when given as the `--keystore`, the target is copied.
+    for args in &[
+        vec![
+            dir_flag.as_ref(),
+            src_dir.path().as_os_str(),
+            follow_flag.as_ref(),
+        ],
+        vec![keystore_flag.as_ref(), link.as_os_str()],
+    ] {
+        let dst_dir = tempdir().unwrap();
+        let output = import(dst_dir.path(), args);
+        assert!(
+            output.status.success(),
+            "{}",
+            from_utf8(&output.stderr).unwrap()
+        );
+
+        let imported = imported_keystore(dst_dir.path());
+        assert!(!fs::symlink_metadata(&imported)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(fs::read(&imported).unwrap(), target_contents);
+        assert_eq!(fs::read_link(&link).unwrap(), target);
+        assert_eq!(fs::read(&target).unwrap(), target_contents);
+    }
+}
+
+#[test]
+fn validator_import_relative_paths() {
+    const PASSWORD: &str = "cats";
+    const KEYSTORE_NAME: &str = "keystore-m_12381_3600_0_0_0-1595406747.json";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let validator_dir = dst_dir.path().join("validators");
+
+    let keystore = write_keystore(src_dir.path().join(KEYSTORE_NAME), PASSWORD);
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        format!("0x{}:{}\n", keystore.pubkey(), PASSWORD),
+    )
+    .unwrap();
+
+    let output = validator_import_cmd()
+        .arg(format!("--{}", import::DIR_FLAG))
+        .arg(src_dir.path().as_os_str())
+        .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(validator_dir.as_os_str())
+        .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+        .arg(password_file.as_os_str())
+        .arg(format!("--{}", import::RELATIVE_PATHS_FLAG))
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        from_utf8(&output.stderr).unwrap()
+    );
+
+    let relative_path = PathBuf::from(format!("0x{}", keystore.pubkey())).join(KEYSTORE_NAME);
+    let yaml = fs::read_to_string(validator_dir.join(CONFIG_FILENAME)).unwrap();
+    assert!(yaml.contains(&format!(
+        "voting_keystore_path: \"{}\"",
+        relative_path.to_str().unwrap()
+    )));
+
+    // The validators are still found after the directory is moved.
+    let moved_dir = dst_dir.path().join("moved");
+    fs::rename(&validator_dir, &moved_dir).unwrap();
+
+    let output = validator_cmd()
+        .arg(VERIFY_CMD)
+        .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(moved_dir.as_os_str())
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        from_utf8(&output.stderr).unwrap()
+    );
+
+    let defs = ValidatorDefinitions::open(&moved_dir).unwrap();
+    match &defs.as_slice()[0].signing_definition {
+        SigningDefinition::LocalKeystore {
+            voting_keystore_path,
+            ..
+        } => assert_eq!(voting_keystore_path, &moved_dir.join(&relative_path)),
+    }
+}
+
+#[test]
+fn validator_import_max_keystore_size() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(
+        src_dir
+            .path()
+            .join("keystore-m_12381_3600_0_0_0-1595406747.json"),
+        PASSWORD,
+    );
+
+    // A file which matches the keystore file names but is too large to be a keystore.
+    let large_file = src_dir
+        .path()
+        .join("keystore-m_12381_3600_1_0_0-1595406747.json");
+    fs::write(&large_file, vec![0; 1 << 20]).unwrap();
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        format!("0x{}:{}\n", keystore.pubkey(), PASSWORD),
+    )
+    .unwrap();
+
+    let import = |max_keystore_size: Option<&str>| {
+        let mut cmd = validator_import_cmd();
+        cmd.arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str());
+        if let Some(max_keystore_size) = max_keystore_size {
+            cmd.arg(format!("--{}", import::MAX_KEYSTORE_SIZE_FLAG))
+                .arg(max_keystore_size);
+        }
+        cmd.output().unwrap()
+    };
+
+    // The large file is parsed, and rejected, when it is within the limit.
+    let output = import(Some(&(2 << 20).to_string()));
+    assert!(!output.status.success());
+    assert_eq!(
+        ValidatorDefinitions::open(&dst_dir)
+            .map(|defs| defs.as_slice().len())
+            .unwrap_or(0),
+        0
+    );
+
+    let output = import(None);
+    let stderr = from_utf8(&output.stderr).unwrap();
+    assert!(output.status.success(), "{}", stderr);
+    assert!(stderr.contains("Skipping keystore larger than"));
+    assert_eq!(
+        ValidatorDefinitions::open(&dst_dir)
+            .unwrap()
+            .as_slice()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn validator_import_max_password_attempts() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+
+    let keystores = (0..2)
+        .map(|i| write_keystore(src_dir.path().join(keystore_name(i)), PASSWORD))
+        .collect::<Vec<_>>();
+
+    let import = |stdin: &str, keep_going: bool| {
+        let dst_dir = tempdir().unwrap();
+        let mut cmd = validator_import_cmd();
+        cmd.arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+            .arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::MAX_PASSWORD_ATTEMPTS_FLAG))
+            .arg("2")
+            .stdin(Stdio::piped())
+            .stderr(Stdio::piped());
+        if keep_going {
+            cmd.arg(format!("--{}", import::KEEP_GOING_FLAG));
+        }
+        let mut child = cmd.spawn().unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stdin.as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        let imported = ValidatorDefinitions::open(&dst_dir)
+            .map(|defs| {
+                defs.as_slice()
+                    .iter()
+                    .map(|def| def.voting_public_key.to_hex_string())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        (
+            output.status.code(),
+            from_utf8(&output.stderr).unwrap().to_string(),
+            imported,
+        )
+    };
+
+    // The import is aborted after the second incorrect password.
+    let (code, stderr, imported) = import("dogs\ndogs\ncats\ncats\n", false);
+    assert_eq!(code, Some(1), "{}", stderr);
+    assert!(stderr.contains("2 incorrect passwords"), "{}", stderr);
+    assert!(imported.is_empty());
+
+    // With `--keep-going`, only the first keystore is skipped.
+    let (code, stderr, imported) = import("dogs\ndogs\ncats\n", true);
+    assert_eq!(code, Some(import::PARTIAL_FAILURE_EXIT_CODE), "{}", stderr);
+    assert_eq!(imported, vec![format!("0x{}", keystores[1].pubkey())]);
+
+    // Fewer incorrect passwords than the limit are retried.
+    let (code, stderr, imported) = import("dogs\ncats\ncats\n", false);
+    assert_eq!(code, Some(0), "{}", stderr);
+    assert_eq!(imported.len(), 2);
+}
+
+#[test]
+fn validator_import_keeps_definitions_comments() {
+    const PASSWORD: &str = "cats";
+    const COMMENT: &str = "# Funded from the cold wallet.";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystores = (0..2)
+        .map(|i| {
+            let dir = src_dir.path().join(i.to_string());
+            fs::create_dir(&dir).unwrap();
+            let path = dir.join(keystore_name(i));
+            let keystore = write_keystore(&path, PASSWORD);
+            (keystore, path)
+        })
+        .collect::<Vec<_>>();
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(
+        &password_file,
+        keystores
+            .iter()
+            .map(|(keystore, _)| format!("0x{}:{}\n", keystore.pubkey(), PASSWORD))
+            .collect::<String>(),
+    )
+    .unwrap();
+
+    let import = |keystore_path: &Path| {
+        let output = validator_import_cmd()
+            .arg(format!("--{}", import::KEYSTORE_FLAG))
+            .arg(keystore_path.as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str())
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            from_utf8(&output.stderr).unwrap()
+        );
+    };
+
+    // The operator annotates the first definition.
+    import(&keystores[0].1);
+    let defs_path = dst_dir.path().join(CONFIG_FILENAME);
+    let yaml = fs::read_to_string(&defs_path).unwrap();
+    let annotated = yaml.replacen("- enabled", &format!("{}\n- enabled", COMMENT), 1);
+    fs::write(&defs_path, &annotated).unwrap();
+
+    import(&keystores[1].1);
+    let yaml = fs::read_to_string(&defs_path).unwrap();
+    assert!(yaml.starts_with(&annotated), "{}", yaml);
+    assert_eq!(
+        ValidatorDefinitions::open(&dst_dir)
+            .unwrap()
+            .as_slice()
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn validator_import_password_with_spaces() {
+    const PASSWORD: &str = " cat s ";
+
+    let src_dir = tempdir().unwrap();
+    let keystore = write_keystore(
+        src_dir
+            .path()
+            .join("keystore-m_12381_3600_0_0_0-1595406747.json"),
+        PASSWORD,
+    );
+
+    let import = |stdin: &str| {
+        let dst_dir = tempdir().unwrap();
+        let mut child = validator_import_cmd()
+            .arg(format!("--{}", import::STDIN_PASSWORD_FLAG))
+            .arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::MAX_PASSWORD_ATTEMPTS_FLAG))
+            .arg("1")
+            .stdin(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stdin.as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        let passwords = ValidatorDefinitions::open(&dst_dir)
+            .map(|defs| {
+                defs.as_slice()
+                    .iter()
+                    .map(|def| match &def.signing_definition {
+                        SigningDefinition::LocalKeystore {
+                            voting_keystore_password,
+                            ..
+                        } => voting_keystore_password
+                            .as_ref()
+                            .map(|password| password.as_ref().to_vec()),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        (
+            output.status.code(),
+            from_utf8(&output.stderr).unwrap().to_string(),
+            passwords,
+        )
+    };
+
+    // Only the line ending is removed from a password read from stdin.
+    for stdin in &[" cat s \n", " cat s \r\n", " cat s "] {
+        let (code, stderr, passwords) = import(stdin);
+        assert_eq!(code, Some(0), "{:?}: {}", stdin, stderr);
+        assert_eq!(passwords, vec![Some(PASSWORD.as_bytes().to_vec())]);
+    }
+
+    // The spaces are not trimmed from the password.
+    for stdin in &["cat s\n", " cat s  \n"] {
+        let (code, stderr, passwords) = import(stdin);
+        assert_eq!(code, Some(1), "{:?}: {}", stdin, stderr);
+        assert!(passwords.is_empty());
+    }
+}
+
+#[test]
+fn validator_import_network() {
+    const PASSWORD: &str = "cats";
+
+    let src_dir = tempdir().unwrap();
+
+    let mut password_file_contents = String::new();
+    let mut keystores = vec![];
+    for (i, description) in [Some("Mainnet validator"), Some("goerli-validator"), None]
+        .iter()
+        .enumerate()
+    {
+        let keypair = Keypair::random();
+        let mut builder = KeystoreBuilder::new(&keypair, PASSWORD.as_bytes(), "".into()).unwrap();
+        if let Some(description) = description {
+            builder = builder.description(description.to_string());
+        }
+        let keystore = builder.build().unwrap();
+        let path = src_dir.path().join(keystore_name(i));
+        write_keystore_file(&keystore, &path);
+        password_file_contents.push_str(&format!("0x{}:{}\n", keystore.pubkey(), PASSWORD));
+        keystores.push(path);
+    }
+
+    let password_file = src_dir.path().join("passwords.txt");
+    fs::write(&password_file, password_file_contents).unwrap();
+
+    let import = |network: &str, strict: bool| {
+        let dst_dir = tempdir().unwrap();
+        let mut cmd = validator_import_cmd();
+        cmd.arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str())
+            .arg(format!("--{}", import::NETWORK_FLAG))
+            .arg(network);
+        if strict {
+            cmd.arg(format!("--{}", import::STRICT_NETWORK_FLAG));
+        }
+        let output = cmd.output().unwrap();
+        let num_imported = ValidatorDefinitions::open(&dst_dir)
+            .map(|defs| defs.as_slice().len())
+            .unwrap_or(0);
+
+        (
+            output.status.success(),
+            from_utf8(&output.stderr).unwrap().to_string(),
+            num_imported,
+        )
+    };
+
+    // The mainnet keystore is imported with a warning.
+    let (success, stderr, num_imported) = import("goerli", false);
+    assert!(success, "{}", stderr);
+    assert_eq!(stderr.matches("names another network").count(), 1);
+    assert!(
+        stderr.contains(&format!("{:?}", keystores[0])),
+        "{}",
+        stderr
+    );
+    assert_eq!(num_imported, 3);
+
+    // Only the keystores which name another network are rejected.
+    let refused = |network: &str| {
+        let (success, stderr, num_imported) = import(network, true);
+        assert!(!success);
+        assert_eq!(num_imported, 0);
+        stderr
+            .lines()
+            .find(|line| line.starts_with("Refusing to import keystores for a network"))
+            .unwrap_or_else(|| panic!("{}", stderr))
+            .to_string()
+    };
+
+    let line = refused("goerli");
+    assert!(line.contains("other than goerli"), "{}", line);
+    assert!(line.contains(&format!("{:?}", keystores[0])), "{}", line);
+    assert!(!line.contains(&format!("{:?}", keystores[1])), "{}", line);
+    assert!(!line.contains(&format!("{:?}", keystores[2])), "{}", line);
+
+    let line = refused("mainnet");
+    assert!(!line.contains(&format!("{:?}", keystores[0])), "{}", line);
+    assert!(line.contains(&format!("{:?}", keystores[1])), "{}", line);
+    assert!(!line.contains(&format!("{:?}", keystores[2])), "{}", line);
+}
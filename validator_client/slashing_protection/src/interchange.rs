@@ -0,0 +1,236 @@
+//! The EIP-3076 slashing protection interchange format, which allows slashing protection data to
+//! be moved between clients.
+//!
+//! Only the "complete" format (version 5) is supported.
+
+use crate::NotSafe;
+use serde_derive::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use types::{Epoch, Hash256, PublicKey, Slot};
+
+/// The `interchange_format_version` of the complete format from EIP-3076.
+pub const INTERCHANGE_FORMAT_VERSION: &str = "5";
+
+/// Errors that may occur whilst importing or exporting an interchange file.
+#[derive(Debug)]
+pub enum InterchangeError {
+    /// The interchange file has a version other than `INTERCHANGE_FORMAT_VERSION`.
+    UnsupportedVersion(String),
+    /// The interchange file is for a different network.
+    GenesisValidatorsRootMismatch {
+        interchange_file: Hash256,
+        client: Hash256,
+    },
+    /// A public key in the slashing protection database could not be parsed.
+    InvalidPubkey(String),
+    /// The slashing protection database could not be read or written.
+    NotSafe(NotSafe),
+}
+
+impl From<NotSafe> for InterchangeError {
+    fn from(error: NotSafe) -> Self {
+        InterchangeError::NotSafe(error)
+    }
+}
+
+impl From<rusqlite::Error> for InterchangeError {
+    fn from(error: rusqlite::Error) -> Self {
+        InterchangeError::NotSafe(error.into())
+    }
+}
+
+impl From<r2d2::Error> for InterchangeError {
+    fn from(error: r2d2::Error) -> Self {
+        InterchangeError::NotSafe(error.into())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InterchangeMetadata {
+    pub interchange_format_version: String,
+    pub genesis_validators_root: Hash256,
+}
+
+/// A block that has previously been signed by a validator.
+///
+/// The `signing_root` is optional, in which case the block must never be signed again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InterchangeBlock {
+    #[serde(with = "quoted_u64")]
+    pub slot: Slot,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_root: Option<Hash256>,
+}
+
+/// An attestation that has previously been signed by a validator.
+///
+/// The `signing_root` is optional, in which case the attestation must never be signed again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InterchangeAttestation {
+    #[serde(with = "quoted_u64")]
+    pub source_epoch: Epoch,
+    #[serde(with = "quoted_u64")]
+    pub target_epoch: Epoch,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_root: Option<Hash256>,
+}
+
+/// The signing history of a single validator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InterchangeData {
+    pub pubkey: PublicKey,
+    pub signed_blocks: Vec<InterchangeBlock>,
+    pub signed_attestations: Vec<InterchangeAttestation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Interchange {
+    pub metadata: InterchangeMetadata,
+    pub data: Vec<InterchangeData>,
+}
+
+impl Interchange {
+    pub fn from_json_reader<R: Read>(reader: R) -> Result<Self, serde_json::Error> {
+        serde_json::from_reader(reader)
+    }
+
+    pub fn to_json_writer<W: Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        serde_json::to_writer_pretty(writer, self)
+    }
+
+    /// Returns an error if `self` cannot be imported into a database for the network with
+    /// `genesis_validators_root`.
+    pub fn check_compatible(
+        &self,
+        genesis_validators_root: Hash256,
+    ) -> Result<(), InterchangeError> {
+        if self.metadata.interchange_format_version != INTERCHANGE_FORMAT_VERSION {
+            return Err(InterchangeError::UnsupportedVersion(
+                self.metadata.interchange_format_version.clone(),
+            ));
+        }
+
+        if self.metadata.genesis_validators_root != genesis_validators_root {
+            return Err(InterchangeError::GenesisValidatorsRootMismatch {
+                interchange_file: self.metadata.genesis_validators_root,
+                client: genesis_validators_root,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Serializes a `Slot` or `Epoch` as a decimal string, as required by EIP-3076.
+mod quoted_u64 {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Copy + Into<u64>,
+        S: Serializer,
+    {
+        serializer.serialize_str(&(*value).into().to_string())
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: From<u64>,
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse::<u64>()
+            .map(T::from)
+            .map_err(|e| D::Error::custom(format!("invalid integer {:?}: {}", s, e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::pubkey;
+
+    #[test]
+    fn json_roundtrip() {
+        let json = format!(
+            r#"{{
+                "metadata": {{
+                    "interchange_format_version": "5",
+                    "genesis_validators_root": "0x{}"
+                }},
+                "data": [
+                    {{
+                        "pubkey": "{}",
+                        "signed_blocks": [
+                            {{ "slot": "81952", "signing_root": "0x{}" }},
+                            {{ "slot": "81951" }}
+                        ],
+                        "signed_attestations": [
+                            {{ "source_epoch": "2290", "target_epoch": "3007" }}
+                        ]
+                    }}
+                ]
+            }}"#,
+            "04".repeat(32),
+            pubkey(0).to_hex_string(),
+            "4f".repeat(32),
+        );
+
+        let interchange = Interchange::from_json_reader(json.as_bytes()).unwrap();
+        assert_eq!(
+            interchange.data[0].signed_blocks,
+            vec![
+                InterchangeBlock {
+                    slot: Slot::new(81952),
+                    signing_root: Some(Hash256::repeat_byte(0x4f)),
+                },
+                InterchangeBlock {
+                    slot: Slot::new(81951),
+                    signing_root: None,
+                },
+            ]
+        );
+        assert_eq!(
+            interchange.data[0].signed_attestations[0].target_epoch,
+            Epoch::new(3007)
+        );
+
+        let mut bytes = vec![];
+        interchange.to_json_writer(&mut bytes).unwrap();
+        assert!(String::from_utf8(bytes.clone())
+            .unwrap()
+            .contains(r#""slot": "81952""#));
+        assert_eq!(
+            Interchange::from_json_reader(bytes.as_slice()).unwrap(),
+            interchange
+        );
+    }
+
+    #[test]
+    fn check_compatible() {
+        let root = Hash256::repeat_byte(1);
+        let mut interchange = Interchange {
+            metadata: InterchangeMetadata {
+                interchange_format_version: INTERCHANGE_FORMAT_VERSION.to_string(),
+                genesis_validators_root: root,
+            },
+            data: vec![],
+        };
+        assert!(interchange.check_compatible(root).is_ok());
+        assert!(matches!(
+            interchange.check_compatible(Hash256::repeat_byte(2)),
+            Err(InterchangeError::GenesisValidatorsRootMismatch { .. })
+        ));
+
+        interchange.metadata.interchange_format_version = "4".to_string();
+        assert!(matches!(
+            interchange.check_compatible(root),
+            Err(InterchangeError::UnsupportedVersion(_))
+        ));
+    }
+}
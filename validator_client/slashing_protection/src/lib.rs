@@ -1,5 +1,6 @@
 mod attestation_tests;
 mod block_tests;
+pub mod interchange;
 mod parallel_tests;
 mod signed_attestation;
 mod signed_block;
@@ -14,6 +15,9 @@ use std::io::{Error as IOError, ErrorKind};
 use std::string::ToString;
 use types::{Hash256, PublicKey};
 
+/// The file name of the slashing protection database within the validator directory.
+pub const SLASHING_PROTECTION_FILENAME: &str = "slashing_protection.sqlite";
+
 /// The attestation or block is not safe to sign.
 ///
 /// This could be because it's slashable, or because an error occurred.
@@ -1,3 +1,7 @@
+use crate::interchange::{
+    Interchange, InterchangeAttestation, InterchangeBlock, InterchangeData, InterchangeError,
+    InterchangeMetadata, INTERCHANGE_FORMAT_VERSION,
+};
 use crate::signed_attestation::InvalidAttestation;
 use crate::signed_block::InvalidBlock;
 use crate::{NotSafe, Safe, SignedAttestation, SignedBlock};
@@ -170,6 +174,23 @@ impl SlashingDatabase {
         .ok_or_else(|| NotSafe::UnregisteredValidator(public_key.clone()))
     }
 
+    /// Get the database-internal ID for a validator, registering it if it is unknown.
+    fn get_or_register_validator_id(
+        txn: &Transaction,
+        public_key: &PublicKey,
+    ) -> Result<i64, NotSafe> {
+        match Self::get_validator_id(txn, public_key) {
+            Err(NotSafe::UnregisteredValidator(_)) => {
+                txn.execute(
+                    "INSERT INTO validators (public_key) VALUES (?1)",
+                    params![&public_key.to_hex_string()],
+                )?;
+                Ok(txn.last_insert_rowid())
+            }
+            result => result,
+        }
+    }
+
     /// Check a block proposal from `validator_pubkey` for slash safety.
     fn check_block_proposal(
         &self,
@@ -403,13 +424,148 @@ impl SlashingDatabase {
         txn.commit()?;
         Ok(safe)
     }
+
+    /// Import the signing history from an EIP-3076 `interchange`, which must be for the network
+    /// with `genesis_validators_root`.
+    ///
+    /// Unknown validators are registered, including those without any history. A record which has
+    /// the same slot (or target epoch) as an existing record is ignored, keeping the existing
+    /// record. A missing signing root is stored as the zero hash, so that the block or attestation
+    /// will never be signed again.
+    pub fn import_interchange_info(
+        &self,
+        interchange: &Interchange,
+        genesis_validators_root: Hash256,
+    ) -> Result<(), InterchangeError> {
+        interchange.check_compatible(genesis_validators_root)?;
+
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
+
+        for record in &interchange.data {
+            let validator_id = Self::get_or_register_validator_id(&txn, &record.pubkey)?;
+
+            for block in &record.signed_blocks {
+                txn.execute(
+                    "INSERT OR IGNORE INTO signed_blocks (validator_id, slot, signing_root)
+                     VALUES (?1, ?2, ?3)",
+                    params![
+                        validator_id,
+                        block.slot,
+                        block.signing_root.unwrap_or_else(Hash256::zero).as_bytes()
+                    ],
+                )?;
+            }
+
+            for attestation in &record.signed_attestations {
+                txn.execute(
+                    "INSERT OR IGNORE INTO signed_attestations
+                     (validator_id, source_epoch, target_epoch, signing_root)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![
+                        validator_id,
+                        attestation.source_epoch,
+                        attestation.target_epoch,
+                        attestation
+                            .signing_root
+                            .unwrap_or_else(Hash256::zero)
+                            .as_bytes()
+                    ],
+                )?;
+            }
+        }
+
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Export the complete signing history of every registered validator as an EIP-3076
+    /// interchange for the network with `genesis_validators_root`.
+    ///
+    /// A zero signing root (e.g., from an interchange without signing roots) is exported as a
+    /// missing signing root.
+    pub fn export_interchange_info(
+        &self,
+        genesis_validators_root: Hash256,
+    ) -> Result<Interchange, InterchangeError> {
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction()?;
+
+        let validators = txn
+            .prepare("SELECT id, public_key FROM validators ORDER BY id ASC")?
+            .query_map(params![], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let signing_root = |signing_root: Hash256| {
+            if signing_root.is_zero() {
+                None
+            } else {
+                Some(signing_root)
+            }
+        };
+
+        let mut data = Vec::with_capacity(validators.len());
+        for (validator_id, public_key) in validators {
+            let pubkey = hex::decode(public_key.trim_start_matches("0x"))
+                .ok()
+                .and_then(|bytes| PublicKey::deserialize(&bytes).ok())
+                .ok_or_else(|| InterchangeError::InvalidPubkey(public_key.clone()))?;
+
+            let signed_blocks = txn
+                .prepare(
+                    "SELECT slot, signing_root FROM signed_blocks
+                     WHERE validator_id = ?1 ORDER BY slot ASC",
+                )?
+                .query_map(params![validator_id], SignedBlock::from_row)?
+                .map(|block| {
+                    block.map(|block| InterchangeBlock {
+                        slot: block.slot,
+                        signing_root: signing_root(block.signing_root),
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let signed_attestations = txn
+                .prepare(
+                    "SELECT source_epoch, target_epoch, signing_root FROM signed_attestations
+                     WHERE validator_id = ?1 ORDER BY target_epoch ASC",
+                )?
+                .query_map(params![validator_id], SignedAttestation::from_row)?
+                .map(|attestation| {
+                    attestation.map(|attestation| InterchangeAttestation {
+                        source_epoch: attestation.source_epoch,
+                        target_epoch: attestation.target_epoch,
+                        signing_root: signing_root(attestation.signing_root),
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            data.push(InterchangeData {
+                pubkey,
+                signed_blocks,
+                signed_attestations,
+            });
+        }
+
+        Ok(Interchange {
+            metadata: InterchangeMetadata {
+                interchange_format_version: INTERCHANGE_FORMAT_VERSION.to_string(),
+                genesis_validators_root,
+            },
+            data,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::test_utils::pubkey;
+    use crate::{InvalidAttestation, InvalidBlock};
     use tempfile::tempdir;
+    use types::{BeaconBlockHeader, Checkpoint, Epoch, Slot};
 
     #[test]
     fn open_non_existent_error() {
@@ -468,4 +624,109 @@ mod tests {
         let db2 = SlashingDatabase::open(&file).unwrap();
         check(&db2);
     }
+
+    fn interchange(genesis_validators_root: Hash256) -> Interchange {
+        Interchange {
+            metadata: InterchangeMetadata {
+                interchange_format_version: INTERCHANGE_FORMAT_VERSION.to_string(),
+                genesis_validators_root,
+            },
+            data: vec![
+                InterchangeData {
+                    pubkey: pubkey(0),
+                    signed_blocks: vec![InterchangeBlock {
+                        slot: Slot::new(10),
+                        signing_root: None,
+                    }],
+                    signed_attestations: vec![InterchangeAttestation {
+                        source_epoch: Epoch::new(2),
+                        target_epoch: Epoch::new(3),
+                        signing_root: Some(Hash256::repeat_byte(2)),
+                    }],
+                },
+                InterchangeData {
+                    pubkey: pubkey(1),
+                    signed_blocks: vec![],
+                    signed_attestations: vec![],
+                },
+            ],
+        }
+    }
+
+    // Imported history should register the validators and prevent conflicting signatures.
+    #[test]
+    fn import_interchange_info() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let db = SlashingDatabase::create(&file).unwrap();
+        let genesis_validators_root = Hash256::repeat_byte(1);
+
+        let interchange = interchange(genesis_validators_root);
+
+        assert!(matches!(
+            db.import_interchange_info(&interchange, Hash256::repeat_byte(2)),
+            Err(InterchangeError::GenesisValidatorsRootMismatch { .. })
+        ));
+        db.import_interchange_info(&interchange, genesis_validators_root)
+            .unwrap();
+        // Importing the same history twice is harmless.
+        db.import_interchange_info(&interchange, genesis_validators_root)
+            .unwrap();
+
+        let block = BeaconBlockHeader {
+            slot: Slot::new(10),
+            proposer_index: 0,
+            parent_root: Hash256::zero(),
+            state_root: Hash256::zero(),
+            body_root: Hash256::zero(),
+        };
+        assert!(matches!(
+            db.check_and_insert_block_proposal(&pubkey(0), &block, Hash256::zero()),
+            Err(NotSafe::InvalidBlock(InvalidBlock::DoubleBlockProposal(_)))
+        ));
+
+        let attestation = AttestationData {
+            slot: Slot::new(0),
+            index: 0,
+            beacon_block_root: Hash256::zero(),
+            source: Checkpoint {
+                epoch: Epoch::new(2),
+                root: Hash256::zero(),
+            },
+            target: Checkpoint {
+                epoch: Epoch::new(3),
+                root: Hash256::zero(),
+            },
+        };
+        assert!(matches!(
+            db.check_and_insert_attestation(&pubkey(0), &attestation, Hash256::zero()),
+            Err(NotSafe::InvalidAttestation(InvalidAttestation::DoubleVote(
+                _
+            )))
+        ));
+
+        // The validator without history was registered.
+        assert_eq!(
+            db.check_and_insert_block_proposal(&pubkey(1), &block, Hash256::zero())
+                .unwrap(),
+            Safe::Valid
+        );
+    }
+
+    // Exporting should return the imported history, including the validator without history.
+    #[test]
+    fn export_interchange_info() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let db = SlashingDatabase::create(&file).unwrap();
+        let genesis_validators_root = Hash256::repeat_byte(1);
+
+        let interchange = interchange(genesis_validators_root);
+        db.import_interchange_info(&interchange, genesis_validators_root)
+            .unwrap();
+        assert_eq!(
+            db.export_interchange_info(genesis_validators_root).unwrap(),
+            interchange
+        );
+    }
 }
@@ -9,7 +9,8 @@
 use account_utils::{
     read_password, read_password_from_user,
     validator_definitions::{
-        self, SigningDefinition, ValidatorDefinition, ValidatorDefinitions, CONFIG_FILENAME,
+        self, DefinitionsLock, SigningDefinition, ValidatorDefinition, ValidatorDefinitions,
+        CONFIG_FILENAME,
     },
 };
 use eth2_keystore::Keystore;
@@ -269,6 +270,9 @@ pub struct InitializedValidators {
     strict_lockfiles: bool,
     /// A list of validator definitions which can be stored on-disk.
     definitions: ValidatorDefinitions,
+    /// Held so that account manager commands cannot modify the definitions file whilst
+    /// `self.definitions` may be saved over it.
+    _definitions_lock: DefinitionsLock,
     /// The directory that the `self.definitions` will be saved into.
     validators_dir: PathBuf,
     /// The canonical set of validators.
@@ -279,8 +283,12 @@ pub struct InitializedValidators {
 
 impl InitializedValidators {
     /// Instantiates `Self`, initializing all validators in `definitions`.
+    ///
+    /// The `definitions_lock` must be the lock on `validators_dir`, it is held until `self` is
+    /// dropped.
     pub fn from_definitions(
         definitions: ValidatorDefinitions,
+        definitions_lock: DefinitionsLock,
         validators_dir: PathBuf,
         strict_lockfiles: bool,
         log: Logger,
@@ -289,6 +297,7 @@ impl InitializedValidators {
             strict_lockfiles,
             validators_dir,
             definitions,
+            _definitions_lock: definitions_lock,
             validators: HashMap::default(),
             log,
         };
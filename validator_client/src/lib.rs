@@ -12,7 +12,7 @@ mod validator_store;
 pub use cli::cli_app;
 pub use config::Config;
 
-use account_utils::validator_definitions::ValidatorDefinitions;
+use account_utils::validator_definitions::{DefinitionsLock, ValidatorDefinitions, LOCKFILE_NAME};
 use attestation_service::{AttestationService, AttestationServiceBuilder};
 use block_service::{BlockService, BlockServiceBuilder};
 use clap::ArgMatches;
@@ -70,6 +70,20 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             "datadir" => format!("{:?}", config.data_dir),
         );
 
+        // Held for the lifetime of the validator client, so that account manager commands cannot
+        // modify the definitions file whilst it may be saved here.
+        let lockfile = config.data_dir.join(LOCKFILE_NAME);
+        let definitions_lock = DefinitionsLock::try_acquire(&config.data_dir)
+            .map_err(|e| format!("Unable to lock {:?}: {:?}", lockfile, e))?
+            .ok_or_else(|| {
+                format!(
+                    "The validator directory is locked by another process holding {:?}. Wait \
+                     for the account manager command or validator client to exit, then try \
+                     again.",
+                    lockfile
+                )
+            })?;
+
         let mut validator_defs = ValidatorDefinitions::open_or_create(&config.data_dir)
             .map_err(|e| format!("Unable to open or create validator definitions: {:?}", e))?;
 
@@ -89,6 +103,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
         let validators = InitializedValidators::from_definitions(
             validator_defs,
+            definitions_lock,
             config.data_dir.clone(),
             config.strict_lockfiles,
             log.clone(),